@@ -1,15 +1,119 @@
 mod hir;
-mod lift_types;
 mod type_lifting;
 mod type_infer;
 mod type_extract;
 mod type_check;
-mod type_infer;
-mod type_check;
+mod mutability;
+mod composition;
+mod resolve;
+
+pub use crate::analysis::resolve::ShadowingLevel;
 
+use crate::analysis::composition::CompositionChecker;
+use crate::analysis::hir::ast_lower::{AstLowering, LoweringCtx};
 use crate::analysis::hir::Hir;
+use crate::analysis::mutability::MutabilityChecker;
+use crate::analysis::resolve::{NameResolver, Resolution};
+use crate::analysis::type_check::TypeChecker;
+use crate::analysis::type_lifting::TypeLifter;
+use crate::error::source::{Severity, SourceError};
 use crate::frontend::ast::Ast;
+use crate::frontend::ast::visitor::{AstVisitor, AstVisitorMut};
+use crate::symtab::SymbolTable;
+
+/// bind every name in the program to the symbol it refers to. Reports undefined names, names
+/// declared twice, and shadowing in the same scope as configured. Gives the warnings found along
+/// with the resolution, or every error and warning if there were errors
+pub fn resolve_names(ast: Ast, shadowing: ShadowingLevel) -> Result<(Resolution, Vec<SourceError>), Vec<SourceError>> {
+    let mut resolver = NameResolver::new(shadowing);
+    let _ = resolver.visit(ast);
+    let (resolution, mut diagnostics) = resolver.into_resolution();
+    diagnostics.sort_by_key(|diagnostic| diagnostic.err_loc().start);
+    if diagnostics.iter().any(|diagnostic| diagnostic.severity() == Severity::Error) {
+        Err(diagnostics)
+    } else {
+        Ok((resolution, diagnostics))
+    }
+}
+
+/// lower the program to HIR and check its types. Names are lowered with the symbols the
+/// resolution bound them to. Lowering and type checking both carry on past errors, so every error
+/// found by either is reported, in the order they appear in the source
+pub fn analyze_ast(ast: Ast, resolution: &Resolution) -> Result<Hir, Vec<SourceError>> {
+    let symtab = TypeLifter::new().visit(ast.clone(), SymbolTable::new())
+        .expect("Lifting types never fails");
+
+    let lowering = AstLowering::new(&symtab, resolution);
+    let lowered = lowering.visit(ast, LoweringCtx::default());
+    let mut errors = lowering.into_errors();
+    let mut hir = match lowered {
+        Ok(hir) => hir,
+        Err(err) => {
+            errors.push(err);
+            return Err(errors);
+        }
+    };
+
+    let mut checker = TypeChecker::new(symtab);
+    checker.check(&mut hir);
+    errors.extend(checker.into_errors());
+    if errors.is_empty() {
+        return Ok(hir);
+    }
+
+    errors.sort_by_key(|err| err.err_loc().start);
+    Err(errors)
+}
+
+/// parse a program and run every analysis on it in the order the compiler does, stopping at the
+/// first analysis that finds errors
+#[cfg(test)]
+pub(crate) fn analyze_source(source: &str) -> Result<Hir, Vec<SourceError>> {
+    use crate::frontend::input::SourceInput;
+    use crate::frontend::parse_input_source;
+
+    let input = SourceInput::raw(source);
+    let ast = parse_input_source(&input)?;
+    let (resolution, _) = resolve_names(ast.clone(), ShadowingLevel::default())?;
+    check_compositions(ast.clone())?;
+    check_mutability(ast.clone())?;
+    analyze_ast(ast, &resolution)
+}
+
+/// the messages of the errors found when analyzing a program, which should have some
+#[cfg(test)]
+pub(crate) fn error_messages(source: &str) -> Vec<String> {
+    analyze_source(source)
+        .err().expect("Analysis should find errors")
+        .iter()
+        .map(|err| err.msg().to_string())
+        .collect()
+}
+
+/// report every assignment to a binding that cannot be reassigned
+pub fn check_mutability(ast: Ast) -> Result<(), Vec<SourceError>> {
+    let mut checker = MutabilityChecker::new();
+    let _ = checker.visit(ast);
+    let errors = checker.into_errors();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// report composed types that aren't objects, composition cycles and clashing composed fields
+pub fn check_compositions(ast: Ast) -> Result<(), Vec<SourceError>> {
+    let Ok(symtab) = TypeLifter::new().visit(ast.clone(), SymbolTable::new()) else {
+        return Ok(());
+    };
 
-pub fn analyze_ast(ast: Ast) -> Hir {
-    todo!()
+    let mut checker = CompositionChecker::new(&symtab);
+    checker.check(ast);
+    let errors = checker.into_errors();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
 }
@@ -0,0 +1,152 @@
+use std::collections::{HashMap, HashSet};
+use crate::error::source::SourceError;
+use crate::frontend::ast::{Ast, CompositionSpecNode, ObjectDeclarationNode};
+use crate::frontend::location::{HasLocation, SourceRange};
+use crate::symtab::SymbolTable;
+use crate::types::{ObjectType, Type};
+
+#[cfg(test)]
+mod test;
+
+/// Checks the `composes` clauses of object declarations. Composed types have to be objects, an
+/// object can never end up composing itself, and the fields that composition makes available on
+/// an object can't clash with each other
+pub struct CompositionChecker<'symtab> {
+    /// the global symbols, which include every declared object
+    symtab: &'symtab SymbolTable,
+    /// errors collected along the way
+    errors: Vec<SourceError>,
+    /// the objects on a composition cycle that was already reported, so each cycle is reported
+    /// once rather than once for every object on it
+    cyclic: HashSet<String>,
+}
+
+impl<'symtab> CompositionChecker<'symtab> {
+    pub fn new(symtab: &'symtab SymbolTable) -> Self {
+        Self {
+            symtab,
+            errors: Vec::new(),
+            cyclic: HashSet::new(),
+        }
+    }
+
+    pub fn into_errors(self) -> Vec<SourceError> {
+        self.errors
+    }
+
+    pub fn check(&mut self, ast: Ast) {
+        let Ast::CompilationUnit(unit) = ast else {
+            return;
+        };
+
+        for decl in unit.declarations {
+            if let Ast::ObjectDeclaration(decl) = decl {
+                self.check_object(decl);
+            }
+        }
+    }
+
+    fn check_object(&mut self, node: ObjectDeclarationNode) {
+        let name = node.name.into_ident();
+        let Some(object) = self.symtab.lookup_object(&name.ident) else {
+            return;
+        };
+
+        let mut is_cyclic = false;
+        for spec in node.composition_specs {
+            let Ast::CompositionSpec(spec) = spec else {
+                panic!("Object declaration has a non-composition node as a composition spec")
+            };
+
+            is_cyclic |= !self.check_composition_spec(object, spec);
+        }
+
+        // the fields of a cyclic composition can't be collected, and the cycle is the real problem
+        if !is_cyclic {
+            self.check_field_conflicts(object, name.location);
+        }
+    }
+
+    /// check a single composed type. Returns false if the composition is part of a cycle
+    fn check_composition_spec(&mut self, object: &ObjectType, spec: CompositionSpecNode) -> bool {
+        let loc = spec.composed_type.source_range();
+        let composed_name = match *spec.composed_type {
+            Ast::TypeSpec(spec) => match spec.tp {
+                Type::UserDefined(name) => name,
+                other => {
+                    let msg = format!("Cannot compose type '{}'. Only objects can be composed", other);
+                    self.errors.push(SourceError::new(msg, loc));
+                    return true;
+                }
+            },
+            _ => return true,
+        };
+
+        let Some(composed) = self.symtab.lookup_object(&composed_name) else {
+            let msg = format!("Cannot compose '{}', which is not an object type", composed_name);
+            self.errors.push(SourceError::new(msg, loc));
+            return true;
+        };
+
+        let Some(mut cycle) = self.composition_path(composed, &object.name, &mut Vec::new()) else {
+            return true;
+        };
+
+        if self.cyclic.contains(&object.name) {
+            return false;
+        }
+
+        cycle.insert(0, object.name.as_str());
+        self.cyclic.extend(cycle.iter().map(|name| name.to_string()));
+        let msg = format!("Composition cycle: {}", cycle.join(" -> "));
+        self.errors.push(SourceError::new(msg, loc));
+        false
+    }
+
+    /// find the chain of compositions leading from the given object to the target object
+    fn composition_path<'a>(&self, from: &'a ObjectType, target: &str, visited: &mut Vec<&'a str>) -> Option<Vec<&'a str>>
+    where
+        'symtab: 'a
+    {
+        if from.name == target {
+            return Some(vec![from.name.as_str()]);
+        }
+
+        if visited.contains(&from.name.as_str()) {
+            return None;
+        }
+        visited.push(&from.name);
+
+        from.comps.values()
+            .filter_map(|composed| self.symtab.lookup_object(&composed.name))
+            .find_map(|composed| self.composition_path(composed, target, visited))
+            .map(|mut path| {
+                path.insert(0, from.name.as_str());
+                path
+            })
+    }
+
+    /// every field on an object must come from exactly one place
+    fn check_field_conflicts(&mut self, object: &ObjectType, loc: SourceRange) {
+        let lookup = |name: &str| self.symtab.lookup_object(name);
+        let mut origins = HashMap::<&str, &str>::new();
+        let mut conflicts = Vec::<String>::new();
+        for (field, origin) in object.field_names(&lookup) {
+            match origins.get(field) {
+                Some(first_origin) if *first_origin != origin => {
+                    let (first, second) = if *first_origin < origin { (*first_origin, origin) } else { (origin, *first_origin) };
+                    conflicts.push(format!("Field '{}' on '{}' is declared on both '{}' and '{}'", field, object.name, first, second));
+                }
+                Some(_) => {}
+                None => {
+                    origins.insert(field, origin);
+                }
+            }
+        }
+
+        conflicts.sort();
+        for conflict in conflicts {
+            self.errors.push(SourceError::new(conflict, loc));
+        }
+    }
+}
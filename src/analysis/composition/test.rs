@@ -0,0 +1,49 @@
+use crate::analysis::{analyze_source, error_messages};
+
+#[test]
+fn compose_objects() {
+    let result = analyze_source("object Person { name: str; } object Grades { gpa: double; } \
+        object Student composes Person, Grades as grades { id: uint; } \
+        fun name_of(s: Student): str { return s.name; } fun gpa_of(s: Student): double { return s.grades.gpa; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn compose_non_object() {
+    let errors = error_messages("enum Color { Red } object Pen composes Color { }");
+    assert_eq!(errors, ["Cannot compose 'Color', which is not an object type"]);
+
+    let errors = error_messages("object Pen composes int { }");
+    assert_eq!(errors, ["Cannot compose type 'int'. Only objects can be composed"]);
+}
+
+#[test]
+fn compose_cycle() {
+    let errors = error_messages("object A composes B { } object B composes A { }");
+    assert_eq!(errors, ["Composition cycle: A -> B -> A"]);
+
+    let errors = error_messages("object A composes A { }");
+    assert_eq!(errors, ["Composition cycle: A -> A"]);
+}
+
+#[test]
+fn compose_cycle_reported_once() {
+    let errors = error_messages("object A composes B { } object B composes C { } object C composes A { } object D composes A { }");
+    assert_eq!(errors, ["Composition cycle: A -> B -> C -> A"]);
+}
+
+#[test]
+fn compose_conflicting_fields() {
+    let errors = error_messages("object Person { name: str; } object Pet { name: str; } object Owner composes Person, Pet { }");
+    assert_eq!(errors, ["Field 'name' on 'Owner' is declared on both 'Person' and 'Pet'"]);
+
+    let errors = error_messages("object Person { name: str; } object Student composes Person { name: str; }");
+    assert_eq!(errors, ["Field 'name' on 'Student' is declared on both 'Person' and 'Student'"]);
+}
+
+#[test]
+fn compose_aliased_parts_with_same_fields() {
+    let result = analyze_source("object Person { name: str; } object Pet { name: str; } \
+        object Owner composes Person, Pet as pet { } fun pet_name(o: Owner): str { return o.pet.name; }");
+    assert!(result.is_ok());
+}
@@ -1,42 +1,22 @@
-mod ast_lower;
+pub(crate) mod ast_lower;
+pub(crate) mod exhaustiveness;
 
 use crate::frontend::location::{HasLocation, SourceRange};
 use crate::literal::Literal;
 use crate::operators::{BinaryOp, UnaryOp};
-use crate::types::{FunParam, ObjectType, Type};
+use crate::symtab::SymbolId;
+use crate::types::{EnumType, FunParam, ObjectType, Type};
 
 pub struct HirNode<InnerT> {
     /// inner node-specific data
-    inner: InnerT,
+    pub(crate) inner: InnerT,
     /// the type given to this HIR node
-    ty: Type,
+    pub(crate) ty: Type,
     /// where in source this node is located
-    loc: SourceRange,
+    pub(crate) loc: SourceRange,
 }
 
 impl<InnerT> HirNode<InnerT> {
-    pub fn new_inner(inner: InnerT) -> Self {
-        Self {
-            inner,
-            ty: Type::Unknown,
-            loc: SourceRange::default(),
-        }
-    }
-
-    pub fn with_type(mut self, tp: Type) -> Self {
-        self.ty = tp;
-        self
-    }
-
-    pub fn with_location(mut self, loc: SourceRange) -> Self {
-        self.loc = loc;
-        self
-    }
-
-    pub fn inner(&self) -> &InnerT {
-        &self.inner
-    }
-
     pub fn into_inner(self) -> InnerT {
         self.inner
     }
@@ -44,86 +24,203 @@ impl<InnerT> HirNode<InnerT> {
 
 pub struct CompilationUnitHIR {
     /// the functions declared in this compilation unit
-    functions: Vec<HirNode<FunctionDeclarationHIR>>,
-    /// the objects we defined
-    objects: Vec<ObjectType>,
+    pub(crate) functions: Vec<HirNode<FunctionDeclarationHIR>>,
+    /// the objects we defined. Analysis itself finds types through the symbol table, so these
+    /// are only read by the stages after it
+    #[allow(dead_code)]
+    pub(crate) objects: Vec<ObjectType>,
+    /// the enums we defined
+    #[allow(dead_code)]
+    pub(crate) enums: Vec<EnumType>,
 }
 
 pub struct FunctionDeclarationHIR {
     /// the name of this given function
-    name: String,
+    pub(crate) name: String,
     /// the parameters for this function
-    params: Vec<FunParam>,
+    pub(crate) params: Vec<FunParam>,
     /// the body for this function
-    body: Vec<Hir>,
+    pub(crate) body: Vec<Hir>,
 }
 
 pub struct VariableDeclarationHIR {
     /// name of this variable
-    name: String,
+    pub(crate) name: String,
+    /// the symbol this declares. Temporaries made up while lowering have none
+    pub(crate) symbol: Option<SymbolId>,
     /// the initializer for this variable
-    initializer: Box<Hir>,
+    pub(crate) initializer: Box<Hir>,
+}
+
+pub struct IdentifierHIR {
+    /// the name used
+    pub(crate) name: String,
+    /// the symbol the name was resolved to. Temporaries made up while lowering have none
+    pub(crate) symbol: Option<SymbolId>,
 }
 
 pub struct BlockHIR {
     /// the instructions in a block
-    insts: Vec<Hir>
+    pub(crate) insts: Vec<Hir>
 }
 
 pub struct AssignmentHIR {
     /// assignment lhs
-    lhs: Box<Hir>,
+    pub(crate) lhs: Box<Hir>,
     /// assignment rhs
-    rhs: Box<Hir>,
+    pub(crate) rhs: Box<Hir>,
 }
 
 pub struct UnaryOpHIR {
-    op: UnaryOp,
-    child: Box<Hir>,
+    pub(crate) op: UnaryOp,
+    pub(crate) child: Box<Hir>,
 }
 
 pub struct BinaryOpHIR {
-    op: BinaryOp,
-    lhs: Box<Hir>,
-    rhs: Box<Hir>,
+    pub(crate) op: BinaryOp,
+    pub(crate) lhs: Box<Hir>,
+    pub(crate) rhs: Box<Hir>,
 }
 
 pub struct ConditionHIR {
     /// condition expression
-    cond: Box<Hir>,
-    true_branch: Vec<Hir>,
-    false_branch: Vec<Hir>,
+    pub(crate) cond: Box<Hir>,
+    pub(crate) true_branch: Vec<Hir>,
+    /// empty when the conditional has no else
+    pub(crate) false_branch: Vec<Hir>,
 }
 
 pub struct LoopHIR {
     /// the statements to be executed in the loop
-    stmts: Vec<Hir>,
+    pub(crate) stmts: Vec<Hir>,
 }
 
 pub struct FunCallHIR {
     /// the expression being called
-    callee: Box<Hir>,
+    pub(crate) callee: Box<Hir>,
     /// the arguments with the call
-    args: Vec<Hir>,
+    pub(crate) args: Vec<Hir>,
+}
+
+pub struct FieldAccessHIR {
+    /// the expression whose field is accessed
+    pub(crate) object: Box<Hir>,
+    /// the name of the field
+    pub(crate) field: String,
+    /// if the access evaluates to null instead when the object is null
+    pub(crate) chained: bool,
 }
 
 pub struct NamedArgHIR {
     /// the name of this argument
-    name: String,
+    pub(crate) name: String,
     /// the expression passed to this name
-    expr: Box<Hir>,
+    pub(crate) expr: Box<Hir>,
+}
+
+pub struct BreakHIR {
+    /// the value the enclosing loop evaluates to, if any
+    pub(crate) value: Option<Box<Hir>>,
 }
 
 pub struct ReturnHIR {
     /// the return value
-    value: Box<Hir>,
+    pub(crate) value: Box<Hir>,
 }
 
 pub struct ArrayAccessHIR {
     /// the expression being accessed
-    accessed: Box<Hir>,
+    pub(crate) accessed: Box<Hir>,
     /// the offset passed
-    offset: Box<Hir>,
+    pub(crate) offset: Box<Hir>,
+}
+
+pub struct ArrayLiteralHIR {
+    /// the elements of the array, in order
+    pub(crate) elements: Vec<Hir>,
+}
+
+pub struct ArrayRepeatHIR {
+    /// the value every element starts out as. Evaluated once
+    pub(crate) value: Box<Hir>,
+    /// how many elements the array has
+    pub(crate) count: usize,
+}
+
+pub struct FieldInitHIR {
+    /// the name of the field being initialized
+    pub(crate) name: String,
+    /// where the field is named
+    pub(crate) name_loc: SourceRange,
+    /// the initial value of the field
+    pub(crate) value: Hir,
+}
+
+pub struct ObjectLiteralHIR {
+    /// the initial value of each field and composed part, in the order they are written
+    pub(crate) fields: Vec<FieldInitHIR>,
+}
+
+pub struct VariantHIR {
+    /// the name of the enum variant being constructed
+    pub(crate) variant: String,
+    /// the values the variant carries. Positional values are named by their index
+    pub(crate) values: Vec<FieldInitHIR>,
+}
+
+pub struct CastHIR {
+    /// the value being converted. The target type is the type of the node
+    pub(crate) expr: Box<Hir>,
+}
+
+pub struct LengthHIR {
+    /// the array or view whose element count is taken
+    pub(crate) array: Box<Hir>,
+}
+
+pub struct IterableHIR {
+    /// the value a for loop iterates over. It has the type of the value, provided it can be iterated
+    pub(crate) value: Box<Hir>,
+}
+
+pub enum PatternHIR {
+    /// matches anything without binding it
+    Wildcard,
+    /// matches a value equal to the literal
+    Literal(Literal),
+    /// matches anything and binds it to a name
+    Binding(String),
+    /// matches an optional that holds a value matching the inner pattern
+    Some(Box<HirNode<PatternHIR>>),
+    /// matches an object whose listed fields match. Unlisted fields match anything
+    Object {
+        name: String,
+        fields: Vec<(String, HirNode<PatternHIR>)>,
+    },
+    /// matches one variant of an enum. Positional values are named by their index, and unlisted
+    /// fields match anything
+    Variant {
+        enum_name: String,
+        variant: String,
+        values: Vec<(String, HirNode<PatternHIR>)>,
+    },
+}
+
+pub struct MatchArmHIR {
+    /// the pattern the matched value is tested against. Its type is the type of value it tests
+    /// for, as far as the pattern alone tells
+    pub(crate) pattern: HirNode<PatternHIR>,
+    /// an extra condition that has to hold for the arm to be taken
+    pub(crate) guard: Option<Box<Hir>>,
+    /// the value of the match when this arm is taken
+    pub(crate) body: Box<Hir>,
+}
+
+pub struct MatchHIR {
+    /// the value being matched. Evaluated once
+    pub(crate) scrutinee: Box<Hir>,
+    /// the arms, tried in order. Each arm is located at its pattern
+    pub(crate) arms: Vec<HirNode<MatchArmHIR>>,
 }
 
 /// high-level intermediate representation. HIR is basically an AST transformed into a sequence of
@@ -138,13 +235,23 @@ pub enum Hir {
     BinaryOp(HirNode<BinaryOpHIR>),
     Condition(HirNode<ConditionHIR>),
     Loop(HirNode<LoopHIR>),
-    Break(HirNode<()>),  // TODO break with a value...
-    Identifier(HirNode<String>),
+    Break(HirNode<BreakHIR>),
+    Continue(HirNode<()>),
+    Identifier(HirNode<IdentifierHIR>),
     Literal(HirNode<Literal>),
     FunCall(HirNode<FunCallHIR>),
     NamedArg(HirNode<NamedArgHIR>),
+    FieldAccess(HirNode<FieldAccessHIR>),
     Return(HirNode<ReturnHIR>),
     ArrayAccess(HirNode<ArrayAccessHIR>),
+    ArrayLiteral(HirNode<ArrayLiteralHIR>),
+    ArrayRepeat(HirNode<ArrayRepeatHIR>),
+    ObjectLiteral(HirNode<ObjectLiteralHIR>),
+    Variant(HirNode<VariantHIR>),
+    Cast(HirNode<CastHIR>),
+    Length(HirNode<LengthHIR>),
+    Iterable(HirNode<IterableHIR>),
+    Match(HirNode<MatchHIR>),
 }
 
 impl Hir {
@@ -164,12 +271,74 @@ impl Hir {
             Hir::Return(node) => &node.ty,
             Hir::ArrayAccess(node) => &node.ty,
             Hir::NamedArg(node) => &node.ty,
+            Hir::FieldAccess(node) => &node.ty,
             Hir::Block(node) => &node.ty,
             Hir::Break(node) => &node.ty,
+            Hir::Continue(node) => &node.ty,
+            Hir::ArrayLiteral(node) => &node.ty,
+            Hir::ArrayRepeat(node) => &node.ty,
+            Hir::ObjectLiteral(node) => &node.ty,
+            Hir::Variant(node) => &node.ty,
+            Hir::Cast(node) => &node.ty,
+            Hir::Length(node) => &node.ty,
+            Hir::Iterable(node) => &node.ty,
+            Hir::Match(node) => &node.ty,
+        }
+    }
+
+    pub fn ty_mut(&mut self) -> &mut Type {
+        match self {
+            Hir::CompilationUnit(node) => &mut node.ty,
+            Hir::FunctionDeclaration(node) => &mut node.ty,
+            Hir::VariableDeclaration(node) => &mut node.ty,
+            Hir::Assignment(node) => &mut node.ty,
+            Hir::UnaryOp(node) => &mut node.ty,
+            Hir::BinaryOp(node) => &mut node.ty,
+            Hir::Condition(node) => &mut node.ty,
+            Hir::Loop(node) => &mut node.ty,
+            Hir::Identifier(node) => &mut node.ty,
+            Hir::Literal(node) => &mut node.ty,
+            Hir::FunCall(node) => &mut node.ty,
+            Hir::Return(node) => &mut node.ty,
+            Hir::ArrayAccess(node) => &mut node.ty,
+            Hir::NamedArg(node) => &mut node.ty,
+            Hir::FieldAccess(node) => &mut node.ty,
+            Hir::Block(node) => &mut node.ty,
+            Hir::Break(node) => &mut node.ty,
+            Hir::Continue(node) => &mut node.ty,
+            Hir::ArrayLiteral(node) => &mut node.ty,
+            Hir::ArrayRepeat(node) => &mut node.ty,
+            Hir::ObjectLiteral(node) => &mut node.ty,
+            Hir::Variant(node) => &mut node.ty,
+            Hir::Cast(node) => &mut node.ty,
+            Hir::Length(node) => &mut node.ty,
+            Hir::Iterable(node) => &mut node.ty,
+            Hir::Match(node) => &mut node.ty,
+        }
+    }
+
+    /// if a value can be stored here: a variable, a field, an array element or a dereferenced
+    /// reference
+    pub fn is_place(&self) -> bool {
+        match self {
+            Hir::Identifier(_) | Hir::FieldAccess(_) | Hir::ArrayAccess(_) => true,
+            Hir::UnaryOp(node) => node.inner.op == UnaryOp::Deref,
+            _ => false,
         }
     }
 }
 
+/// the type a sequence of statements evaluates to, which is the value of the last statement.
+/// Declarations have no value, and a sequence that ends by leaving it with a `return`, `break` or
+/// `continue` never produces a value, so it can stand in for any type
+pub fn block_type(stmts: &[Hir]) -> Type {
+    match stmts.last() {
+        None | Some(Hir::VariableDeclaration(_)) => Type::Unit,
+        Some(Hir::Return(_) | Hir::Break(_) | Hir::Continue(_)) => Type::Unknown,
+        Some(last) => last.ty().clone(),
+    }
+}
+
 impl HasLocation for Hir {
     fn source_range(&self) -> SourceRange {
         match self {
@@ -187,8 +356,18 @@ impl HasLocation for Hir {
             Hir::Return(node) => node.loc,
             Hir::ArrayAccess(node) => node.loc,
             Hir::NamedArg(node) => node.loc,
+            Hir::FieldAccess(node) => node.loc,
             Hir::Block(node) => node.loc,
             Hir::Break(node) => node.loc,
+            Hir::Continue(node) => node.loc,
+            Hir::ArrayLiteral(node) => node.loc,
+            Hir::ArrayRepeat(node) => node.loc,
+            Hir::ObjectLiteral(node) => node.loc,
+            Hir::Variant(node) => node.loc,
+            Hir::Cast(node) => node.loc,
+            Hir::Length(node) => node.loc,
+            Hir::Iterable(node) => node.loc,
+            Hir::Match(node) => node.loc,
         }
     }
 }
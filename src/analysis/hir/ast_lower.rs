@@ -1,85 +1,644 @@
-use std::marker::PhantomData;
-use crate::analysis::hir::{ConditionHIR, FunCallHIR, Hir, HirNode, LoopHIR, NamedArgHIR, ReturnHIR};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use crate::analysis::hir::{ArrayAccessHIR, ArrayLiteralHIR, ArrayRepeatHIR, AssignmentHIR, BinaryOpHIR, BlockHIR, BreakHIR, CastHIR, CompilationUnitHIR, ConditionHIR, FieldAccessHIR, FieldInitHIR, FunCallHIR, FunctionDeclarationHIR, Hir, HirNode, IdentifierHIR, IterableHIR, LengthHIR, LoopHIR, MatchArmHIR, MatchHIR, NamedArgHIR, ObjectLiteralHIR, PatternHIR, ReturnHIR, UnaryOpHIR, VariableDeclarationHIR, VariantHIR};
+use crate::analysis::resolve::Resolution;
 use crate::error::source::SourceError;
-use crate::frontend::ast::{ArrayAccessNode, AssignmentNode, Ast, BinaryOpNode, CompilationUnitNode, CompositionSpecNode, CondExprNode, FieldDeclarationNode, FunCallNode, FunctionDeclarationNode, IdentNode, LitNode, NamedArgNode, ObjectDeclarationNode, ParamNode, ReturnNode, TypeSpecNode, UnaryOpNode, VariableDeclarationNode, WhileNode};
+use crate::frontend::ast::{ArrayAccessNode, ArrayLiteralNode, ArrayRepeatNode, AssignmentNode, Ast, BinaryOpNode, BreakNode, CastNode, CompilationUnitNode, CompositionSpecNode, CondExprNode, ContinueNode, EnumDeclarationNode, EnumVariantNode, FieldAccessNode, FieldDeclarationNode, ForNode, FunCallNode, FunctionDeclarationNode, IdentNode, LitNode, MatchNode, MethodCallNode, NamedArgNode, ObjectDeclarationNode, ObjectLiteralNode, ParamNode, Pattern, PatternField, ReturnNode, TypeSpecNode, UnaryOpNode, VariableDeclarationNode, WhileNode};
 use crate::frontend::ast::visitor::AstVisitor;
+use crate::frontend::location::{HasLocation, SourceRange};
 use crate::literal::Literal;
-use crate::symtab::SymbolTable;
-use crate::types::Type;
+use crate::operators::{BinaryOp, UnaryOp};
+use crate::symtab::{SymbolId, SymbolTable};
+use crate::types::{EnumType, ObjectType, Type, VariantType};
+
+#[cfg(test)]
+mod test;
 
 pub struct AstLowering<'symtab> {
-    phantom: PhantomData<&'symtab SymbolTable>,
+    /// symbols declared at the top level, used to look up object types
+    symtab: &'symtab SymbolTable,
+    /// the symbol each identifier was resolved to, which identifiers are lowered with
+    resolution: &'symtab Resolution,
+    /// counter used to generate unique names for compiler-introduced temporaries
+    temp_counter: Cell<usize>,
+    /// errors from statements that could not be lowered. Lowering carries on with the next
+    /// statement, so that as many errors as possible are reported at once
+    errors: RefCell<Vec<SourceError>>,
+}
+
+/// context threaded through lowering
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoweringCtx {
+    /// if we are currently lowering the body of a loop
+    in_loop: bool,
+}
+
+impl LoweringCtx {
+    fn in_loop(self) -> Self {
+        Self { in_loop: true }
+    }
+}
+
+impl<'symtab> AstLowering<'symtab> {
+    pub fn new(symtab: &'symtab SymbolTable, resolution: &'symtab Resolution) -> Self {
+        Self {
+            symtab,
+            resolution,
+            temp_counter: Cell::new(0),
+            errors: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn into_errors(self) -> Vec<SourceError> {
+        self.errors.into_inner()
+    }
+
+    /// lower a sequence of statements. A statement that fails to lower is reported and replaced
+    /// by a unit of unknown type, so that the error doesn't cascade into the types around it
+    fn lower_stmts(&self, stmts: Vec<Ast>, ctx: LoweringCtx) -> Vec<Hir> {
+        let mut lowered = Vec::<Hir>::new();
+        for stmt in stmts {
+            let loc = stmt.source_range();
+            match self.visit(stmt, ctx) {
+                Ok(stmt) => lowered.push(stmt),
+                Err(err) => {
+                    self.errors.borrow_mut().push(err);
+                    lowered.push(Hir::Literal(HirNode {
+                        inner: Literal::Unit,
+                        ty: Type::Unknown,
+                        loc,
+                    }));
+                }
+            }
+        }
+
+        lowered
+    }
+
+    /// create a name for a compiler-introduced temporary. The name contains a '$', so it can never
+    /// collide with an identifier written in source
+    fn temporary_name(&self, purpose: &str) -> String {
+        let id = self.temp_counter.get();
+        self.temp_counter.set(id + 1);
+        format!("${}{}", purpose, id)
+    }
+
+    /// lower a name written in source, along with the symbol it was resolved to
+    fn lowered_name(&self, ident: &IdentNode) -> Hir {
+        Hir::Identifier(HirNode {
+            inner: IdentifierHIR {
+                name: ident.ident.clone(),
+                symbol: self.resolution.symbol_of(ident),
+            },
+            ty: Type::Unknown,
+            loc: ident.location,
+        })
+    }
+
+    /// lower the named field initializers used to construct an object or an enum variant. Which
+    /// fields are given is checked by the type checker
+    fn lower_field_inits(&self, inits: Vec<Ast>, constructed: &str, ctx: LoweringCtx) -> Result<Vec<FieldInitHIR>, SourceError> {
+        let mut fields = Vec::new();
+        for init in inits {
+            let Ast::NamedArg(init) = init else {
+                let msg = format!("The fields of '{}' have to be given by name", constructed);
+                return Err(SourceError::new(msg, init.source_range()));
+            };
+
+            let name = init.param_name.into_ident();
+            fields.push(FieldInitHIR {
+                name: name.ident,
+                name_loc: name.location,
+                value: self.visit(*init.value, ctx)?,
+            });
+        }
+
+        Ok(fields)
+    }
+
+    /// the enum named by the object of a field access or method call, if it names one. Accessing
+    /// a member of an enum constructs one of its variants
+    fn enum_receiver(&self, receiver: &Ast) -> Option<&'symtab EnumType> {
+        match receiver {
+            Ast::Identifier(ident) => self.symtab.lookup_enum(&ident.ident),
+            _ => None,
+        }
+    }
+
+    /// lower the construction of an enum variant. Variants that carry data are constructed with
+    /// a call (`Shape.Circle(1.0)`, with named args for variants with fields), and variants that
+    /// don't are named directly (`Shape.Empty`)
+    fn lower_variant(&self, enum_type: &EnumType, variant: IdentNode, args: Option<Vec<Ast>>, loc: SourceRange, ctx: LoweringCtx) -> Result<Hir, SourceError> {
+        let Some(enum_variant) = enum_type.variant(&variant.ident) else {
+            let msg = format!("No variant '{}' on enum '{}'", variant.ident, enum_type.name);
+            return Err(SourceError::new(msg, variant.location));
+        };
+
+        let qualified = format!("{}.{}", enum_type.name, variant.ident);
+        let values = match (&enum_variant.payload, args) {
+            (VariantType::Unit, None) => Vec::new(),
+            (VariantType::Unit, Some(_)) => {
+                let msg = format!("Variant '{}' carries no values, so it is constructed without parentheses", qualified);
+                return Err(SourceError::new(msg, loc));
+            }
+            (_, None) => {
+                let msg = format!("Variant '{}' carries values, so it has to be constructed with '{}(...)'", qualified, qualified);
+                return Err(SourceError::new(msg, loc));
+            }
+            (VariantType::Tuple(value_types), Some(args)) => {
+                if args.len() != value_types.len() {
+                    let noun = if value_types.len() == 1 { "value" } else { "values" };
+                    let msg = format!("Variant '{}' takes {} {}, but got {}", qualified, value_types.len(), noun, args.len());
+                    return Err(SourceError::new(msg, loc));
+                }
+
+                // positional values are named by their index
+                let mut values = Vec::new();
+                for (idx, arg) in args.into_iter().enumerate() {
+                    let value = self.visit(arg, ctx)?;
+                    values.push(FieldInitHIR {
+                        name: idx.to_string(),
+                        name_loc: value.source_range(),
+                        value,
+                    });
+                }
+
+                values
+            }
+            (VariantType::Struct(_), Some(args)) => self.lower_field_inits(args, &qualified, ctx)?,
+        };
+
+        Ok(Hir::Variant(HirNode {
+            inner: VariantHIR {
+                variant: variant.ident,
+                values,
+            },
+            ty: Type::UserDefined(enum_type.name.clone()),
+            loc,
+        }))
+    }
+
+    /// lower the block of a conditional branch into its statements
+    fn lower_branch(&self, branch: Ast, ctx: LoweringCtx) -> Vec<Hir> {
+        let Ast::Block(stmts) = branch else {
+            panic!("Conditional has a non-block node as a branch")
+        };
+
+        self.lower_stmts(stmts, ctx)
+    }
+
+    /// lower a pattern. Whether the pattern can match the matched value is up to the type
+    /// checker, once the value's type is known
+    fn lower_pattern(&self, pattern: Pattern) -> Result<HirNode<PatternHIR>, SourceError> {
+        let loc = pattern.source_range();
+        let inner = match pattern {
+            Pattern::Wildcard(_) => PatternHIR::Wildcard,
+            Pattern::Binding(name) => PatternHIR::Binding(name.ident),
+            Pattern::Literal(lit) => PatternHIR::Literal(lit.lit),
+            Pattern::Some { inner, .. } => PatternHIR::Some(self.lower_pattern(*inner)?.into()),
+            Pattern::Object { name, fields, .. } => {
+                let Some(object) = self.symtab.lookup_object(&name.ident) else {
+                    let msg = format!("Cannot match on '{}', which is not an object type", name.ident);
+                    return Err(SourceError::new(msg, name.location));
+                };
+
+                let lookup = |name: &str| self.symtab.lookup_object(name);
+                let field_names = object.field_names(&lookup).into_iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>();
+                let fields = self.lower_field_patterns(fields, "type", &object.name, &field_names)?;
+                PatternHIR::Object {
+                    name: name.ident,
+                    fields,
+                }
+            }
+            Pattern::Variant { enum_name, variant, values, location } => {
+                let Some(enum_type) = self.symtab.lookup_enum(&enum_name.ident) else {
+                    let msg = format!("Cannot match on '{}', which is not an enum type", enum_name.ident);
+                    return Err(SourceError::new(msg, enum_name.location));
+                };
+
+                let Some(enum_variant) = enum_type.variant(&variant.ident) else {
+                    let msg = format!("No variant '{}' on enum '{}'", variant.ident, enum_type.name);
+                    return Err(SourceError::new(msg, variant.location));
+                };
+
+                let qualified = format!("{}.{}", enum_type.name, variant.ident);
+                let values = match (&enum_variant.payload, values) {
+                    (VariantType::Unit, None) => Vec::new(),
+                    (VariantType::Unit, Some(_)) => {
+                        let msg = format!("Variant '{}' carries no values, so it is matched without parentheses", qualified);
+                        return Err(SourceError::new(msg, location));
+                    }
+                    (_, None) => {
+                        let msg = format!("Variant '{}' carries values, so it has to be matched with '{}(...)'", qualified, qualified);
+                        return Err(SourceError::new(msg, location));
+                    }
+                    (VariantType::Tuple(value_types), Some(values)) => {
+                        if values.len() != value_types.len() {
+                            let noun = if value_types.len() == 1 { "value" } else { "values" };
+                            let msg = format!("Variant '{}' carries {} {}, but the pattern matches {}", qualified, value_types.len(), noun, values.len());
+                            return Err(SourceError::new(msg, location));
+                        }
+
+                        // positional values are named by their index
+                        let mut lowered = Vec::<(String, HirNode<PatternHIR>)>::new();
+                        for (idx, value) in values.into_iter().enumerate() {
+                            if let Some(name) = value.name {
+                                let msg = format!("The values of variant '{}' are positional, so they can't be matched by name", qualified);
+                                return Err(SourceError::new(msg, name.location));
+                            }
+
+                            lowered.push((idx.to_string(), self.lower_pattern(value.pattern)?));
+                        }
+
+                        lowered
+                    }
+                    (VariantType::Struct(fields), Some(values)) => {
+                        let field_names = fields.keys()
+                            .map(String::as_str)
+                            .collect::<Vec<_>>();
+                        self.lower_field_patterns(values, "variant", &qualified, &field_names)?
+                    }
+                };
+
+                PatternHIR::Variant {
+                    enum_name: enum_name.ident,
+                    variant: variant.ident,
+                    values,
+                }
+            }
+        };
+
+        Ok(HirNode {
+            ty: pattern_type(&inner),
+            inner,
+            loc,
+        })
+    }
+
+    /// lower the field patterns of an object or enum variant pattern. A lone binding matches the
+    /// field of the same name, and fields that are left out match anything. `field_names` are
+    /// the fields that can be matched
+    fn lower_field_patterns(&self, fields: Vec<PatternField>, kind: &str, matched: &str, field_names: &[&str]) -> Result<Vec<(String, HirNode<PatternHIR>)>, SourceError> {
+        let mut lowered = Vec::<(String, HirNode<PatternHIR>)>::new();
+        let mut matched_fields = HashMap::<String, SourceRange>::new();
+        for field in fields {
+            let name = match (field.name, &field.pattern) {
+                (Some(name), _) => name,
+                (None, Pattern::Binding(binding)) => binding.clone(),
+                (None, pattern) => {
+                    let msg = format!("The fields of '{}' have to be matched by name", matched);
+                    return Err(SourceError::new(msg, pattern.source_range()));
+                }
+            };
+
+            if !field_names.contains(&name.ident.as_str()) {
+                let msg = format!("No field '{}' on {} '{}'", name.ident, kind, matched);
+                let err = SourceError::new(msg, name.location)
+                    .with_closest_match(&name.ident, name.location, field_names.iter().copied());
+                return Err(err);
+            }
+
+            if let Some(first_loc) = matched_fields.get(&name.ident) {
+                let msg = format!("Field '{}' is matched more than once", name.ident);
+                return Err(SourceError::new(msg, name.location).with_context_note(*first_loc, "first matched here"));
+            }
+
+            matched_fields.insert(name.ident.clone(), name.location);
+            lowered.push((name.ident, self.lower_pattern(field.pattern)?));
+        }
+
+        Ok(lowered)
+    }
+}
+
+/// the type of value a pattern tests for, as far as the pattern alone tells. Integer literals
+/// without a suffix could be any integer type, so they tell nothing
+fn pattern_type(pattern: &PatternHIR) -> Type {
+    match pattern {
+        PatternHIR::Wildcard | PatternHIR::Binding(_) | PatternHIR::Literal(Literal::Int(_)) => Type::Unknown,
+        PatternHIR::Literal(lit) => Type::from(lit.clone()),
+        PatternHIR::Some(inner) => Type::Optional(inner.ty.clone().into()),
+        PatternHIR::Object { name, .. } => Type::UserDefined(name.clone()),
+        PatternHIR::Variant { enum_name, .. } => Type::UserDefined(enum_name.clone()),
+    }
+}
+
+/// a compiler-introduced temporary, which isn't bound to any symbol
+fn lowered_ident(name: &str, loc: SourceRange) -> Hir {
+    Hir::Identifier(HirNode {
+        inner: IdentifierHIR {
+            name: name.to_string(),
+            symbol: None,
+        },
+        ty: Type::Unknown,
+        loc,
+    })
+}
+
+fn lowered_literal(lit: Literal, loc: SourceRange) -> Hir {
+    let ty = Type::from(lit.clone());
+    Hir::Literal(HirNode {
+        inner: lit,
+        ty,
+        loc,
+    })
+}
+
+fn lowered_var_decl(name: String, symbol: Option<SymbolId>, initializer: Hir, loc: SourceRange) -> Hir {
+    Hir::VariableDeclaration(HirNode {
+        inner: VariableDeclarationHIR {
+            name,
+            symbol,
+            initializer: initializer.into(),
+        },
+        ty: Type::Unknown,
+        loc,
+    })
+}
+
+fn lowered_assignment(lhs: Hir, rhs: Hir, loc: SourceRange) -> Hir {
+    Hir::Assignment(HirNode {
+        inner: AssignmentHIR {
+            lhs: lhs.into(),
+            rhs: rhs.into(),
+        },
+        ty: Type::Unknown,
+        loc,
+    })
+}
+
+fn lowered_unary_op(op: UnaryOp, child: Hir, loc: SourceRange) -> Hir {
+    Hir::UnaryOp(HirNode {
+        inner: UnaryOpHIR {
+            op,
+            child: child.into(),
+        },
+        ty: Type::Unknown,
+        loc,
+    })
+}
+
+fn lowered_binary_op(op: BinaryOp, lhs: Hir, rhs: Hir, loc: SourceRange) -> Hir {
+    Hir::BinaryOp(HirNode {
+        inner: BinaryOpHIR {
+            op,
+            lhs: lhs.into(),
+            rhs: rhs.into(),
+        },
+        ty: Type::Unknown,
+        loc,
+    })
+}
+
+/// check the shape of an array literal against the type of the binding it initializes. The types
+/// of the elements are left for the type checker, since literals among them take on the declared
+/// element type
+fn check_array_initializer(declared: &Type, init: &Hir) -> Result<(), SourceError> {
+    let (elements, len) = match init {
+        Hir::ArrayLiteral(node) => (node.inner.elements.iter().collect::<Vec<_>>(), node.inner.elements.len()),
+        Hir::ArrayRepeat(node) => (vec![node.inner.value.as_ref()], node.inner.count),
+        _ => return Ok(()),
+    };
+
+    let declared_element = match declared {
+        Type::Array(element, declared_len) => {
+            if len != *declared_len {
+                let msg = format!("Expected an array of {} elements for type '{}', but the literal has {}", declared_len, declared, len);
+                return Err(SourceError::new(msg, init.source_range()));
+            }
+
+            element
+        }
+        // sized arrays convert to views, so any length is fine
+        Type::View(element) => element,
+        Type::Unknown => return Ok(()),
+        other => {
+            let msg = format!("Cannot initialize a value of type '{}' with an array literal", other);
+            return Err(SourceError::new(msg, init.source_range()));
+        }
+    };
+
+    for element in elements {
+        check_array_initializer(declared_element, element)?;
+    }
+
+    Ok(())
 }
 
 impl<'symtab> AstVisitor for AstLowering<'symtab> {
     type ResT = Hir;
     type ErrT = SourceError;
-    type CtxT = ();
+    type CtxT = LoweringCtx;
 
     fn visit_compilation_unit(&self, node: CompilationUnitNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+        let mut functions = Vec::<HirNode<FunctionDeclarationHIR>>::new();
+        let mut objects = Vec::<ObjectType>::new();
+        let mut enums = Vec::<EnumType>::new();
+        for decl in node.declarations {
+            // the types of objects and enums were already collected into the symbol table
+            match decl {
+                Ast::ObjectDeclaration(decl) => {
+                    let name = decl.name.into_ident().ident;
+                    objects.extend(self.symtab.lookup_object(&name).cloned());
+                }
+                Ast::EnumDeclaration(decl) => {
+                    let name = decl.name.into_ident().ident;
+                    enums.extend(self.symtab.lookup_enum(&name).cloned());
+                }
+                decl => match self.visit(decl, ctx) {
+                    Ok(Hir::FunctionDeclaration(function)) => functions.push(function),
+                    Ok(_) => panic!("Compilation unit has a declaration that did not lower to a function"),
+                    Err(err) => self.errors.borrow_mut().push(err),
+                },
+            }
+        }
+
+        Ok(Hir::CompilationUnit(HirNode {
+            inner: CompilationUnitHIR {
+                functions,
+                objects,
+                enums,
+            },
+            ty: Type::Unit,
+            loc: SourceRange::default(),
+        }))
     }
 
     fn visit_function_declaration(&self, node: FunctionDeclarationNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+        let name = node.name.into_ident();
+        let Some(Type::Function(fun)) = self.symtab.symbol_defined(&name.ident).map(|symbol| &symbol.tp) else {
+            panic!("Function '{}' was not lifted into the symbol table", name.ident)
+        };
+
+        let Ast::Block(stmts) = *node.body else {
+            panic!("Function declaration has a non-block node as its body")
+        };
+
+        let body = self.lower_stmts(stmts, ctx);
+
+        Ok(Hir::FunctionDeclaration(HirNode {
+            inner: FunctionDeclarationHIR {
+                name: name.ident,
+                params: fun.args.clone(),
+                body,
+            },
+            ty: Type::Function(fun.clone()),
+            loc: node.location,
+        }))
+    }
+
+    fn visit_object_declaration(&self, _node: ObjectDeclarationNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        unreachable!("Object declarations are collected by the compilation unit")
+    }
+
+    fn visit_field_declaration(&self, _node: FieldDeclarationNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        unreachable!("Field declarations are part of their object's type")
     }
 
-    fn visit_object_declaration(&self, node: ObjectDeclarationNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+    fn visit_enum_declaration(&self, _node: EnumDeclarationNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        unreachable!("Enum declarations are collected by the compilation unit")
     }
 
-    fn visit_field_declaration(&self, node: FieldDeclarationNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+    fn visit_enum_variant(&self, _node: EnumVariantNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        unreachable!("Enum variants are part of their enum's type")
     }
 
-    fn visit_composition_spec(&self, node: CompositionSpecNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+    fn visit_composition_spec(&self, _node: CompositionSpecNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        unreachable!("Composition specs are part of their object's type")
     }
 
-    fn visit_variable_declaration(&self, node: VariableDeclarationNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+    fn visit_variable_declaration(&self, _node: VariableDeclarationNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        unreachable!("Variable declarations are lowered with the assignment that initializes them")
     }
 
-    fn visit_param(&self, node: ParamNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+    fn visit_param(&self, _node: ParamNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        unreachable!("Parameters are part of their function's type")
     }
 
-    fn visit_block(&self, stmts: Vec<Box<Ast>>, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+    fn visit_block(&self, stmts: Vec<Ast>, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        let loc = stmts.first()
+            .map(|first| SourceRange::spanned(first, stmts.last().unwrap()))
+            .unwrap_or_default();
+        let insts = self.lower_stmts(stmts, ctx);
+
+        Ok(Hir::Block(HirNode {
+            inner: BlockHIR {
+                insts,
+            },
+            ty: Type::Unknown,
+            loc,
+        }))
     }
 
     fn visit_assignment(&self, node: AssignmentNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+        let rhs = self.visit(*node.rhs, ctx)?;
+        match *node.decl {
+            Ast::VariableDeclaration(decl) => {
+                let declared = match *decl.tp {
+                    Ast::TypeSpec(spec) => spec.tp,
+                    _ => Type::Unknown,
+                };
+
+                check_array_initializer(&declared, &rhs)?;
+
+                let name = decl.name.into_ident();
+                Ok(Hir::VariableDeclaration(HirNode {
+                    inner: VariableDeclarationHIR {
+                        symbol: self.resolution.symbol_of(&name),
+                        name: name.ident,
+                        initializer: rhs.into(),
+                    },
+                    ty: declared,
+                    loc: node.location,
+                }))
+            }
+            target => {
+                let loc = node.location;
+                let Some(op) = node.op else {
+                    let lhs = self.visit(target, ctx)?;
+                    return Ok(lowered_assignment(lhs, rhs, loc));
+                };
+
+                // x op= y is desugared into x = x op y. Plain bindings can simply be named twice
+                if let Ast::Identifier(ident) = target {
+                    let combined = lowered_binary_op(op, self.lowered_name(&ident), rhs, loc);
+                    return Ok(lowered_assignment(self.lowered_name(&ident), combined, loc));
+                }
+
+                // any other place may have side effects while it is computed, e.g. arr[f()] += 1,
+                // so take a reference to it once: { let $place = &<lhs>; *$place = *$place op <rhs> }
+                let place_name = self.temporary_name("place");
+                let lhs = self.visit(target, ctx)?;
+                if !lhs.is_place() {
+                    let msg = "Only variables, fields, array elements and dereferenced references can be updated in place";
+                    return Err(SourceError::new(msg, lhs.source_range()));
+                }
+
+                let place_decl = lowered_var_decl(place_name.clone(), None, lowered_unary_op(UnaryOp::Ref, lhs, loc), loc);
+                let combined = lowered_binary_op(op, lowered_unary_op(UnaryOp::Deref, lowered_ident(&place_name, loc), loc), rhs, loc);
+                let update = lowered_assignment(lowered_unary_op(UnaryOp::Deref, lowered_ident(&place_name, loc), loc), combined, loc);
+
+                Ok(Hir::Block(HirNode {
+                    inner: BlockHIR {
+                        insts: vec![place_decl, update],
+                    },
+                    ty: Type::Unit,
+                    loc,
+                }))
+            }
+        }
     }
 
     fn visit_unary_op(&self, node: UnaryOpNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+        let child = self.visit(*node.child, ctx)?;
+        Ok(lowered_unary_op(node.op, child, node.location))
     }
 
     fn visit_binary_op(&self, node: BinaryOpNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+        let lhs = self.visit(*node.lhs, ctx)?;
+        let rhs = self.visit(*node.rhs, ctx)?;
+        Ok(lowered_binary_op(node.op, lhs, rhs, node.location))
     }
 
     fn visit_cond_expr(&self, node: CondExprNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+        let cond = self.visit(*node.cond, ctx)?;
+        let true_branch = self.lower_branch(*node.true_branch, ctx);
+        let false_branch = match node.false_branch {
+            Some(false_branch) => self.lower_branch(*false_branch, ctx),
+            None => Vec::new(),
+        };
+
+        Ok(Hir::Condition(HirNode {
+            inner: ConditionHIR {
+                cond: cond.into(),
+                true_branch,
+                false_branch,
+            },
+            ty: Type::Unknown,
+            loc: node.location,
+        }))
     }
 
     fn visit_while(&self, node: WhileNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        // first, build out a conditional
-        let lowered_cond = self.visit(node.cond, ctx)?;
+        // first, build out a conditional that leaves the loop once the condition is false
+        let lowered_cond = self.visit(*node.cond, ctx)?;
         let check_inst = Hir::Condition(HirNode {
             inner: ConditionHIR {
                 cond: lowered_cond.into(),
                 true_branch: vec![
-                    Hir::Break(HirNode {
-                        inner: (),
+                    Hir::Literal(HirNode {
+                        inner: Literal::Unit,
                         ty: Type::Unknown,
                         loc: Default::default(),
                     })
                 ],
                 false_branch: vec![
-                    Hir::Literal(HirNode {
-                        inner: Literal::Unit,
+                    Hir::Break(HirNode {
+                        inner: BreakHIR {
+                            value: None,
+                        },
                         ty: Type::Unknown,
                         loc: Default::default(),
                     })
@@ -89,12 +648,12 @@ impl<'symtab> AstVisitor for AstLowering<'symtab> {
             loc: Default::default(),
         });
 
-        let Hir::Block(block_insts) = self.visit(node.body, ctx)? else {
+        let Hir::Block(block_insts) = self.visit(*node.body, ctx.in_loop())? else {
             panic!()
         };
 
         let mut insts = vec![check_inst];
-        insts.extend(block_insts.into_inner().insts.into_iter());
+        insts.extend(block_insts.into_inner().insts);
 
         Ok(Hir::Loop(HirNode {
             inner: LoopHIR {
@@ -105,15 +664,118 @@ impl<'symtab> AstVisitor for AstLowering<'symtab> {
         }))
     }
 
-    fn visit_identifier(&self, node: IdentNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        Ok(Hir::Identifier(HirNode {
-            inner: node.ident,
+    fn visit_for(&self, node: ForNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        // for loops are desugared into a loop over an index into the iterable:
+        // { let $iter = <iterable>; let $index = 0; loop { if ($index >= len($iter)) break;
+        //   let <binding> = $iter[$index]; $index = $index + 1; <body> } }
+        let loc = node.location;
+        let binding = node.binding.into_ident();
+        let iter_name = self.temporary_name("iter");
+        let index_name = self.temporary_name("index");
+
+        // evaluate the iterable exactly once. A value that cannot be iterated is reported there
+        let iter_loc = node.iterable.source_range();
+        let lowered_iterable = Hir::Iterable(HirNode {
+            inner: IterableHIR {
+                value: self.visit(*node.iterable, ctx)?.into(),
+            },
             ty: Type::Unknown,
-            loc: node.location
+            loc: iter_loc,
+        });
+        let iter_decl = lowered_var_decl(iter_name.clone(), None, lowered_iterable, iter_loc);
+        let index_decl = lowered_var_decl(index_name.clone(), None, lowered_literal(Literal::Int(0), loc), loc);
+
+        // stop once the index runs off the end of the iterable
+        let check_inst = Hir::Condition(HirNode {
+            inner: ConditionHIR {
+                cond: Hir::BinaryOp(HirNode {
+                    inner: BinaryOpHIR {
+                        op: BinaryOp::Gte,
+                        lhs: lowered_ident(&index_name, loc).into(),
+                        rhs: Hir::Length(HirNode {
+                            inner: LengthHIR {
+                                array: lowered_ident(&iter_name, iter_loc).into(),
+                            },
+                            ty: Type::Unknown,
+                            loc: iter_loc,
+                        }).into(),
+                    },
+                    ty: Type::Unknown,
+                    loc,
+                }).into(),
+                true_branch: vec![
+                    Hir::Break(HirNode {
+                        inner: BreakHIR {
+                            value: None,
+                        },
+                        ty: Type::Unknown,
+                        loc: Default::default(),
+                    })
+                ],
+                false_branch: vec![
+                    lowered_literal(Literal::Unit, Default::default())
+                ],
+            },
+            ty: Type::Unknown,
+            loc: Default::default(),
+        });
+
+        // bind the current element, then step the index before the body runs
+        let element_decl = lowered_var_decl(binding.ident.clone(), self.resolution.symbol_of(&binding), Hir::ArrayAccess(HirNode {
+            inner: ArrayAccessHIR {
+                accessed: lowered_ident(&iter_name, iter_loc).into(),
+                offset: lowered_ident(&index_name, loc).into(),
+            },
+            ty: Type::Unknown,
+            loc: binding.location,
+        }), binding.location);
+
+        let step_inst = Hir::Assignment(HirNode {
+            inner: AssignmentHIR {
+                lhs: lowered_ident(&index_name, loc).into(),
+                rhs: Hir::BinaryOp(HirNode {
+                    inner: BinaryOpHIR {
+                        op: BinaryOp::Plus,
+                        lhs: lowered_ident(&index_name, loc).into(),
+                        rhs: lowered_literal(Literal::Int(1), loc).into(),
+                    },
+                    ty: Type::Unknown,
+                    loc,
+                }).into(),
+            },
+            ty: Type::Unknown,
+            loc,
+        });
+
+        let Hir::Block(block_insts) = self.visit(*node.body, ctx.in_loop())? else {
+            panic!()
+        };
+
+        let mut insts = vec![check_inst, element_decl, step_inst];
+        insts.extend(block_insts.into_inner().insts);
+
+        let loop_inst = Hir::Loop(HirNode {
+            inner: LoopHIR {
+                stmts: insts,
+            },
+            ty: Type::Unknown,
+            loc,
+        });
+
+        Ok(Hir::Block(HirNode {
+            inner: BlockHIR {
+                insts: vec![iter_decl, index_decl, loop_inst],
+            },
+            ty: Type::Unknown,
+            loc,
         }))
     }
 
-    fn visit_literal(&self, node: LitNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+    fn visit_identifier(&self, node: IdentNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(self.lowered_name(&node))
+    }
+
+    fn visit_literal(&self, node: LitNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         let ty = Type::from(node.lit.clone());
         Ok(Hir::Literal(HirNode {
             inner: node.lit,
@@ -123,7 +785,7 @@ impl<'symtab> AstVisitor for AstLowering<'symtab> {
     }
 
     fn visit_fun_call(&self, node: FunCallNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        let callee = self.visit(node.fun_name, ctx)?;
+        let callee = self.visit(*node.fun_name, ctx)?;
 
         let mut lowered_args = Vec::<Hir>::new();
         for arg in node.args {
@@ -136,6 +798,62 @@ impl<'symtab> AstVisitor for AstLowering<'symtab> {
                 callee: callee.into(),
                 args: lowered_args,
             },
+            // calls are typed by the type checker
+            ty: Type::Unknown,
+            loc: node.location,
+        }))
+    }
+
+    fn visit_field_access(&self, node: FieldAccessNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        if let Some(enum_type) = self.enum_receiver(&node.object).filter(|_| !node.chained) {
+            return self.lower_variant(enum_type, node.field.into_ident(), None, node.location, ctx);
+        }
+
+        let object = self.visit(*node.object, ctx)?;
+        let field = node.field.into_ident();
+
+        Ok(Hir::FieldAccess(HirNode {
+            inner: FieldAccessHIR {
+                object: object.into(),
+                field: field.ident,
+                chained: node.chained,
+            },
+            // fields are typed by the type checker, which also checks '?.' against the object
+            ty: Type::Unknown,
+            loc: node.location,
+        }))
+    }
+
+    fn visit_method_call(&self, node: MethodCallNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        if let Some(enum_type) = self.enum_receiver(&node.receiver).filter(|_| !node.chained) {
+            return self.lower_variant(enum_type, node.method.into_ident(), Some(node.args), node.location, ctx);
+        }
+
+        // objects only have fields, so a method is a field holding a function, which we call
+        let receiver = self.visit(*node.receiver, ctx)?;
+        let method = node.method.into_ident();
+
+        let callee = Hir::FieldAccess(HirNode {
+            inner: FieldAccessHIR {
+                object: receiver.into(),
+                field: method.ident,
+                chained: node.chained,
+            },
+            ty: Type::Unknown,
+            loc: method.location,
+        });
+
+        let mut lowered_args = Vec::<Hir>::new();
+        for arg in node.args {
+            lowered_args.push(self.visit(arg, ctx)?);
+        }
+
+        Ok(Hir::FunCall(HirNode {
+            inner: FunCallHIR {
+                callee: callee.into(),
+                args: lowered_args,
+            },
+            // calls are typed by the type checker
             ty: Type::Unknown,
             loc: node.location,
         }))
@@ -143,7 +861,7 @@ impl<'symtab> AstVisitor for AstLowering<'symtab> {
 
     fn visit_named_arg(&self, node: NamedArgNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         let name = node.param_name.into_ident().ident;
-        let value = self.visit(node.value, ctx)?;
+        let value = self.visit(*node.value, ctx)?;
         Ok(Hir::NamedArg(HirNode {
             inner: NamedArgHIR {
                 name,
@@ -155,7 +873,7 @@ impl<'symtab> AstVisitor for AstLowering<'symtab> {
     }
 
     fn visit_return(&self, node: ReturnNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        let lowered_expr = self.visit(node.expr, ctx)?;
+        let lowered_expr = self.visit(*node.expr, ctx)?;
         let ty = lowered_expr.ty().clone();
         Ok(Hir::Return(HirNode {
             inner: ReturnHIR {
@@ -166,11 +884,165 @@ impl<'symtab> AstVisitor for AstLowering<'symtab> {
         }))
     }
 
+    fn visit_break(&self, node: BreakNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        if !ctx.in_loop {
+            return Err(SourceError::new("'break' can only be used inside of a loop", node.location));
+        }
+
+        let value = match node.value {
+            Some(value) => Some(self.visit(*value, ctx)?.into()),
+            None => None,
+        };
+
+        Ok(Hir::Break(HirNode {
+            inner: BreakHIR {
+                value,
+            },
+            ty: Type::Unknown,
+            loc: node.location,
+        }))
+    }
+
+    fn visit_continue(&self, node: ContinueNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        if !ctx.in_loop {
+            return Err(SourceError::new("'continue' can only be used inside of a loop", node.location));
+        }
+
+        Ok(Hir::Continue(HirNode {
+            inner: (),
+            ty: Type::Unknown,
+            loc: node.location,
+        }))
+    }
+
     fn visit_array_access(&self, node: ArrayAccessNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        todo!()
+        let accessed = self.visit(*node.derefed, ctx)?;
+        let offset = self.visit(*node.access, ctx)?;
+
+        // the type checker reports accesses into values that aren't arrays, and non-integer offsets
+        let ty = match accessed.ty() {
+            Type::Array(element, _) | Type::View(element) => element.as_ref().clone(),
+            _ => Type::Unknown,
+        };
+
+        Ok(Hir::ArrayAccess(HirNode {
+            inner: ArrayAccessHIR {
+                accessed: accessed.into(),
+                offset: offset.into(),
+            },
+            ty,
+            loc: node.location,
+        }))
+    }
+
+    fn visit_array_literal(&self, node: ArrayLiteralNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        let mut elements = Vec::<Hir>::new();
+        for element in node.elements {
+            elements.push(self.visit(element, ctx)?);
+        }
+
+        // the element type is settled by the type checker, which also adapts literals to it
+        let element_type = elements.iter()
+            .map(|element| element.ty())
+            .find(|tp| !matches!(tp, Type::Unknown))
+            .cloned()
+            .unwrap_or(Type::Unknown);
+
+        let len = elements.len();
+        Ok(Hir::ArrayLiteral(HirNode {
+            inner: ArrayLiteralHIR {
+                elements,
+            },
+            ty: Type::Array(element_type.into(), len),
+            loc: node.location,
+        }))
+    }
+
+    fn visit_array_repeat(&self, node: ArrayRepeatNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        let value = self.visit(*node.value, ctx)?;
+        let element_type = value.ty().clone();
+        Ok(Hir::ArrayRepeat(HirNode {
+            inner: ArrayRepeatHIR {
+                value: value.into(),
+                count: node.count,
+            },
+            ty: Type::Array(element_type.into(), node.count),
+            loc: node.location,
+        }))
+    }
+
+    fn visit_object_literal(&self, node: ObjectLiteralNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        let name = node.name.into_ident();
+        let Some(object) = self.symtab.lookup_object(&name.ident) else {
+            let msg = format!("Cannot construct '{}', which is not an object type", name.ident);
+            return Err(SourceError::new(msg, name.location));
+        };
+
+        // fields and composed parts are both initialized by name
+        let fields = self.lower_field_inits(node.fields, &object.name, ctx)?;
+
+        Ok(Hir::ObjectLiteral(HirNode {
+            inner: ObjectLiteralHIR {
+                fields,
+            },
+            ty: Type::UserDefined(name.ident),
+            loc: node.location,
+        }))
+    }
+
+    fn visit_cast(&self, node: CastNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        let expr = self.visit(*node.expr, ctx)?;
+        let Ast::TypeSpec(spec) = *node.tp else {
+            panic!("Cast has a non-type node as its target type")
+        };
+
+        // the type checker checks the cast once the type of the value is settled
+        Ok(Hir::Cast(HirNode {
+            inner: CastHIR {
+                expr: expr.into(),
+            },
+            ty: spec.tp,
+            loc: node.location,
+        }))
+    }
+
+    fn visit_match(&self, node: MatchNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        let scrutinee = self.visit(*node.scrutinee, ctx)?;
+
+        let mut arms = Vec::<HirNode<MatchArmHIR>>::new();
+        for arm in node.arms {
+            let pattern = self.lower_pattern(arm.pattern)?;
+            let pattern_loc = pattern.loc;
+
+            let guard = match arm.guard {
+                Some(guard) => Some(self.visit(*guard, ctx)?.into()),
+                None => None,
+            };
+
+            // guards and the types of the arms are checked by the type checker
+            let body = self.visit(*arm.body, ctx)?;
+            arms.push(HirNode {
+                ty: Type::Unknown,
+                inner: MatchArmHIR {
+                    pattern,
+                    guard,
+                    body: body.into(),
+                },
+                loc: pattern_loc,
+            });
+        }
+
+        Ok(Hir::Match(HirNode {
+            inner: MatchHIR {
+                scrutinee: scrutinee.into(),
+                arms,
+            },
+            ty: Type::Unknown,
+            loc: node.location,
+        }))
     }
 
-    fn visit_type_spec(&self, node: TypeSpecNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        unimplemented!()
+    fn visit_type_spec(&self, _node: TypeSpecNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        unreachable!("Type specs are read by the nodes they belong to")
     }
 }
@@ -0,0 +1,210 @@
+use crate::analysis::{analyze_source, error_messages};
+use crate::analysis::hir::Hir;
+use crate::frontend::location::{SourceLocation, SourceRange};
+use crate::operators::BinaryOp;
+
+/// lower and check a program, giving the body of its first function
+fn lower_body(source: &str) -> Vec<Hir> {
+    let Hir::CompilationUnit(unit) = analyze_source(source).expect("Analysis should not error") else {
+        panic!("Program did not lower to a compilation unit")
+    };
+
+    unit.inner.functions.into_iter()
+        .next()
+        .expect("Program should have a function")
+        .inner.body
+}
+
+#[test]
+fn lower_for_loop_over_index() {
+    let body = lower_body("fun main() { let xs = [1, 2]; for x in xs { let y: int = x; } }");
+    let Some(Hir::Block(lowered)) = body.last() else {
+        panic!("For loop should lower to a block")
+    };
+
+    let [Hir::VariableDeclaration(iter), Hir::VariableDeclaration(index), Hir::Loop(body)] = lowered.inner.insts.as_slice() else {
+        panic!("For loop should declare the iterable and index before looping")
+    };
+
+    assert!(iter.inner.name.starts_with('$') && index.inner.name.starts_with('$'));
+    assert!(matches!(body.inner.stmts.get(1), Some(Hir::VariableDeclaration(element)) if element.inner.name == "x"));
+}
+
+#[test]
+fn lower_for_loop_over_non_array() {
+    let errors = analyze_source("fun main() { let n = 3; for x in n { } }")
+        .err().expect("Iterating over an int should be rejected");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].msg(), "Cannot iterate over a value of type 'int'");
+    assert_eq!(errors[0].err_loc(), SourceRange {
+        start: SourceLocation { line: 0, col: 33 },
+        end: SourceLocation { line: 0, col: 34 },
+    });
+}
+
+#[test]
+fn lower_while_breaks_once_condition_is_false() {
+    let body = lower_body("fun main() { let c = true; while (c) { } }");
+    let Some(Hir::Loop(lowered)) = body.last() else {
+        panic!("While loop should lower to a loop")
+    };
+
+    let Some(Hir::Condition(check)) = lowered.inner.stmts.first() else {
+        panic!("Loop should start by checking the condition")
+    };
+
+    assert!(!matches!(check.inner.true_branch.as_slice(), [Hir::Break(_)]));
+    assert!(matches!(check.inner.false_branch.as_slice(), [Hir::Break(_)]));
+}
+
+#[test]
+fn lower_break_and_continue_in_loop() {
+    let body = lower_body("fun main() { let c = true; while (c) { if (c) { continue; }; break; } }");
+    let Some(Hir::Loop(lowered)) = body.last() else {
+        panic!("While loop should lower to a loop")
+    };
+
+    assert!(matches!(lowered.inner.stmts.last(), Some(Hir::Break(node)) if node.inner.value.is_none()));
+}
+
+#[test]
+fn lower_break_outside_loop() {
+    let errors = analyze_source("fun main() { break; }")
+        .err().expect("Break outside of a loop should be rejected");
+    assert_eq!(errors[0].msg(), "'break' can only be used inside of a loop");
+}
+
+#[test]
+fn lower_continue_outside_loop() {
+    let errors = analyze_source("fun main() { let c = true; if (c) { continue; }; }")
+        .err().expect("Continue outside of a loop should be rejected");
+    assert_eq!(errors[0].msg(), "'continue' can only be used inside of a loop");
+}
+
+#[test]
+fn lower_array_initializers() {
+    let body = lower_body("fun main() { let xs: [3]uint = [1, 2, 3]; let ys: [2][2]long = [[1, 2], [3, 4]]; let zs: []ulong = [5; 4]; }");
+    let [Hir::VariableDeclaration(xs), Hir::VariableDeclaration(ys), Hir::VariableDeclaration(zs)] = body.as_slice() else {
+        panic!("Expected three declarations")
+    };
+
+    assert_eq!(xs.inner.initializer.ty().to_string(), "[3]uint");
+    assert_eq!(ys.inner.initializer.ty().to_string(), "[2][2]long");
+    assert_eq!(zs.inner.initializer.ty().to_string(), "[4]ulong");
+}
+
+#[test]
+fn lower_array_initializer_of_wrong_length() {
+    let errors = error_messages("fun main() { let xs: [3]uint = [1, 2]; }");
+    assert_eq!(errors, ["Expected an array of 3 elements for type '[3]uint', but the literal has 2"]);
+
+    let errors = error_messages("fun main() { let xs: [2][2]int = [[1, 2], [3]]; }");
+    assert_eq!(errors, ["Expected an array of 2 elements for type '[2]int', but the literal has 1"]);
+}
+
+#[test]
+fn lower_array_initializer_of_non_array() {
+    let errors = error_messages("fun main() { let x: int = [1, 2]; }");
+    assert_eq!(errors, ["Cannot initialize a value of type 'int' with an array literal"]);
+}
+
+#[test]
+fn lower_compound_assignment_to_place_evaluates_it_once() {
+    let body = lower_body("fun main() { let mut xs = [1, 2]; xs[f()] += 1; } fun f(): int { return 0; }");
+    let Some(Hir::Block(lowered)) = body.last() else {
+        panic!("Compound assignment to an element should lower to a block")
+    };
+
+    let [Hir::VariableDeclaration(place), Hir::Assignment(update)] = lowered.inner.insts.as_slice() else {
+        panic!("Compound assignment should bind the place, then update it")
+    };
+
+    assert!(place.inner.name.starts_with('$'));
+    let Hir::UnaryOp(reference) = place.inner.initializer.as_ref() else {
+        panic!("The place should be bound by reference")
+    };
+    assert!(matches!(reference.inner.child.as_ref(), Hir::ArrayAccess(access) if matches!(access.inner.offset.as_ref(), Hir::FunCall(_))));
+
+    // the update only goes through the binding, so the index is computed once
+    let is_place = |hir: &Hir| matches!(hir, Hir::UnaryOp(deref) if matches!(deref.inner.child.as_ref(), Hir::Identifier(name) if name.inner.name == place.inner.name));
+    assert!(is_place(&update.inner.lhs));
+    let Hir::BinaryOp(combined) = update.inner.rhs.as_ref() else {
+        panic!("The place should be updated with the combined value")
+    };
+    assert!(is_place(&combined.inner.lhs));
+}
+
+#[test]
+fn lower_compound_assignment_operators() {
+    let ops = [
+        ("+=", BinaryOp::Plus),
+        ("-=", BinaryOp::Minus),
+        ("*=", BinaryOp::Times),
+        ("/=", BinaryOp::Divides),
+        ("%=", BinaryOp::Modulo),
+        ("&=", BinaryOp::BitAnd),
+        ("|=", BinaryOp::BitOr),
+        ("^=", BinaryOp::BitXor),
+        ("<<=", BinaryOp::ShiftLeft),
+        (">>=", BinaryOp::ShiftRight),
+    ];
+
+    for (assign, op) in ops {
+        let body = lower_body(&format!("fun main() {{ let mut x = 6; x {} 2; }}", assign));
+        let Some(Hir::Assignment(lowered)) = body.last() else {
+            panic!("'{}' should lower to an assignment", assign)
+        };
+
+        assert!(matches!(lowered.inner.lhs.as_ref(), Hir::Identifier(name) if name.inner.name == "x"));
+        assert!(matches!(lowered.inner.rhs.as_ref(), Hir::BinaryOp(combined) if combined.inner.op == op), "'{}' should combine with {:?}", assign, op);
+    }
+}
+
+#[test]
+fn lower_compound_assignment_to_immutable_binding() {
+    let errors = error_messages("fun main() { let x = 1; x += 1; }");
+    assert_eq!(errors, ["Cannot assign twice to immutable binding 'x'. Declare it with 'let mut' to allow reassignment"]);
+}
+
+#[test]
+fn lower_compound_assignment_to_non_place() {
+    let errors = error_messages("fun main() { f() += 1; } fun f(): int { return 0; }");
+    assert_eq!(errors, ["Only variables, fields, array elements and dereferenced references can be updated in place"]);
+}
+
+#[test]
+fn lower_object_and_variant_patterns() {
+    let errors = error_messages("object Point { x: int; } fun main() { let p = Point { x = 1 }; let x = match p { Point { z } => z }; }");
+    assert_eq!(errors, ["No field 'z' on type 'Point'"]);
+
+    let errors = error_messages("enum Shape { Circle(double), Empty } fun f(s: Shape): int { return match s { Shape.Circle(r, q) => 1, _ => 0 }; }");
+    assert_eq!(errors, ["Variant 'Shape.Circle' carries 1 value, but the pattern matches 2"]);
+
+    let errors = error_messages("enum Shape { Circle(double), Empty } fun f(s: Shape): int { return match s { Shape.Empty() => 1, _ => 0 }; }");
+    assert_eq!(errors, ["Variant 'Shape.Empty' carries no values, so it is matched without parentheses"]);
+
+    let errors = error_messages("enum Shape { Circle(double), Empty } fun f(s: Shape): int { return match s { Shape.Square => 1, _ => 0 }; }");
+    assert_eq!(errors, ["No variant 'Square' on enum 'Shape'"]);
+}
+
+#[test]
+fn lower_suggests_field_fixes() {
+    let errors = analyze_source("object Person { age: uint; } fun main() { let p = Person { age = 3 }; let a = p.aeg; }")
+        .err().expect("Analysis should error");
+    let fix = errors[0].fix().expect("A misspelled field should have a fix");
+    assert_eq!(fix.loc(), SourceRange {
+        start: SourceLocation { line: 0, col: 80 },
+        end: SourceLocation { line: 0, col: 83 },
+    });
+    assert_eq!(fix.replacement(), "age");
+
+    let errors = analyze_source("object Person { age: uint; } fun main() { let p = Person { aeg = 3 }; }")
+        .err().expect("Analysis should error");
+    let unknown = errors.iter().find(|err| err.msg() == "No field 'aeg' on type 'Person'").expect("The misspelled field should be reported");
+    let fix = unknown.fix().expect("A misspelled field should have a fix");
+    assert_eq!(fix.replacement(), "age");
+
+    let errors = analyze_source("object Person { age: uint; } fun main() { let p = Person { age = 3 }; let a = p.name; }")
+        .err().expect("Analysis should error");
+    assert!(errors[0].fix().is_none());
+}
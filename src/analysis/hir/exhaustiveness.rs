@@ -0,0 +1,312 @@
+use crate::analysis::hir::{HirNode, MatchArmHIR, PatternHIR};
+use crate::error::source::SourceError;
+use crate::frontend::location::SourceRange;
+use crate::literal::Literal;
+use crate::symtab::SymbolTable;
+use crate::types::VariantType;
+
+#[cfg(test)]
+mod test;
+
+/// a way of building a value that a pattern can test for
+#[derive(Debug, Clone, PartialEq)]
+enum Constructor {
+    Bool(bool),
+    Null,
+    Some,
+    /// any other literal. There are too many of these to ever list them all
+    Literal(Literal),
+    /// an enum variant, with the names of the values it carries in a fixed order
+    Variant {
+        enum_name: String,
+        variant: String,
+        fields: Vec<String>,
+    },
+    /// an object, with the names of all of its fields in a fixed order
+    Object {
+        name: String,
+        fields: Vec<String>,
+    },
+}
+
+impl Constructor {
+    /// how many values the constructor is built from
+    fn arity(&self) -> usize {
+        match self {
+            Constructor::Some => 1,
+            Constructor::Variant { fields, .. } | Constructor::Object { fields, .. } => fields.len(),
+            _ => 0,
+        }
+    }
+}
+
+/// a pattern reduced to what matters for coverage. Bindings are wildcards, and every constructor
+/// lists a pattern for each of its values
+#[derive(Debug, Clone)]
+enum Pat {
+    Wild,
+    Ctor(Constructor, Vec<Pat>),
+}
+
+/// Checks that the arms of a match cover every value the matched value can have, and that every
+/// arm can be reached. Coverage is computed on a matrix of patterns with one row per arm, along
+/// the lines of Maranget's "Warnings for pattern matching"
+pub struct ExhaustivenessChecker<'symtab> {
+    /// symbols declared at the top level, used to look up enums and objects
+    symtab: &'symtab SymbolTable,
+}
+
+impl<'symtab> ExhaustivenessChecker<'symtab> {
+    pub fn new(symtab: &'symtab SymbolTable) -> Self {
+        Self {
+            symtab,
+        }
+    }
+
+    /// check the arms of the match at the given location, giving an error for each arm that can't
+    /// be reached and one listing the values no arm matches. Guarded arms may not be taken, so
+    /// they never count towards covering a value
+    pub fn check(&self, arms: &[HirNode<MatchArmHIR>], loc: SourceRange) -> Vec<SourceError> {
+        let mut errors = Vec::new();
+        let mut covered = Vec::<Vec<Pat>>::new();
+        for arm in arms {
+            let row = vec![self.reduce(&arm.inner.pattern)];
+            if self.witnesses(&covered, &row).is_empty() {
+                let msg = "Unreachable match arm. Every value it matches is matched by an earlier arm";
+                errors.push(SourceError::new(msg, arm.loc));
+                continue;
+            }
+
+            if arm.inner.guard.is_none() {
+                covered.push(row);
+            }
+        }
+
+        let missing = self.witnesses(&covered, &[Pat::Wild]);
+        if !missing.is_empty() {
+            let cases = missing.iter()
+                .map(|witness| render(&witness[0]))
+                .collect::<Vec<_>>();
+            let msg = format!("Match is not exhaustive. Missing cases: {}", cases.join(", "));
+            errors.push(SourceError::new(msg, loc));
+        }
+
+        errors
+    }
+
+    /// the names of the values carried by a variant. Positional values are named by their index
+    fn variant_fields(payload: &VariantType) -> Vec<String> {
+        match payload {
+            VariantType::Unit => Vec::new(),
+            VariantType::Tuple(values) => (0..values.len()).map(|idx| idx.to_string()).collect(),
+            VariantType::Struct(fields) => {
+                let mut names = fields.keys().cloned().collect::<Vec<_>>();
+                names.sort();
+                names
+            }
+        }
+    }
+
+    fn reduce(&self, pattern: &HirNode<PatternHIR>) -> Pat {
+        match &pattern.inner {
+            PatternHIR::Wildcard | PatternHIR::Binding(_) => Pat::Wild,
+            PatternHIR::Literal(Literal::Boolean(value)) => Pat::Ctor(Constructor::Bool(*value), Vec::new()),
+            PatternHIR::Literal(Literal::Null) => Pat::Ctor(Constructor::Null, Vec::new()),
+            PatternHIR::Literal(lit) => Pat::Ctor(Constructor::Literal(lit.clone()), Vec::new()),
+            PatternHIR::Some(inner) => Pat::Ctor(Constructor::Some, vec![self.reduce(inner)]),
+            PatternHIR::Object { name, fields: listed } => {
+                let fields = match self.symtab.lookup_object(name) {
+                    Some(object) => {
+                        let lookup = |name: &str| self.symtab.lookup_object(name);
+                        let mut names = object.field_names(&lookup)
+                            .into_iter()
+                            .map(|(field, _)| field.to_string())
+                            .collect::<Vec<_>>();
+                        names.sort();
+                        names.dedup();
+                        names
+                    }
+                    None => listed.iter().map(|(field, _)| field.clone()).collect(),
+                };
+
+                let values = self.reduce_fields(&fields, listed);
+                Pat::Ctor(Constructor::Object { name: name.clone(), fields }, values)
+            }
+            PatternHIR::Variant { enum_name, variant, values: listed } => {
+                let fields = self.symtab.lookup_enum(enum_name)
+                    .and_then(|enum_type| enum_type.variant(variant))
+                    .map(|variant| Self::variant_fields(&variant.payload))
+                    .unwrap_or_else(|| listed.iter().map(|(field, _)| field.clone()).collect());
+
+                let values = self.reduce_fields(&fields, listed);
+                let ctor = Constructor::Variant {
+                    enum_name: enum_name.clone(),
+                    variant: variant.clone(),
+                    fields,
+                };
+                Pat::Ctor(ctor, values)
+            }
+        }
+    }
+
+    /// the pattern for each field in order. Fields that aren't listed match anything
+    fn reduce_fields(&self, fields: &[String], listed: &[(String, HirNode<PatternHIR>)]) -> Vec<Pat> {
+        fields.iter()
+            .map(|field| listed.iter()
+                .find(|(name, _)| name == field)
+                .map_or(Pat::Wild, |(_, pattern)| self.reduce(pattern)))
+            .collect()
+    }
+
+    /// every constructor of the type the given constructor builds, if there are few enough to
+    /// list them all
+    fn all_constructors(&self, ctor: &Constructor) -> Option<Vec<Constructor>> {
+        match ctor {
+            Constructor::Bool(_) => Some(vec![Constructor::Bool(true), Constructor::Bool(false)]),
+            Constructor::Null | Constructor::Some => Some(vec![Constructor::Null, Constructor::Some]),
+            Constructor::Literal(_) => None,
+            Constructor::Variant { enum_name, .. } => {
+                let enum_type = self.symtab.lookup_enum(enum_name)?;
+                let ctors = enum_type.variants.iter()
+                    .map(|variant| Constructor::Variant {
+                        enum_name: enum_name.clone(),
+                        variant: variant.name.clone(),
+                        fields: Self::variant_fields(&variant.payload),
+                    })
+                    .collect();
+                Some(ctors)
+            }
+            Constructor::Object { .. } => Some(vec![ctor.clone()]),
+        }
+    }
+
+    /// find the values matched by `row` that no row of the matrix matches. Each witness has one
+    /// pattern per column. An empty result means the row is covered by the matrix
+    fn witnesses(&self, matrix: &[Vec<Pat>], row: &[Pat]) -> Vec<Vec<Pat>> {
+        let Some((head, rest)) = row.split_first() else {
+            return if matrix.is_empty() { vec![Vec::new()] } else { Vec::new() };
+        };
+
+        if let Pat::Ctor(ctor, values) = head {
+            let specialized = specialize(matrix, ctor);
+            let row = values.iter().chain(rest).cloned().collect::<Vec<_>>();
+            return self.witnesses(&specialized, &row)
+                .into_iter()
+                .map(|witness| rebuild(ctor, witness))
+                .collect();
+        }
+
+        let used = matrix.iter()
+            .filter_map(|row| match &row[0] {
+                Pat::Ctor(ctor, _) => Some(ctor),
+                Pat::Wild => None,
+            })
+            .fold(Vec::<&Constructor>::new(), |mut used, ctor| {
+                if !used.contains(&ctor) {
+                    used.push(ctor);
+                }
+                used
+            });
+
+        let all = used.first().and_then(|ctor| self.all_constructors(ctor));
+        if let Some(all) = all.as_ref().filter(|all| all.iter().all(|ctor| used.contains(&ctor))) {
+            // every constructor shows up in the column, so each one has to be covered in turn
+            return all.iter()
+                .flat_map(|ctor| {
+                    let specialized = specialize(matrix, ctor);
+                    let row = std::iter::repeat_n(Pat::Wild, ctor.arity())
+                        .chain(rest.iter().cloned())
+                        .collect::<Vec<_>>();
+                    self.witnesses(&specialized, &row)
+                        .into_iter()
+                        .map(|witness| rebuild(ctor, witness))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+        }
+
+        // some constructor is missing from the column, so only the wildcard rows can cover it
+        let default = matrix.iter()
+            .filter(|row| matches!(row[0], Pat::Wild))
+            .map(|row| row[1..].to_vec())
+            .collect::<Vec<_>>();
+
+        let missing = match all {
+            Some(all) if !used.is_empty() => all.into_iter()
+                .filter(|ctor| !used.contains(&ctor))
+                .map(|ctor| {
+                    let values = vec![Pat::Wild; ctor.arity()];
+                    Pat::Ctor(ctor, values)
+                })
+                .collect(),
+            _ => vec![Pat::Wild],
+        };
+
+        self.witnesses(&default, rest)
+            .into_iter()
+            .flat_map(|witness| missing.iter().map(move |head| {
+                let mut witness = witness.clone();
+                witness.insert(0, head.clone());
+                witness
+            }))
+            .collect()
+    }
+}
+
+/// keep the rows whose first pattern can match a value built by the constructor, replacing that
+/// pattern with patterns for the constructor's values
+fn specialize(matrix: &[Vec<Pat>], ctor: &Constructor) -> Vec<Vec<Pat>> {
+    matrix.iter()
+        .filter_map(|row| {
+            let values = match &row[0] {
+                Pat::Ctor(row_ctor, values) if row_ctor == ctor => values.clone(),
+                Pat::Ctor(..) => return None,
+                Pat::Wild => vec![Pat::Wild; ctor.arity()],
+            };
+
+            Some(values.into_iter().chain(row[1..].iter().cloned()).collect())
+        })
+        .collect()
+}
+
+/// undo a specialization on a witness, folding the constructor's values back into it
+fn rebuild(ctor: &Constructor, mut witness: Vec<Pat>) -> Vec<Pat> {
+    let rest = witness.split_off(ctor.arity());
+    let mut rebuilt = vec![Pat::Ctor(ctor.clone(), witness)];
+    rebuilt.extend(rest);
+    rebuilt
+}
+
+/// write a witness the way it would appear as a pattern in source
+fn render(pat: &Pat) -> String {
+    let Pat::Ctor(ctor, values) = pat else {
+        return "_".to_string();
+    };
+
+    let named = |fields: &[String]| fields.iter()
+        .zip(values)
+        .map(|(field, value)| format!("{} = {}", field, render(value)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match ctor {
+        Constructor::Bool(value) => value.to_string(),
+        Constructor::Null => "null".to_string(),
+        Constructor::Some => format!("some {}", render(&values[0])),
+        Constructor::Literal(lit) => format!("{:?}", lit),
+        Constructor::Variant { enum_name, variant, fields } => {
+            if fields.is_empty() {
+                return format!("{}.{}", enum_name, variant);
+            }
+
+            let is_positional = fields.iter().all(|field| field.parse::<usize>().is_ok());
+            let values = if is_positional {
+                values.iter().map(render).collect::<Vec<_>>().join(", ")
+            } else {
+                named(fields)
+            };
+            format!("{}.{}({})", enum_name, variant, values)
+        }
+        Constructor::Object { name, fields } => format!("{} {{ {} }}", name, named(fields)),
+    }
+}
@@ -0,0 +1,72 @@
+use crate::analysis::{analyze_source, error_messages};
+
+const SHAPE: &str = "enum Shape { Circle(double), Rect { w: double; h: double; }, Empty }";
+
+#[test]
+fn exhaustive_optional_match() {
+    let result = analyze_source("fun main() { let o: int? = 3; let x = match o { some v => v, null => 0 }; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn non_exhaustive_optional_match() {
+    let errors = error_messages("fun main() { let o: int? = 3; let x = match o { null => 0 }; }");
+    assert_eq!(errors, ["Match is not exhaustive. Missing cases: some _"]);
+
+    let errors = error_messages("fun main() { let o: int? = 3; let x = match o { some 1 => 1, null => 0 }; }");
+    assert_eq!(errors, ["Match is not exhaustive. Missing cases: some _"]);
+}
+
+#[test]
+fn exhaustive_enum_match() {
+    let result = analyze_source(&format!("{} fun area(s: Shape): double {{ return match s {{ \
+        Shape.Circle(r) => r * r, Shape.Rect(w = w, h = h) => w * h, Shape.Empty => 0.0 }}; }}", SHAPE));
+    assert!(result.is_ok());
+}
+
+#[test]
+fn non_exhaustive_enum_match() {
+    let errors = error_messages(&format!("{} fun area(s: Shape): double {{ return match s {{ Shape.Circle(r) => r * r }}; }}", SHAPE));
+    assert_eq!(errors, ["Match is not exhaustive. Missing cases: Shape.Rect(h = _, w = _), Shape.Empty"]);
+}
+
+#[test]
+fn guarded_arms_do_not_cover() {
+    let errors = error_messages("fun main() { let b = true; let x = match b { true => 1, false if b => 0 }; }");
+    assert_eq!(errors, ["Match is not exhaustive. Missing cases: false"]);
+
+    let result = analyze_source("fun main() { let b = true; let x = match b { true => 1, false if b => 0, _ => 2 }; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn non_exhaustive_literal_match() {
+    let errors = error_messages("fun main() { let n = 3; let x = match n { 1 => 1, 2 => 2 }; }");
+    assert_eq!(errors, ["Match is not exhaustive. Missing cases: _"]);
+}
+
+#[test]
+fn unreachable_arm() {
+    let errors = error_messages("fun main() { let n = 3; let x = match n { _ => 1, 2 => 2 }; }");
+    assert_eq!(errors, ["Unreachable match arm. Every value it matches is matched by an earlier arm"]);
+
+    let errors = error_messages("fun main() { let o: int? = 3; let x = match o { some v => v, null => 0, some 1 => 1 }; }");
+    assert_eq!(errors, ["Unreachable match arm. Every value it matches is matched by an earlier arm"]);
+}
+
+#[test]
+fn every_unreachable_arm_and_missing_case() {
+    let errors = error_messages("fun main() { let b = true; let x = match b { true => 1, true => 2, true => 3 }; }");
+    assert_eq!(errors, [
+        "Match is not exhaustive. Missing cases: false",
+        "Unreachable match arm. Every value it matches is matched by an earlier arm",
+        "Unreachable match arm. Every value it matches is matched by an earlier arm",
+    ]);
+}
+
+#[test]
+fn mismatched_patterns_are_not_checked_for_coverage() {
+    let errors = error_messages("fun main() { let b = true; let x = match b { 1 => 1 }; }");
+    assert_eq!(errors.len(), 1);
+    assert!(!errors[0].starts_with("Match is not exhaustive"));
+}
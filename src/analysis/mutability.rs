@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+use crate::error::source::SourceError;
+use crate::frontend::ast::{ArrayAccessNode, ArrayLiteralNode, ArrayRepeatNode, AssignmentNode, Ast, BinaryOpNode, BreakNode, CastNode, CompilationUnitNode, CompositionSpecNode, CondExprNode, ContinueNode, EnumDeclarationNode, EnumVariantNode, FieldAccessNode, FieldDeclarationNode, ForNode, FunCallNode, FunctionDeclarationNode, IdentNode, LitNode, MatchNode, MethodCallNode, NamedArgNode, ObjectDeclarationNode, ObjectLiteralNode, ParamNode, ReturnNode, TypeSpecNode, UnaryOpNode, VariableDeclarationNode, WhileNode};
+use crate::frontend::ast::visitor::AstVisitorMut;
+use crate::frontend::location::HasLocation;
+use crate::operators::UnaryOp;
+use crate::symtab::{Symbol, SymbolTable};
+use crate::types::{Type, VariableDeclarationMode};
+
+#[cfg(test)]
+mod test;
+
+/// maps a binding holding a reference to the binding it references. A `None` entry shadows a
+/// reference binding from an outer scope
+type ReferenceFrame = HashMap<String, Option<Symbol>>;
+
+/// Reports assignments to bindings that were not declared with `let mut`, either directly or
+/// through a reference that was taken to an immutable binding
+pub struct MutabilityChecker {
+    /// the bindings currently in scope
+    symtab: SymbolTable,
+    /// bindings known to reference another binding, scoped in step with the symbol table
+    references: Vec<ReferenceFrame>,
+    /// errors collected along the way
+    errors: Vec<SourceError>,
+}
+
+impl MutabilityChecker {
+    pub fn new() -> Self {
+        Self {
+            symtab: SymbolTable::new(),
+            references: vec![ReferenceFrame::new()],
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn into_errors(self) -> Vec<SourceError> {
+        self.errors
+    }
+
+    fn push_scope(&mut self) {
+        self.symtab.push_scope();
+        self.references.push(ReferenceFrame::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.symtab.pop_scope();
+        self.references.pop();
+    }
+
+    fn declare(&mut self, name: IdentNode, tp: Type, mode: VariableDeclarationMode, referenced: Option<Symbol>) {
+        self.references.last_mut().unwrap().insert(name.ident.clone(), referenced);
+        self.symtab.add_symbol(Symbol::new(name.ident, tp, name.location, mode));
+    }
+
+    /// finds the binding that the given reference binding points to, if it is known
+    fn referenced_binding(&self, name: &str) -> Option<&Symbol> {
+        self.references.iter()
+            .rev()
+            .find_map(|frame| frame.get(name))
+            .and_then(|referenced| referenced.as_ref())
+    }
+
+    /// determine which binding a value refers to, if the value is a reference. Covers taking a
+    /// reference directly (`&x`) and copying an existing reference binding
+    fn reference_origin(&self, value: &Ast) -> Option<Symbol> {
+        match value {
+            Ast::UnaryOp(UnaryOpNode { op: UnaryOp::Ref, child, .. }) => {
+                let (root, _) = Self::assigned_root(child)?;
+                self.symtab.symbol_defined(&root.ident).cloned()
+            }
+            Ast::Identifier(ident) => self.referenced_binding(&ident.ident).cloned(),
+            // converting a reference still points at the same binding
+            Ast::Cast(node) => self.reference_origin(&node.expr),
+            _ => None,
+        }
+    }
+
+    /// find the binding that is ultimately written to by assigning to the given expression, and
+    /// whether the write goes through a dereference
+    fn assigned_root(target: &Ast) -> Option<(&IdentNode, bool)> {
+        match target {
+            Ast::Identifier(ident) => Some((ident, false)),
+            Ast::UnaryOp(UnaryOpNode { op: UnaryOp::Deref, child, .. }) => {
+                Self::assigned_root(child).map(|(root, _)| (root, true))
+            }
+            Ast::ArrayAccess(node) => Self::assigned_root(&node.derefed),
+            Ast::FieldAccess(node) => Self::assigned_root(&node.object),
+            _ => None,
+        }
+    }
+
+    fn check_assignment_target(&mut self, target: &Ast) {
+        let Some((root, through_ref)) = Self::assigned_root(target) else {
+            return;
+        };
+
+        let target_range = target.source_range();
+        if through_ref {
+            if let Some(referenced) = self.referenced_binding(&root.ident) {
+                if referenced.mode != VariableDeclarationMode::Mutable {
+                    let err = SourceError::new(format!("Cannot assign through '{}', which references immutable binding '{}'", root.ident, referenced.name), target_range)
+                        .with_context_location(referenced.loc);
+                    self.errors.push(err);
+                }
+            }
+        } else if let Some(symbol) = self.symtab.symbol_defined(&root.ident) {
+            let msg = match symbol.mode {
+                VariableDeclarationMode::Mutable => return,
+                VariableDeclarationMode::Const => format!("Cannot assign to constant '{}'", symbol.name),
+                VariableDeclarationMode::Immutable => format!("Cannot assign twice to immutable binding '{}'. Declare it with 'let mut' to allow reassignment", symbol.name),
+            };
+
+            let err = SourceError::new(msg, target_range)
+                .with_context_location(symbol.loc);
+            self.errors.push(err);
+        }
+    }
+
+    fn declared_type(tp: Ast) -> Type {
+        match tp {
+            Ast::TypeSpec(spec) => spec.tp,
+            _ => Type::Unknown,
+        }
+    }
+}
+
+impl AstVisitorMut for MutabilityChecker {
+    type ResT = ();
+    type ErrT = ();
+
+    fn visit_compilation_unit(&mut self, node: CompilationUnitNode) -> Result<Self::ResT, Self::ErrT> {
+        for decl in node.declarations {
+            self.visit(decl)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_function_declaration(&mut self, node: FunctionDeclarationNode) -> Result<Self::ResT, Self::ErrT> {
+        self.push_scope();
+        for param in node.params {
+            self.visit(param)?;
+        }
+
+        self.visit(*node.body)?;
+        self.pop_scope();
+        Ok(())
+    }
+
+    fn visit_object_declaration(&mut self, _node: ObjectDeclarationNode) -> Result<Self::ResT, Self::ErrT> {
+        Ok(())
+    }
+
+    fn visit_enum_declaration(&mut self, _node: EnumDeclarationNode) -> Result<Self::ResT, Self::ErrT> {
+        Ok(())
+    }
+
+    fn visit_enum_variant(&mut self, _node: EnumVariantNode) -> Result<Self::ResT, Self::ErrT> {
+        Ok(())
+    }
+
+    fn visit_field_declaration(&mut self, _node: FieldDeclarationNode) -> Result<Self::ResT, Self::ErrT> {
+        Ok(())
+    }
+
+    fn visit_composition_spec(&mut self, _node: CompositionSpecNode) -> Result<Self::ResT, Self::ErrT> {
+        Ok(())
+    }
+
+    fn visit_variable_declaration(&mut self, node: VariableDeclarationNode) -> Result<Self::ResT, Self::ErrT> {
+        let tp = Self::declared_type(*node.tp);
+        self.declare(node.name.into_ident(), tp, node.decl_mode, None);
+        Ok(())
+    }
+
+    fn visit_param(&mut self, node: ParamNode) -> Result<Self::ResT, Self::ErrT> {
+        // parameters behave like plain `let` bindings
+        let tp = Self::declared_type(*node.tp);
+        self.declare(node.name.into_ident(), tp, VariableDeclarationMode::Immutable, None);
+        Ok(())
+    }
+
+    fn visit_block(&mut self, stmts: Vec<Ast>) -> Result<Self::ResT, Self::ErrT> {
+        self.push_scope();
+        for stmt in stmts {
+            self.visit(stmt)?;
+        }
+
+        self.pop_scope();
+        Ok(())
+    }
+
+    fn visit_assignment(&mut self, node: AssignmentNode) -> Result<Self::ResT, Self::ErrT> {
+        let referenced = self.reference_origin(&node.rhs);
+        self.visit(*node.rhs)?;
+
+        match *node.decl {
+            Ast::VariableDeclaration(decl) => {
+                let tp = Self::declared_type(*decl.tp);
+                self.declare(decl.name.into_ident(), tp, decl.decl_mode, referenced);
+            }
+            target => {
+                self.check_assignment_target(&target);
+                self.visit(target)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn visit_unary_op(&mut self, node: UnaryOpNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.child)
+    }
+
+    fn visit_binary_op(&mut self, node: BinaryOpNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.lhs)?;
+        self.visit(*node.rhs)
+    }
+
+    fn visit_cond_expr(&mut self, node: CondExprNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.cond)?;
+        self.visit(*node.true_branch)?;
+        match node.false_branch {
+            Some(false_branch) => self.visit(*false_branch),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_while(&mut self, node: WhileNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.cond)?;
+        self.visit(*node.body)
+    }
+
+    fn visit_for(&mut self, node: ForNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.iterable)?;
+        self.push_scope();
+        self.declare(node.binding.into_ident(), Type::Unknown, VariableDeclarationMode::Immutable, None);
+        self.visit(*node.body)?;
+        self.pop_scope();
+        Ok(())
+    }
+
+    fn visit_identifier(&mut self, _node: IdentNode) -> Result<Self::ResT, Self::ErrT> {
+        Ok(())
+    }
+
+    fn visit_literal(&mut self, _node: LitNode) -> Result<Self::ResT, Self::ErrT> {
+        Ok(())
+    }
+
+    fn visit_fun_call(&mut self, node: FunCallNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.fun_name)?;
+        for arg in node.args {
+            self.visit(arg)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_field_access(&mut self, node: FieldAccessNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.object)
+    }
+
+    fn visit_method_call(&mut self, node: MethodCallNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.receiver)?;
+        for arg in node.args {
+            self.visit(arg)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_named_arg(&mut self, node: NamedArgNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.value)
+    }
+
+    fn visit_return(&mut self, node: ReturnNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.expr)
+    }
+
+    fn visit_break(&mut self, node: BreakNode) -> Result<Self::ResT, Self::ErrT> {
+        match node.value {
+            Some(value) => self.visit(*value),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_continue(&mut self, _node: ContinueNode) -> Result<Self::ResT, Self::ErrT> {
+        Ok(())
+    }
+
+    fn visit_array_access(&mut self, node: ArrayAccessNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.derefed)?;
+        self.visit(*node.access)
+    }
+
+    fn visit_array_literal(&mut self, node: ArrayLiteralNode) -> Result<Self::ResT, Self::ErrT> {
+        for element in node.elements {
+            self.visit(element)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_array_repeat(&mut self, node: ArrayRepeatNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.value)
+    }
+
+    fn visit_object_literal(&mut self, node: ObjectLiteralNode) -> Result<Self::ResT, Self::ErrT> {
+        for field in node.fields {
+            self.visit(field)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_cast(&mut self, node: CastNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.expr)
+    }
+
+    fn visit_match(&mut self, node: MatchNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.scrutinee)?;
+        for arm in node.arms {
+            self.push_scope();
+            for binding in arm.pattern.bindings() {
+                self.declare(binding.clone(), Type::Unknown, VariableDeclarationMode::Immutable, None);
+            }
+
+            if let Some(guard) = arm.guard {
+                self.visit(*guard)?;
+            }
+
+            self.visit(*arm.body)?;
+            self.pop_scope();
+        }
+
+        Ok(())
+    }
+
+    fn visit_type_spec(&mut self, _node: TypeSpecNode) -> Result<Self::ResT, Self::ErrT> {
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::analysis::{analyze_source, error_messages};
+
+#[test]
+fn reassign_mutable_binding() {
+    let result = analyze_source("fun main() { let mut x = 1; x = 2; x += 3; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn reassign_immutable_binding() {
+    let errors = error_messages("fun main() { let x = 1; x = 2; }");
+    assert_eq!(errors, ["Cannot assign twice to immutable binding 'x'. Declare it with 'let mut' to allow reassignment"]);
+}
+
+#[test]
+fn compound_assign_immutable_binding() {
+    let errors = error_messages("fun main() { let x = 1; x += 2; }");
+    assert_eq!(errors, ["Cannot assign twice to immutable binding 'x'. Declare it with 'let mut' to allow reassignment"]);
+}
+
+#[test]
+fn assign_constant() {
+    let errors = error_messages("fun main() { const x = 1; x = 2; }");
+    assert_eq!(errors, ["Cannot assign to constant 'x'"]);
+}
+
+#[test]
+fn assign_through_reference() {
+    let result = analyze_source("fun main() { let mut x = 1; let r = &x; *r = 2; }");
+    assert!(result.is_ok());
+
+    let errors = error_messages("fun main() { let x = 1; let r = &x; *r = 2; }");
+    assert_eq!(errors, ["Cannot assign through 'r', which references immutable binding 'x'"]);
+}
@@ -0,0 +1,506 @@
+use std::collections::HashMap;
+use clap::ValueEnum;
+use crate::error::source::SourceError;
+use crate::error::suggest::closest_match;
+use crate::frontend::ast::{ArrayAccessNode, ArrayLiteralNode, ArrayRepeatNode, AssignmentNode, Ast, BinaryOpNode, BreakNode, CastNode, CompilationUnitNode, CompositionSpecNode, CondExprNode, ContinueNode, EnumDeclarationNode, EnumVariantNode, FieldAccessNode, FieldDeclarationNode, ForNode, FunCallNode, FunctionDeclarationNode, IdentNode, LitNode, MatchNode, MethodCallNode, NamedArgNode, ObjectDeclarationNode, ObjectLiteralNode, ParamNode, Pattern, ReturnNode, TypeSpecNode, UnaryOpNode, VariableDeclarationNode, VariantPayload, WhileNode};
+use crate::frontend::ast::visitor::AstVisitorMut;
+use crate::frontend::location::SourceRange;
+use crate::symtab::{Symbol, SymbolId, SymbolTable};
+use crate::types::{Type, VariableDeclarationMode};
+
+#[cfg(test)]
+mod test;
+
+/// what to do about a `let` that declares a name that was already declared in the same scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ShadowingLevel {
+    /// say nothing, the later binding simply hides the earlier one
+    Allow,
+    /// report a warning
+    #[default]
+    Warn,
+    /// report an error
+    Deny,
+}
+
+/// the symbol each identifier in a program declares or refers to
+pub struct Resolution {
+    /// symbols by the location of the identifier bound to them
+    symbols: HashMap<SourceRange, SymbolId>,
+}
+
+impl Resolution {
+    /// the symbol an identifier declares or refers to. Names of fields, methods, variants and
+    /// named arguments belong to a type rather than a scope, so they aren't bound to a symbol
+    pub fn symbol_of(&self, ident: &IdentNode) -> Option<SymbolId> {
+        self.symbols.get(&ident.location).copied()
+    }
+}
+
+/// Binds every identifier to the symbol it names, and reports names that aren't declared, or are
+/// declared twice where that isn't allowed. Functions, objects and enums can be used anywhere in
+/// the program, including before they are declared, while bindings can only be used after their
+/// declaration, in the scope they are declared in
+pub struct NameResolver {
+    /// the symbols currently in scope
+    symtab: SymbolTable,
+    /// the symbols identifiers were bound to so far
+    symbols: HashMap<SourceRange, SymbolId>,
+    /// how shadowing in the same scope is reported
+    shadowing: ShadowingLevel,
+    /// errors and warnings collected along the way
+    errors: Vec<SourceError>,
+}
+
+impl NameResolver {
+    pub fn new(shadowing: ShadowingLevel) -> Self {
+        Self {
+            symtab: SymbolTable::new(),
+            symbols: HashMap::new(),
+            shadowing,
+            errors: Vec::new(),
+        }
+    }
+
+    /// the names resolved, and the errors and warnings found while resolving them
+    pub fn into_resolution(self) -> (Resolution, Vec<SourceError>) {
+        (Resolution { symbols: self.symbols }, self.errors)
+    }
+
+    fn declare(&mut self, name: &IdentNode, tp: Type, mode: VariableDeclarationMode) {
+        let id = self.symtab.add_symbol(Symbol::new(&name.ident, tp, name.location, mode));
+        self.symbols.insert(name.location, id);
+    }
+
+    /// declare a name in the current scope, unless it already is. Reports the duplicate with the
+    /// given message otherwise
+    fn declare_unique(&mut self, name: &IdentNode, tp: Type, mode: VariableDeclarationMode, msg: impl FnOnce(&str) -> String) {
+        if let Some(earlier) = self.symtab.symbol_defined_in_current_scope(&name.ident) {
+            let err = SourceError::new(msg(&name.ident), name.location)
+                .with_context_note(earlier.loc, "first declared here");
+            self.errors.push(err);
+            return;
+        }
+
+        self.declare(name, tp, mode);
+    }
+
+    /// declare a `let` binding, reporting it if it shadows one from the same scope
+    fn declare_local(&mut self, name: &IdentNode, mode: VariableDeclarationMode) {
+        if let Some(earlier) = self.symtab.symbol_defined_in_current_scope(&name.ident) {
+            let msg = format!("'{}' shadows an earlier binding in the same scope", name.ident);
+            let diagnostic = match self.shadowing {
+                ShadowingLevel::Allow => None,
+                ShadowingLevel::Warn => Some(SourceError::warning(msg, name.location)),
+                ShadowingLevel::Deny => Some(SourceError::new(msg, name.location)),
+            };
+
+            if let Some(diagnostic) = diagnostic {
+                self.errors.push(diagnostic.with_context_location(earlier.loc));
+            }
+        }
+
+        self.declare(name, Type::Unknown, mode);
+    }
+
+    /// bind a name used as a value to the symbol in scope
+    fn resolve_value(&mut self, name: &IdentNode) {
+        match self.symtab.symbol_defined(&name.ident) {
+            Some(symbol) => {
+                self.symbols.insert(name.location, symbol.id);
+            }
+            None => {
+                let msg = format!("Undefined name '{}'", name.ident);
+                let candidates = self.symtab.visible_symbols()
+                    .map(|symbol| symbol.name.as_str());
+                let err = SourceError::new(msg, name.location)
+                    .with_closest_match(&name.ident, name.location, candidates);
+                self.errors.push(err);
+            }
+        }
+    }
+
+    /// bind a name used as a type to the object or enum it names. Types are only declared at the
+    /// top level, so bindings with the same name don't hide them
+    fn resolve_type_name(&mut self, name: &IdentNode) {
+        if let Some(id) = self.check_type_name(&name.ident, name.location, str::to_string) {
+            self.symbols.insert(name.location, id);
+        }
+    }
+
+    /// check that a name names an object or enum. When it names nothing, the fix suggested for
+    /// the source at the given location is the corrected name passed through `corrected`
+    fn check_type_name(&mut self, name: &str, loc: SourceRange, corrected: impl FnOnce(&str) -> String) -> Option<SymbolId> {
+        let err = match self.symtab.symbol_defined_globally(name) {
+            Some(symbol) if matches!(symbol.tp, Type::UserDefined(_)) => return Some(symbol.id),
+            Some(_) => SourceError::new(format!("'{}' is not a type", name), loc),
+            None => {
+                let types = self.symtab.visible_symbols()
+                    .filter(|symbol| matches!(symbol.tp, Type::UserDefined(_)))
+                    .map(|symbol| symbol.name.as_str());
+                let fix = closest_match(name, types).map(corrected);
+
+                let err = SourceError::new(format!("Undefined type '{}'", name), loc);
+                match fix {
+                    Some(fix) => err.with_fix(loc, fix),
+                    None => err,
+                }
+            }
+        };
+
+        self.errors.push(err);
+        None
+    }
+
+    /// check the names of objects and enums a type is built from. Only the whole type has a
+    /// location, so a misspelled name is fixed by writing out the whole type again
+    fn check_type(&mut self, tp: &Type, loc: SourceRange) {
+        for name in type_names(tp) {
+            self.check_type_name(name, loc, |closest| {
+                let mut corrected = tp.clone();
+                rename_type(&mut corrected, name, closest);
+                corrected.to_string()
+            });
+        }
+    }
+
+    fn resolve_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Wildcard(_) | Pattern::Literal(_) => {}
+            Pattern::Binding(name) => {
+                // every arm has a scope of its own, so a name in it already comes from this pattern
+                self.declare_unique(name, Type::Unknown, VariableDeclarationMode::Immutable, |name| format!("'{}' is bound more than once in the same pattern", name));
+            }
+            Pattern::Some { inner, .. } => self.resolve_pattern(inner),
+            Pattern::Object { name, fields, .. } => {
+                self.resolve_type_name(name);
+                for field in fields {
+                    self.resolve_pattern(&field.pattern);
+                }
+            }
+            Pattern::Variant { enum_name, values, .. } => {
+                self.resolve_type_name(enum_name);
+                for value in values.iter().flatten() {
+                    self.resolve_pattern(&value.pattern);
+                }
+            }
+        }
+    }
+
+    /// report fields declared more than once on the same type
+    fn check_duplicate_fields(&mut self, fields: &[Ast], owner: &str) {
+        let mut declared = HashMap::<&str, SourceRange>::new();
+        for field in fields {
+            let Ast::FieldDeclaration(field) = field else {
+                continue;
+            };
+            let Ast::Identifier(name) = field.name.as_ref() else {
+                continue;
+            };
+
+            if let Some(earlier) = declared.get(name.ident.as_str()) {
+                let err = SourceError::new(format!("Duplicate field '{}' on '{}'", name.ident, owner), name.location)
+                    .with_context_note(*earlier, "first declared here");
+                self.errors.push(err);
+            } else {
+                declared.insert(&name.ident, name.location);
+            }
+        }
+    }
+}
+
+impl AstVisitorMut for NameResolver {
+    type ResT = ();
+    type ErrT = ();
+
+    fn visit_compilation_unit(&mut self, node: CompilationUnitNode) -> Result<Self::ResT, Self::ErrT> {
+        // everything declared at the top level is in scope everywhere, so it is all declared
+        // before anything is resolved. Objects and enums are marked as naming a type, and the
+        // actual types are worked out later by type lifting
+        for decl in &node.declarations {
+            let (name, names_type) = match decl {
+                Ast::FunctionDeclaration(decl) => (&decl.name, false),
+                Ast::ObjectDeclaration(decl) => (&decl.name, true),
+                Ast::EnumDeclaration(decl) => (&decl.name, true),
+                _ => continue,
+            };
+            let Ast::Identifier(name) = name.as_ref() else {
+                continue;
+            };
+
+            let tp = if names_type { Type::UserDefined(name.ident.clone()) } else { Type::Unknown };
+            self.declare_unique(name, tp, VariableDeclarationMode::Const, |name| format!("'{}' is already declared", name));
+        }
+
+        for decl in node.declarations {
+            self.visit(decl)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_function_declaration(&mut self, node: FunctionDeclarationNode) -> Result<Self::ResT, Self::ErrT> {
+        self.symtab.push_scope();
+        for param in node.params {
+            self.visit(param)?;
+        }
+
+        self.visit(*node.ret_tp)?;
+        self.visit(*node.body)?;
+        self.symtab.pop_scope();
+        Ok(())
+    }
+
+    fn visit_object_declaration(&mut self, node: ObjectDeclarationNode) -> Result<Self::ResT, Self::ErrT> {
+        let name = node.name.into_ident();
+        self.check_duplicate_fields(&node.fields, &name.ident);
+
+        for spec in node.composition_specs {
+            self.visit(spec)?;
+        }
+
+        for field in node.fields {
+            self.visit(field)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_enum_declaration(&mut self, node: EnumDeclarationNode) -> Result<Self::ResT, Self::ErrT> {
+        let enum_name = node.name.into_ident();
+        let mut declared = HashMap::<String, SourceRange>::new();
+        for variant in node.variants {
+            let Ast::EnumVariant(variant) = variant else {
+                panic!("Enum declaration has a non-variant node as a variant")
+            };
+
+            let name = variant.name.clone().into_ident();
+            if let Some(earlier) = declared.get(&name.ident) {
+                let err = SourceError::new(format!("Duplicate variant '{}' on '{}'", name.ident, enum_name.ident), name.location)
+                    .with_context_note(*earlier, "first declared here");
+                self.errors.push(err);
+            } else {
+                declared.insert(name.ident.clone(), name.location);
+            }
+
+            if let VariantPayload::Struct(fields) = &variant.payload {
+                self.check_duplicate_fields(fields, &format!("{}.{}", enum_name.ident, name.ident));
+            }
+
+            self.visit_enum_variant(variant)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_enum_variant(&mut self, node: EnumVariantNode) -> Result<Self::ResT, Self::ErrT> {
+        match node.payload {
+            VariantPayload::Unit => Ok(()),
+            VariantPayload::Tuple(values) | VariantPayload::Struct(values) => {
+                for value in values {
+                    self.visit(value)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn visit_field_declaration(&mut self, node: FieldDeclarationNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.tp)
+    }
+
+    fn visit_composition_spec(&mut self, node: CompositionSpecNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.composed_type)
+    }
+
+    fn visit_variable_declaration(&mut self, node: VariableDeclarationNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.tp)?;
+        self.declare_local(&node.name.into_ident(), node.decl_mode);
+        Ok(())
+    }
+
+    fn visit_param(&mut self, node: ParamNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.tp)?;
+        self.declare_unique(&node.name.into_ident(), Type::Unknown, VariableDeclarationMode::Immutable, |name| format!("Duplicate parameter '{}'", name));
+        Ok(())
+    }
+
+    fn visit_block(&mut self, stmts: Vec<Ast>) -> Result<Self::ResT, Self::ErrT> {
+        self.symtab.push_scope();
+        for stmt in stmts {
+            self.visit(stmt)?;
+        }
+
+        self.symtab.pop_scope();
+        Ok(())
+    }
+
+    fn visit_assignment(&mut self, node: AssignmentNode) -> Result<Self::ResT, Self::ErrT> {
+        // the value is computed before a binding it initializes comes into scope
+        self.visit(*node.rhs)?;
+        self.visit(*node.decl)
+    }
+
+    fn visit_unary_op(&mut self, node: UnaryOpNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.child)
+    }
+
+    fn visit_binary_op(&mut self, node: BinaryOpNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.lhs)?;
+        self.visit(*node.rhs)
+    }
+
+    fn visit_cond_expr(&mut self, node: CondExprNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.cond)?;
+        self.visit(*node.true_branch)?;
+        match node.false_branch {
+            Some(false_branch) => self.visit(*false_branch),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_while(&mut self, node: WhileNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.cond)?;
+        self.visit(*node.body)
+    }
+
+    fn visit_for(&mut self, node: ForNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.iterable)?;
+        self.symtab.push_scope();
+        self.declare(&node.binding.into_ident(), Type::Unknown, VariableDeclarationMode::Immutable);
+        self.visit(*node.body)?;
+        self.symtab.pop_scope();
+        Ok(())
+    }
+
+    fn visit_identifier(&mut self, node: IdentNode) -> Result<Self::ResT, Self::ErrT> {
+        self.resolve_value(&node);
+        Ok(())
+    }
+
+    fn visit_literal(&mut self, _node: LitNode) -> Result<Self::ResT, Self::ErrT> {
+        Ok(())
+    }
+
+    fn visit_fun_call(&mut self, node: FunCallNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.fun_name)?;
+        for arg in node.args {
+            self.visit(arg)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_named_arg(&mut self, node: NamedArgNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.value)
+    }
+
+    fn visit_return(&mut self, node: ReturnNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.expr)
+    }
+
+    fn visit_break(&mut self, node: BreakNode) -> Result<Self::ResT, Self::ErrT> {
+        match node.value {
+            Some(value) => self.visit(*value),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_continue(&mut self, _node: ContinueNode) -> Result<Self::ResT, Self::ErrT> {
+        Ok(())
+    }
+
+    fn visit_array_access(&mut self, node: ArrayAccessNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.derefed)?;
+        self.visit(*node.access)
+    }
+
+    fn visit_array_literal(&mut self, node: ArrayLiteralNode) -> Result<Self::ResT, Self::ErrT> {
+        for element in node.elements {
+            self.visit(element)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_array_repeat(&mut self, node: ArrayRepeatNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.value)
+    }
+
+    fn visit_field_access(&mut self, node: FieldAccessNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.object)
+    }
+
+    fn visit_method_call(&mut self, node: MethodCallNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.receiver)?;
+        for arg in node.args {
+            self.visit(arg)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_cast(&mut self, node: CastNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.expr)?;
+        self.visit(*node.tp)
+    }
+
+    fn visit_object_literal(&mut self, node: ObjectLiteralNode) -> Result<Self::ResT, Self::ErrT> {
+        self.resolve_type_name(&node.name.into_ident());
+        for field in node.fields {
+            self.visit(field)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_match(&mut self, node: MatchNode) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.scrutinee)?;
+        for arm in node.arms {
+            self.symtab.push_scope();
+            self.resolve_pattern(&arm.pattern);
+            if let Some(guard) = arm.guard {
+                self.visit(*guard)?;
+            }
+
+            self.visit(*arm.body)?;
+            self.symtab.pop_scope();
+        }
+
+        Ok(())
+    }
+
+    fn visit_type_spec(&mut self, node: TypeSpecNode) -> Result<Self::ResT, Self::ErrT> {
+        self.check_type(&node.tp, node.location);
+        Ok(())
+    }
+}
+
+/// the names of the objects and enums a type is built from, in the order they are written
+fn type_names(tp: &Type) -> Vec<&String> {
+    match tp {
+        Type::UserDefined(name) => vec![name],
+        Type::Reference(inner) | Type::Optional(inner) | Type::View(inner) | Type::Array(inner, _) => type_names(inner),
+        Type::Function(fun) => fun.args.iter()
+            .flat_map(|arg| type_names(&arg.tp))
+            .chain(type_names(&fun.ret))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// replace every use of a name in a type with another name
+fn rename_type(tp: &mut Type, from: &str, to: &str) {
+    match tp {
+        Type::UserDefined(name) if name == from => *name = to.to_string(),
+        Type::Reference(inner) | Type::Optional(inner) | Type::View(inner) | Type::Array(inner, _) => rename_type(inner, from, to),
+        Type::Function(fun) => {
+            for arg in &mut fun.args {
+                rename_type(&mut arg.tp, from, to);
+            }
+            rename_type(&mut fun.ret, from, to);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,142 @@
+use crate::analysis::resolve::{Resolution, ShadowingLevel};
+use crate::analysis::resolve_names;
+use crate::error::source::{Severity, SourceError};
+use crate::frontend::ast::IdentNode;
+use crate::frontend::input::SourceInput;
+use crate::frontend::location::{SourceLocation, SourceRange};
+use crate::frontend::parse_input_source;
+
+/// resolve the names in a program, giving the resolution and the warnings when there are no
+/// errors, and the errors along with the warnings otherwise
+fn resolve(source: &str, shadowing: ShadowingLevel) -> Result<(Resolution, Vec<SourceError>), Vec<SourceError>> {
+    let input = SourceInput::raw(source);
+    let ast = parse_input_source(&input).expect("Program should parse");
+    resolve_names(ast, shadowing)
+}
+
+fn resolve_errors(source: &str) -> Vec<String> {
+    resolve(source, ShadowingLevel::default())
+        .err().expect("Resolution should find errors")
+        .iter()
+        .map(|err| err.msg().to_string())
+        .collect()
+}
+
+/// an identifier on the first line, for looking up what it resolved to
+fn ident_at(name: &str, col: usize) -> IdentNode {
+    IdentNode {
+        ident: name.to_string(),
+        location: SourceRange {
+            start: SourceLocation { line: 0, col },
+            end: SourceLocation { line: 0, col: col + name.len() },
+        },
+    }
+}
+
+#[test]
+fn resolve_forward_references() {
+    let result = resolve("fun main() { let p: Person = make(); } fun make(): Person { return Person { age = 1 }; } \
+        object Person { age: uint; }", ShadowingLevel::default());
+    assert!(result.is_ok());
+}
+
+#[test]
+fn resolve_undefined_names() {
+    let errors = resolve_errors("fun main() { let x = y; }");
+    assert_eq!(errors, ["Undefined name 'y'"]);
+
+    let errors = resolve_errors("fun main() { let x: Persn = 1; }");
+    assert_eq!(errors, ["Undefined type 'Persn'"]);
+
+    let errors = resolve_errors("fun main() { let x = 1; } fun f() { let y = x; }");
+    assert_eq!(errors, ["Undefined name 'x'"]);
+}
+
+#[test]
+fn resolve_binding_before_declaration() {
+    let errors = resolve_errors("fun main() { let x = y; let y = 1; }");
+    assert_eq!(errors, ["Undefined name 'y'"]);
+
+    let errors = resolve_errors("fun main() { if (true) { let y = 1; }; let x = y; }");
+    assert_eq!(errors, ["Undefined name 'y'"]);
+}
+
+#[test]
+fn resolve_shadowing_levels() {
+    let source = "fun main() { let age = 12; let age = 13; }";
+
+    let (_, warnings) = resolve(source, ShadowingLevel::Warn).expect("Shadowing should only warn");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].msg(), "'age' shadows an earlier binding in the same scope");
+    assert_eq!(warnings[0].severity(), Severity::Warning);
+
+    let (_, warnings) = resolve(source, ShadowingLevel::Allow).expect("Shadowing should be allowed");
+    assert!(warnings.is_empty());
+
+    let errors = resolve(source, ShadowingLevel::Deny).err().expect("Shadowing should be denied");
+    assert_eq!(errors.iter().map(|err| err.msg()).collect::<Vec<_>>(), ["'age' shadows an earlier binding in the same scope"]);
+}
+
+#[test]
+fn resolve_shadowing_in_inner_scope() {
+    let (_, warnings) = resolve("fun main() { let age = 12; if (true) { let age = 13; }; }", ShadowingLevel::Deny)
+        .expect("Shadowing a binding from an outer scope should be fine");
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn resolve_shadowed_bindings_to_different_symbols() {
+    let source = "fun main() { let a = 1; let b = a; let a = 2; let c = a; }";
+    let (resolution, _) = resolve(source, ShadowingLevel::Allow).expect("Shadowing should be allowed");
+
+    let first = resolution.symbol_of(&ident_at("a", 17)).expect("First 'a' should be declared");
+    let second = resolution.symbol_of(&ident_at("a", 39)).expect("Second 'a' should be declared");
+    assert_ne!(first, second);
+    assert_eq!(resolution.symbol_of(&ident_at("a", 32)), Some(first));
+    assert_eq!(resolution.symbol_of(&ident_at("a", 54)), Some(second));
+}
+
+#[test]
+fn resolve_duplicate_declarations() {
+    let errors = resolve_errors("fun f(a: int, a: int) { }");
+    assert_eq!(errors, ["Duplicate parameter 'a'"]);
+
+    let errors = resolve_errors("object Person { age: uint; age: int; }");
+    assert_eq!(errors, ["Duplicate field 'age' on 'Person'"]);
+
+    let errors = resolve_errors("enum Shape { Empty, Empty }");
+    assert_eq!(errors, ["Duplicate variant 'Empty' on 'Shape'"]);
+
+    let errors = resolve_errors("fun f() { } object f { }");
+    assert_eq!(errors, ["'f' is already declared"]);
+}
+
+#[test]
+fn resolve_duplicate_pattern_bindings() {
+    let errors = resolve_errors("object Point { x: int; y: int; } fun f(p: Point): int { return match p { Point { x = v, y = v } => v }; }");
+    assert_eq!(errors, ["'v' is bound more than once in the same pattern"]);
+}
+
+#[test]
+fn resolve_non_type_as_type() {
+    let errors = resolve_errors("fun f() { } fun main() { let x: f = 1; }");
+    assert_eq!(errors, ["'f' is not a type"]);
+}
+
+#[test]
+fn resolve_suggests_fixes() {
+    let errors = resolve("fun main() { let count = 1; let x = coutn; }", ShadowingLevel::default())
+        .err().expect("Resolution should find errors");
+    let fix = errors[0].fix().expect("A misspelled name should have a fix");
+    assert_eq!(fix.loc(), ident_at("coutn", 36).location);
+    assert_eq!(fix.replacement(), "count");
+
+    let errors = resolve("object Person { } fun main() { let p: Persn? = null; }", ShadowingLevel::default())
+        .err().expect("Resolution should find errors");
+    let fix = errors[0].fix().expect("A misspelled type should have a fix");
+    assert_eq!(fix.replacement(), "Person?");
+
+    let errors = resolve("fun main() { let count = 1; let x = total; }", ShadowingLevel::default())
+        .err().expect("Resolution should find errors");
+    assert!(errors[0].fix().is_none());
+}
@@ -1,5 +1,1149 @@
-use crate::symtab::SymbolTable;
+use std::collections::HashMap;
+use std::mem;
+use crate::analysis::type_infer::{BindingKey, LocalInference};
+use crate::analysis::hir::{block_type, ArrayAccessHIR, AssignmentHIR, BinaryOpHIR, CastHIR, ConditionHIR, FieldAccessHIR, FieldInitHIR, FunCallHIR, FunctionDeclarationHIR, Hir, HirNode, IterableHIR, LengthHIR, LoopHIR, MatchHIR, ObjectLiteralHIR, PatternHIR, ReturnHIR, UnaryOpHIR, VariableDeclarationHIR, VariantHIR};
+use crate::analysis::hir::exhaustiveness::ExhaustivenessChecker;
+use crate::error::source::SourceError;
+use crate::frontend::location::{HasLocation, SourceLocation, SourceRange};
+use crate::literal::Literal;
+use crate::operators::{BinaryOp, UnaryOp};
+use crate::symtab::{Symbol, SymbolTable};
+use crate::types::{ObjectType, Type, VariableDeclarationMode, VariantType};
 
+#[cfg(test)]
+mod test;
+
+/// what is known about the value of a loop while its body is checked
+struct LoopValue {
+    /// the type the loop's value is used as, or unknown if it isn't used as any type in particular
+    expected: Type,
+    /// the type of the values given to `break` so far
+    tp: Type,
+    /// if any `break` gave a value
+    given: bool,
+}
+
+/// Checks the types of a lowered program and fills in the type of every HIR node along the way.
+/// Errors are collected rather than returned. A node whose type can't be worked out is given the
+/// unknown type, which is accepted everywhere, so that one mistake isn't reported over and over
 pub struct TypeChecker {
-    symtab: SymbolTable
+    /// the symbols in scope, starting with the top level declarations
+    symtab: SymbolTable,
+    /// the return type of the function being checked
+    ret_type: Type,
+    /// the values given to `break` in each enclosing loop
+    loop_values: Vec<LoopValue>,
+    /// the types of the bindings declared without one in the function being checked
+    inference: LocalInference,
+    /// errors collected along the way
+    errors: Vec<SourceError>,
+}
+
+impl TypeChecker {
+    pub fn new(symtab: SymbolTable) -> Self {
+        Self {
+            symtab,
+            ret_type: Type::Unit,
+            loop_values: Vec::new(),
+            inference: LocalInference::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn into_errors(self) -> Vec<SourceError> {
+        self.errors
+    }
+
+    /// check a node and everything under it, and record the node's type. Returns that type
+    pub fn check(&mut self, hir: &mut Hir) -> Type {
+        let ty = match hir {
+            Hir::CompilationUnit(node) => {
+                for function in &mut node.inner.functions {
+                    self.check_function(function);
+                }
+
+                Type::Unit
+            }
+            Hir::FunctionDeclaration(node) => {
+                self.check_function(node);
+                node.ty.clone()
+            }
+            Hir::VariableDeclaration(node) => self.check_var_decl(node),
+            Hir::Block(node) => self.check_scoped(&mut node.inner.insts),
+            Hir::Assignment(node) => self.check_assignment(node),
+            Hir::UnaryOp(node) => self.check_unary_op(node),
+            Hir::BinaryOp(node) => self.check_binary_op(node),
+            Hir::Condition(node) => self.check_condition(node, &Type::Unknown),
+            Hir::Loop(node) => self.check_loop(node, &Type::Unknown),
+            Hir::Break(node) => {
+                if let Some(value) = &mut node.inner.value {
+                    self.check_break_value(value);
+                }
+
+                Type::Unit
+            }
+            Hir::Continue(_) => Type::Unit,
+            Hir::Identifier(_) => self.identifier_type(hir),
+            // literals are typed when they are lowered, or by the value they are compared with
+            Hir::Literal(node) => node.ty.clone(),
+            Hir::FunCall(node) => self.check_call(node),
+            Hir::NamedArg(node) => self.check(&mut node.inner.expr),
+            Hir::FieldAccess(node) => self.check_field_access(node),
+            Hir::Return(node) => self.check_return(node),
+            Hir::ArrayAccess(node) => self.check_array_access(node),
+            Hir::ArrayLiteral(node) => {
+                let mut element_type = Type::Unknown;
+                for element in &mut node.inner.elements {
+                    let tp = self.check_expecting(element, &element_type);
+                    match element_type.lub(&tp) {
+                        Some(unified) => element_type = unified,
+                        None => {
+                            let msg = format!("Array elements must all have the same type. Expected '{}' but got '{}'", element_type, tp);
+                            self.error(msg, element.source_range());
+                        }
+                    }
+                }
+
+                Type::Array(element_type.into(), node.inner.elements.len())
+            }
+            Hir::ArrayRepeat(node) => {
+                let element_type = self.check(&mut node.inner.value);
+                Type::Array(element_type.into(), node.inner.count)
+            }
+            Hir::ObjectLiteral(node) => self.check_object_literal(node),
+            Hir::Variant(node) => self.check_variant(node),
+            Hir::Cast(node) => self.check_cast(node),
+            Hir::Length(node) => self.check_length(node),
+            Hir::Iterable(node) => self.check_iterable(node),
+            Hir::Match(node) => self.check_match(node, &Type::Unknown),
+        };
+
+        *hir.ty_mut() = ty.clone();
+        ty
+    }
+
+    fn error<StrT: Into<String>>(&mut self, msg: StrT, loc: SourceRange) {
+        self.errors.push(SourceError::new(msg, loc));
+    }
+
+    /// find the object type behind a value of the given type. Fields can be accessed through
+    /// references, so those are looked through
+    fn object_type<'a>(&'a self, tp: &'a Type) -> Option<&'a ObjectType> {
+        match tp {
+            Type::Reference(inner) => self.object_type(inner),
+            tp => tp.resolve(&|name| self.symtab.lookup_object(name)),
+        }
+    }
+
+    /// the type of a field on the given object, if it has one
+    fn field_type(&self, object: &ObjectType, field: &str) -> Option<Type> {
+        let lookup = |name: &str| self.symtab.lookup_object(name);
+        object.field_type(field, &lookup)
+    }
+
+    /// the binding an identifier refers to, if it's a local binding that is being inferred
+    fn binding_key(&self, hir: &Hir) -> Option<BindingKey> {
+        let Hir::Identifier(node) = hir else {
+            return None;
+        };
+
+        if let Some(symbol) = node.inner.symbol {
+            return Some(BindingKey::Symbol(symbol));
+        }
+
+        self.symtab.symbol_defined(&node.inner.name)
+            .map(|symbol| BindingKey::Temporary(symbol.name.clone(), symbol.loc))
+    }
+
+    /// the type of a binding. A binding declared without a type has the type inferred for it so
+    /// far, which a use before may have settled on
+    fn identifier_type(&self, hir: &Hir) -> Type {
+        let Hir::Identifier(node) = hir else {
+            return Type::Unknown;
+        };
+
+        self.binding_key(hir)
+            .and_then(|key| self.inference.inferred_type(&key))
+            .cloned()
+            .unwrap_or_else(|| self.symtab.symbol_type_or_unknown(&node.inner.name))
+    }
+
+    /// record that a value is used where the expected type is needed, which tells the type of
+    /// the bindings it was computed from
+    fn constrain(&mut self, hir: &Hir, expected: &Type) {
+        match hir {
+            Hir::Identifier(_) => {
+                if let Some(key) = self.binding_key(hir) {
+                    self.inference.constrain(&key, expected);
+                }
+            }
+            Hir::UnaryOp(node) if matches!(node.inner.op, UnaryOp::Neg | UnaryOp::BitNeg) => self.constrain(&node.inner.child, expected),
+            Hir::BinaryOp(node) => match node.inner.op {
+                BinaryOp::Plus | BinaryOp::Minus | BinaryOp::Times | BinaryOp::Divides | BinaryOp::Modulo | BinaryOp::Exp
+                | BinaryOp::BitAnd | BinaryOp::BitOr | BinaryOp::BitXor => {
+                    self.constrain(&node.inner.lhs, expected);
+                    self.constrain(&node.inner.rhs, expected);
+                }
+                BinaryOp::ShiftLeft | BinaryOp::ShiftRight => self.constrain(&node.inner.lhs, expected),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// the defaulted bindings an initializer is computed from, when it is computed only from
+    /// integer literals with no suffix and bindings that are themselves still defaulted
+    fn defaulted_sources(&self, init: &Hir) -> Option<Vec<BindingKey>> {
+        match init {
+            Hir::Literal(node) if matches!(node.inner, Literal::Int(_)) => Some(Vec::new()),
+            Hir::Identifier(_) => self.binding_key(init)
+                .filter(|key| self.inference.is_defaulted(key))
+                .map(|key| vec![key]),
+            Hir::UnaryOp(node) if matches!(node.inner.op, UnaryOp::Neg | UnaryOp::BitNeg) => self.defaulted_sources(&node.inner.child),
+            Hir::BinaryOp(node) => match node.inner.op {
+                BinaryOp::Plus | BinaryOp::Minus | BinaryOp::Times | BinaryOp::Divides | BinaryOp::Modulo | BinaryOp::Exp
+                | BinaryOp::BitAnd | BinaryOp::BitOr | BinaryOp::BitXor => {
+                    let mut sources = self.defaulted_sources(&node.inner.lhs)?;
+                    sources.extend(self.defaulted_sources(&node.inner.rhs)?);
+                    Some(sources)
+                }
+                BinaryOp::ShiftLeft | BinaryOp::ShiftRight => self.defaulted_sources(&node.inner.lhs),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn declare(&mut self, name: &str, tp: Type, loc: SourceRange) {
+        self.symtab.add_symbol(Symbol::new(name, tp, loc, VariableDeclarationMode::Immutable));
+    }
+
+    /// check a sequence of statements in a scope of their own
+    fn check_scoped(&mut self, stmts: &mut [Hir]) -> Type {
+        self.symtab.push_scope();
+        for stmt in stmts.iter_mut() {
+            self.check(stmt);
+        }
+
+        self.symtab.pop_scope();
+        block_type(stmts)
+    }
+
+    /// check a sequence of statements in a scope of their own, where the value of the last one is
+    /// used where a value of the expected type is needed
+    fn check_scoped_expecting(&mut self, stmts: &mut [Hir], expected: &Type) -> Type {
+        self.symtab.push_scope();
+        if let Some((last, rest)) = stmts.split_last_mut() {
+            for stmt in rest {
+                self.check(stmt);
+            }
+
+            self.check_expecting(last, expected);
+        }
+
+        self.symtab.pop_scope();
+        block_type(stmts)
+    }
+
+    /// a loop evaluates to the values given to its `break`s. Every loop can also finish without
+    /// one, like a `while` does once its condition is false, so a loop that is given values
+    /// evaluates to an optional of them
+    fn check_loop(&mut self, node: &mut HirNode<LoopHIR>, expected: &Type) -> Type {
+        let expected = match expected {
+            Type::Optional(inner) => inner.as_ref().clone(),
+            tp => tp.clone(),
+        };
+
+        self.loop_values.push(LoopValue { expected, tp: Type::Unknown, given: false });
+        self.check_scoped(&mut node.inner.stmts);
+        match self.loop_values.pop() {
+            Some(LoopValue { tp, given: true, .. }) => Type::optional_of(tp),
+            _ => Type::Unit,
+        }
+    }
+
+    /// check a value given to `break` against the values given to the enclosing loop before, or
+    /// the type the loop's value is used as if this is the first
+    fn check_break_value(&mut self, value: &mut Hir) {
+        let Some(loop_value) = self.loop_values.last() else {
+            self.check(value);
+            return;
+        };
+
+        let given_before = loop_value.tp.clone();
+        let expected = match given_before {
+            Type::Unknown => loop_value.expected.clone(),
+            ref tp => tp.clone(),
+        };
+
+        let tp = self.check_expecting(value, &expected);
+        let Some(unified) = given_before.lub(&tp) else {
+            let msg = format!("Loop was given a value of type '{}' before, but this 'break' gives it '{}'", given_before, tp);
+            self.error(msg, value.source_range());
+            return;
+        };
+
+        let loop_value = self.loop_values.last_mut().unwrap();
+        loop_value.tp = unified;
+        loop_value.given = true;
+    }
+
+    /// make the operands of a binary operator agree where they can. A side built from integer
+    /// literals takes the type of the other side without telling anything about it, and when both
+    /// sides are, they have to end up with the same type
+    fn join_operands(&mut self, node: &mut HirNode<BinaryOpHIR>, lhs_type: Type, rhs_type: Type) -> (Type, Type) {
+        // shift amounts are independent of the shifted value
+        if matches!(node.inner.op, BinaryOp::ShiftLeft | BinaryOp::ShiftRight) {
+            return (lhs_type, rhs_type);
+        }
+
+        match (self.defaulted_sources(&node.inner.lhs), self.defaulted_sources(&node.inner.rhs)) {
+            (Some(mut lhs_sources), Some(rhs_sources)) => {
+                lhs_sources.extend(rhs_sources);
+                self.inference.link(&lhs_sources);
+                (lhs_type, rhs_type)
+            }
+            (Some(_), None) => (self.expect(&mut node.inner.lhs, &lhs_type, &rhs_type), rhs_type),
+            (None, Some(_)) => {
+                let rhs_type = self.expect(&mut node.inner.rhs, &rhs_type, &lhs_type);
+                (lhs_type, rhs_type)
+            }
+            (None, None) => {
+                let rhs_type = self.expect(&mut node.inner.rhs, &rhs_type, &lhs_type);
+                let lhs_type = self.expect(&mut node.inner.lhs, &lhs_type, &rhs_type);
+                (lhs_type, rhs_type)
+            }
+        }
+    }
+
+    /// check a value that is used where a value of the expected type is needed. An integer literal
+    /// without a suffix takes on whichever integer type is expected of it, and has to fit in it
+    fn check_expecting(&mut self, hir: &mut Hir, expected: &Type) -> Type {
+        let tp = match hir {
+            // the value of a loop comes from its breaks, the value of a block from its last
+            // statement and the value of an 'if' or a match from its branches, so those are what
+            // is expected to have the type
+            Hir::Loop(node) => {
+                node.ty = self.check_loop(node, expected);
+                node.ty.clone()
+            }
+            Hir::Block(node) => {
+                node.ty = self.check_scoped_expecting(&mut node.inner.insts, expected);
+                node.ty.clone()
+            }
+            Hir::Condition(node) => {
+                node.ty = self.check_condition(node, expected);
+                node.ty.clone()
+            }
+            Hir::Match(node) => {
+                node.ty = self.check_match(node, expected);
+                node.ty.clone()
+            }
+            _ => self.check(hir),
+        };
+        self.expect(hir, &tp, expected)
+    }
+
+    /// use an already checked value of the given type where the expected type is needed. Gives
+    /// the type the value ends up with
+    fn expect(&mut self, hir: &mut Hir, tp: &Type, expected: &Type) -> Type {
+        self.constrain(hir, expected);
+        let expected = match expected {
+            Type::Optional(inner) => inner.as_ref(),
+            tp => tp,
+        };
+
+        if let Some(tp) = self.expect_elements(hir, expected) {
+            return tp;
+        }
+
+        if expected.is_integer() && adapt_int_literal(hir, expected) {
+            self.check_literal_range(hir, expected);
+            return expected.clone();
+        }
+
+        tp.clone()
+    }
+
+    /// check that the integer literals an adapted value is computed from fit in the type they
+    /// were given
+    fn check_literal_range(&mut self, hir: &Hir, tp: &Type) {
+        if let Hir::BinaryOp(node) = hir {
+            self.check_literal_range(&node.inner.lhs, tp);
+            if !matches!(node.inner.op, BinaryOp::ShiftLeft | BinaryOp::ShiftRight) {
+                self.check_literal_range(&node.inner.rhs, tp);
+            }
+
+            return;
+        }
+
+        if int_literal_value(hir, tp).is_some_and(|value| !fits_in(value, tp)) {
+            let msg = format!("Literal {} does not fit in '{}'", int_literal_text(hir), tp);
+            self.error(msg, hir.source_range());
+        }
+    }
+
+    /// use an already checked array literal where an array is needed, so that the elements are
+    /// used where the expected element type is needed. Gives the array's type, or nothing if the
+    /// value isn't an array literal or no array is expected
+    fn expect_elements(&mut self, hir: &mut Hir, expected: &Type) -> Option<Type> {
+        let (Type::Array(expected_element, _) | Type::View(expected_element)) = expected else {
+            return None;
+        };
+
+        let ty = match hir {
+            Hir::ArrayLiteral(node) => {
+                let mut element_type = Type::Unknown;
+                for element in &mut node.inner.elements {
+                    let tp = element.ty().clone();
+                    let tp = self.expect(element, &tp, expected_element);
+                    // elements that don't agree were reported when the literal was checked
+                    element_type = element_type.lub(&tp)?;
+                }
+
+                Type::Array(element_type.into(), node.inner.elements.len())
+            }
+            Hir::ArrayRepeat(node) => {
+                let tp = node.inner.value.ty().clone();
+                let tp = self.expect(&mut node.inner.value, &tp, expected_element);
+                Type::Array(tp.into(), node.inner.count)
+            }
+            _ => return None,
+        };
+
+        *hir.ty_mut() = ty.clone();
+        Some(ty)
+    }
+
+    fn check_function(&mut self, node: &mut HirNode<FunctionDeclarationHIR>) {
+        let Type::Function(fun) = &node.ty else {
+            panic!("Function declaration '{}' does not have a function type", node.inner.name)
+        };
+
+        let ret_type = mem::replace(&mut self.ret_type, fun.ret.as_ref().clone());
+        let inference = mem::replace(&mut self.inference, LocalInference::new());
+
+        // the body is gone through twice. The first time collects how the bindings without a
+        // type are used to infer their types, and the second checks the body with those types
+        let errors = mem::take(&mut self.errors);
+        self.check_body(node);
+        self.errors = errors;
+
+        let inference_errors = self.inference.settle();
+        self.errors.extend(inference_errors);
+        self.check_body(node);
+
+        self.inference = inference;
+        self.ret_type = ret_type;
+    }
+
+    fn check_body(&mut self, node: &mut HirNode<FunctionDeclarationHIR>) {
+        self.symtab.push_scope();
+        for param in &node.inner.params {
+            self.declare(&param.name, param.tp.as_ref().clone(), node.loc);
+        }
+
+        self.check_scoped(&mut node.inner.body);
+        self.symtab.pop_scope();
+    }
+
+    fn check_var_decl(&mut self, node: &mut HirNode<VariableDeclarationHIR>) -> Type {
+        // the type of a binding declared without one is inferred the first time the body is
+        // checked, and used as if it had been declared the second time
+        let key = match node.inner.symbol {
+            Some(symbol) => BindingKey::Symbol(symbol),
+            None => BindingKey::Temporary(node.inner.name.clone(), node.loc),
+        };
+        let declared = match self.inference.binding_type(&key) {
+            Some(inferred) => inferred.clone(),
+            None => node.ty.clone(),
+        };
+
+        let init_type = self.check_expecting(&mut node.inner.initializer, &declared);
+        if matches!(declared, Type::Unknown) {
+            let defaulted = self.defaulted_sources(&node.inner.initializer);
+            self.inference.declare(key, &node.inner.name, node.loc, init_type.clone(), defaulted);
+        }
+
+        if !init_type.is_assignable_to(&declared) {
+            let msg = format!("Cannot initialize '{}' of type '{}' with a value of type '{}'", node.inner.name, declared, init_type);
+            self.error(msg, node.inner.initializer.source_range());
+        }
+
+        // without a declared type, the binding takes the type of its initializer
+        let tp = match declared {
+            Type::Unknown => init_type,
+            declared => declared,
+        };
+
+        self.declare(&node.inner.name, tp.clone(), node.loc);
+        tp
+    }
+
+    fn check_assignment(&mut self, node: &mut HirNode<AssignmentHIR>) -> Type {
+        let place_type = self.check(&mut node.inner.lhs);
+        if !node.inner.lhs.is_place() {
+            let msg = "Only variables, fields, array elements and dereferenced references can be assigned to";
+            self.error(msg, node.inner.lhs.source_range());
+        }
+
+        let value_type = self.check_expecting(&mut node.inner.rhs, &place_type);
+        self.constrain(&node.inner.lhs, &value_type);
+        if !value_type.is_assignable_to(&place_type) {
+            let msg = format!("Cannot assign a value of type '{}' to a place of type '{}'", value_type, place_type);
+            self.error(msg, node.inner.rhs.source_range());
+        }
+
+        Type::Unit
+    }
+
+    fn check_unary_op(&mut self, node: &mut HirNode<UnaryOpHIR>) -> Type {
+        let child_type = self.check(&mut node.inner.child);
+        if matches!(child_type, Type::Unknown) {
+            return match node.inner.op {
+                UnaryOp::Not => Type::Boolean,
+                _ => Type::Unknown,
+            };
+        }
+
+        let tp = match (&node.inner.op, &child_type) {
+            (UnaryOp::Neg, Type::Int | Type::Long | Type::Double) => Some(child_type.clone()),
+            (UnaryOp::BitNeg, tp) if tp.is_integer() => Some(child_type.clone()),
+            (UnaryOp::Not, Type::Boolean) => Some(Type::Boolean),
+            (UnaryOp::Ref, tp) => Some(Type::Reference(tp.clone().into())),
+            (UnaryOp::Deref, Type::Reference(inner)) => Some(inner.as_ref().clone()),
+            _ => None,
+        };
+
+        tp.unwrap_or_else(|| {
+            let msg = format!("Cannot apply '{}' to a value of type '{}'", node.inner.op.symbol(), child_type);
+            self.error(msg, node.loc);
+            Type::Unknown
+        })
+    }
+
+    fn check_binary_op(&mut self, node: &mut HirNode<BinaryOpHIR>) -> Type {
+        let op = node.inner.op.clone();
+        if op == BinaryOp::NullCoalesce {
+            return self.check_coalesce(node);
+        }
+
+        let lhs_type = self.check(&mut node.inner.lhs);
+        let rhs_type = self.check(&mut node.inner.rhs);
+        let (lhs_type, rhs_type) = self.join_operands(node, lhs_type, rhs_type);
+
+        let (accepts, same_types): (fn(&Type) -> bool, bool) = match op {
+            BinaryOp::Plus | BinaryOp::Minus | BinaryOp::Times | BinaryOp::Divides | BinaryOp::Modulo | BinaryOp::Exp => (is_numeric, true),
+            BinaryOp::BitAnd | BinaryOp::BitOr | BinaryOp::BitXor => (Type::is_integer, true),
+            // the shift amount doesn't have to match the shifted value
+            BinaryOp::ShiftLeft | BinaryOp::ShiftRight => (Type::is_integer, false),
+            BinaryOp::Gt | BinaryOp::Lt | BinaryOp::Gte | BinaryOp::Lte => (|tp| is_numeric(tp) || *tp == Type::Char, true),
+            BinaryOp::Eq | BinaryOp::Neq => (|_| true, true),
+            BinaryOp::And | BinaryOp::Or => (|tp| *tp == Type::Boolean, true),
+            BinaryOp::NullCoalesce | BinaryOp::Access | BinaryOp::ChainedAccess => (|_| true, false),
+        };
+
+        let mut valid = true;
+        for (tp, operand) in [(&lhs_type, &node.inner.lhs), (&rhs_type, &node.inner.rhs)] {
+            if !matches!(tp, Type::Unknown) && !accepts(tp) {
+                let msg = format!("Cannot apply '{}' to a value of type '{}'", op.symbol(), tp);
+                self.errors.push(SourceError::new(msg, operand.source_range()));
+                valid = false;
+            }
+        }
+
+        // values compared for equality only need a type in common, so an optional can be compared
+        // with `null` or with a value it may hold
+        let agree = match op {
+            BinaryOp::Eq | BinaryOp::Neq => lhs_type.lub(&rhs_type).is_some(),
+            _ => lhs_type.is_same_as(&rhs_type),
+        };
+
+        if valid && same_types && !agree {
+            let msg = format!("The operands of '{}' must have the same type, but got '{}' and '{}'", op.symbol(), lhs_type, rhs_type);
+            self.error(msg, node.loc);
+            valid = false;
+        }
+
+        match op {
+            BinaryOp::Gt | BinaryOp::Lt | BinaryOp::Gte | BinaryOp::Lte | BinaryOp::Eq | BinaryOp::Neq | BinaryOp::And | BinaryOp::Or => Type::Boolean,
+            _ if !valid => Type::Unknown,
+            BinaryOp::ShiftLeft | BinaryOp::ShiftRight => lhs_type,
+            _ => lhs_type.lub(&rhs_type).unwrap_or(Type::Unknown),
+        }
+    }
+
+    /// `lhs ?? rhs` unwraps the optional on the left, with the right as the default value
+    fn check_coalesce(&mut self, node: &mut HirNode<BinaryOpHIR>) -> Type {
+        let lhs_type = self.check(&mut node.inner.lhs);
+        let inner = match &lhs_type {
+            Type::Optional(inner) => inner.as_ref().clone(),
+            Type::Unknown => Type::Unknown,
+            tp => {
+                let msg = format!("The left side of '??' must be an optional value, but has type '{}'", tp);
+                self.error(msg, node.inner.lhs.source_range());
+                Type::Unknown
+            }
+        };
+
+        let rhs_type = self.check_expecting(&mut node.inner.rhs, &inner);
+        match (&inner, &rhs_type) {
+            (Type::Unknown, tp) => tp.clone(),
+            (inner, Type::Unknown) => inner.clone(),
+            // the default may itself be optional, which makes the whole expression optional
+            (inner, Type::Optional(default)) if default.is_assignable_to(inner) => rhs_type.clone(),
+            (inner, tp) if tp.is_assignable_to(inner) => inner.clone(),
+            (inner, tp) => {
+                let msg = format!("The default value for '??' has type '{}', but the optional holds '{}'", tp, inner);
+                self.error(msg, node.inner.rhs.source_range());
+                inner.clone()
+            }
+        }
+    }
+
+    fn check_condition(&mut self, node: &mut HirNode<ConditionHIR>, expected: &Type) -> Type {
+        let cond_type = self.check(&mut node.inner.cond);
+        if !matches!(cond_type, Type::Unknown | Type::Boolean) {
+            let msg = format!("A condition must be a bool, but has type '{}'", cond_type);
+            self.error(msg, node.inner.cond.source_range());
+        }
+
+        if node.inner.false_branch.is_empty() {
+            let true_type = self.check_scoped(&mut node.inner.true_branch);
+            if !matches!(true_type, Type::Unknown | Type::Unit) {
+                let msg = format!("An 'if' without an 'else' must evaluate to (), but its branch has type '{}'", true_type);
+                self.error(msg, node.inner.true_branch.last().unwrap().source_range());
+            }
+
+            return Type::Unit;
+        }
+
+        let true_type = self.check_scoped_expecting(&mut node.inner.true_branch, expected);
+        let false_type = self.check_scoped_expecting(&mut node.inner.false_branch, expected);
+        true_type.lub(&false_type).unwrap_or_else(|| {
+            let msg = format!("The branches of an 'if' have different types. Expected '{}' but got '{}'", true_type, false_type);
+            // an empty branch is unit, so at most one of the branches is empty
+            let err = SourceError::new(msg, node.inner.false_branch.last().map_or(node.loc, |last| last.source_range()))
+                .with_context_note(node.inner.true_branch.last().map_or(node.loc, |last| last.source_range()), "expected because of this");
+            self.errors.push(err);
+            Type::Unknown
+        })
+    }
+
+    fn check_call(&mut self, node: &mut HirNode<FunCallHIR>) -> Type {
+        let callee_type = self.check(&mut node.inner.callee);
+
+        // a method called with '?.' is looked up on the optional's value, and so is its result
+        let chained = matches!(node.inner.callee.as_ref(), Hir::FieldAccess(access) if access.inner.chained);
+        let callee_type = match callee_type {
+            Type::Optional(inner) if chained => *inner,
+            tp => tp,
+        };
+
+        let fun = match callee_type {
+            Type::Function(fun) => fun,
+            tp => {
+                if !matches!(tp, Type::Unknown) {
+                    let msg = format!("Cannot call a value of type '{}'", tp);
+                    self.error(msg, node.inner.callee.source_range());
+                }
+
+                for arg in &mut node.inner.args {
+                    self.check(arg);
+                }
+
+                return Type::Unknown;
+            }
+        };
+
+        if node.inner.args.len() != fun.args.len() {
+            let noun = if fun.args.len() == 1 { "argument" } else { "arguments" };
+            let msg = format!("Expected {} {}, but got {}", fun.args.len(), noun, node.inner.args.len());
+            self.error(msg, node.loc);
+        }
+
+        // positional arguments go to the parameters in order, and named ones to the parameter with
+        // that name
+        for (idx, arg) in node.inner.args.iter_mut().enumerate() {
+            let param = match arg {
+                Hir::NamedArg(named) => {
+                    let param = fun.args.iter().find(|param| param.name == named.inner.name);
+                    if param.is_none() {
+                        let msg = format!("No parameter named '{}'", named.inner.name);
+                        self.error(msg, named.loc);
+                    }
+
+                    param
+                }
+                _ => fun.args.get(idx),
+            };
+
+            let Some(param) = param else {
+                self.check(arg);
+                continue;
+            };
+
+            let arg_type = self.check_expecting(arg, &param.tp);
+            if !arg_type.is_assignable_to(&param.tp) {
+                let msg = format!("Parameter '{}' has type '{}', but the argument has type '{}'", param.name, param.tp, arg_type);
+                self.error(msg, arg.source_range());
+            }
+        }
+
+        match fun.ret.as_ref().clone() {
+            tp if chained => Type::optional_of(tp),
+            tp => tp,
+        }
+    }
+
+    fn check_field_access(&mut self, node: &mut HirNode<FieldAccessHIR>) -> Type {
+        let object_type = self.check(&mut node.inner.object);
+        let field = &node.inner.field;
+        let (accessed, chained) = match &object_type {
+            Type::Unknown => return Type::Unknown,
+            Type::Optional(inner) if node.inner.chained => (inner.as_ref(), true),
+            Type::Optional(_) => {
+                let msg = format!("Cannot access field '{}' on optional type '{}'. Use '?.' instead", field, object_type);
+                self.error(msg, node.loc);
+                return Type::Unknown;
+            }
+            tp if node.inner.chained => {
+                let msg = format!("'?.' can only be used on optional values, but the object has type '{}'. Use '.' instead", tp);
+                self.error(msg, node.loc);
+                return Type::Unknown;
+            }
+            tp => (tp, false),
+        };
+
+        let field_type = match self.object_type(accessed) {
+            Some(object) => self.field_type(object, field).ok_or_else(|| {
+                let lookup = |name: &str| self.symtab.lookup_object(name);
+                let fields = object.field_names(&lookup);
+                SourceError::new(format!("No field '{}' on type '{}'", field, object.name), node.loc)
+                    .with_closest_match(field, field_name_range(node.loc, field), fields.into_iter().map(|(name, _)| name))
+            }),
+            None if matches!(accessed, Type::Unknown) => Ok(Type::Unknown),
+            None => Err(SourceError::new(format!("Type '{}' has no fields", accessed), node.loc)),
+        };
+
+        match field_type {
+            Ok(tp) if chained => Type::optional_of(tp),
+            Ok(tp) => tp,
+            Err(err) => {
+                self.errors.push(err);
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_return(&mut self, node: &mut HirNode<ReturnHIR>) -> Type {
+        let expected = self.ret_type.clone();
+        let value_type = self.check_expecting(&mut node.inner.value, &expected);
+        if !value_type.is_assignable_to(&expected) {
+            let msg = format!("The function returns '{}', but this returns a value of type '{}'", expected, value_type);
+            self.error(msg, node.inner.value.source_range());
+        }
+
+        value_type
+    }
+
+    fn check_array_access(&mut self, node: &mut HirNode<ArrayAccessHIR>) -> Type {
+        let accessed_type = self.check(&mut node.inner.accessed);
+        let offset_type = self.check(&mut node.inner.offset);
+        if !matches!(offset_type, Type::Unknown) && !offset_type.is_integer() {
+            let msg = format!("Array index must be an integer, but got '{}'", offset_type);
+            self.error(msg, node.inner.offset.source_range());
+        }
+
+        match accessed_type {
+            Type::Array(element, _) | Type::View(element) => *element,
+            Type::Unknown => Type::Unknown,
+            other => {
+                let msg = format!("Cannot index into a value of type '{}'", other);
+                self.error(msg, node.inner.accessed.source_range());
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_object_literal(&mut self, node: &mut HirNode<ObjectLiteralHIR>) -> Type {
+        let declared = match &node.ty {
+            Type::UserDefined(name) => self.symtab.lookup_object(name)
+                .map(|object| object.props.keys()
+                    .chain(object.comps.keys())
+                    .map(|field| (field.clone(), self.field_type(object, field).unwrap_or(Type::Unknown)))
+                    .collect::<Vec<_>>())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        let constructed = node.ty.to_string();
+        let expected = self.expected_field_types(&node.inner.fields, &declared, "type", &constructed, node.loc);
+        for (field, expected) in node.inner.fields.iter_mut().zip(expected) {
+            let value_type = self.check_expecting(&mut field.value, &expected);
+            if !value_type.is_assignable_to(&expected) {
+                let msg = format!("Field '{}' has type '{}', but is given a value of type '{}'", field.name, expected, value_type);
+                self.error(msg, field.value.source_range());
+            }
+        }
+
+        node.ty.clone()
+    }
+
+    fn check_variant(&mut self, node: &mut HirNode<VariantHIR>) -> Type {
+        let payload = match &node.ty {
+            Type::UserDefined(name) => self.symtab.lookup_enum(name)
+                .and_then(|enum_type| enum_type.variant(&node.inner.variant))
+                .map(|variant| variant.payload.clone()),
+            _ => None,
+        };
+
+        let expected = match &payload {
+            Some(VariantType::Tuple(values)) => values.clone(),
+            Some(VariantType::Struct(fields)) => {
+                let declared = fields.iter()
+                    .map(|(field, tp)| (field.clone(), tp.as_ref().clone()))
+                    .collect::<Vec<_>>();
+                let constructed = format!("{}.{}", node.ty, node.inner.variant);
+                self.expected_field_types(&node.inner.values, &declared, "variant", &constructed, node.loc)
+            }
+            _ => Vec::new(),
+        };
+
+        for (idx, value) in node.inner.values.iter_mut().enumerate() {
+            let expected = expected.get(idx).cloned().unwrap_or(Type::Unknown);
+            let value_type = self.check_expecting(&mut value.value, &expected);
+            if !value_type.is_assignable_to(&expected) {
+                let msg = format!("Value '{}' of variant '{}' has type '{}', but is given a value of type '{}'", value.name, node.inner.variant, expected, value_type);
+                self.error(msg, value.value.source_range());
+            }
+        }
+
+        node.ty.clone()
+    }
+
+    /// the type each of the fields a value is constructed with is expected to have. Fields the
+    /// value doesn't have and fields given more than once are reported and expected to be of
+    /// unknown type. Every field left out is reported in a single error
+    fn expected_field_types(&mut self, fields: &[FieldInitHIR], declared: &[(String, Type)], kind: &str, constructed: &str, loc: SourceRange) -> Vec<Type> {
+        let mut initialized = HashMap::<&str, SourceRange>::new();
+        let mut expected = Vec::new();
+        for field in fields {
+            let Some((_, tp)) = declared.iter().find(|(name, _)| *name == field.name) else {
+                let msg = format!("No field '{}' on {} '{}'", field.name, kind, constructed);
+                let err = SourceError::new(msg, field.name_loc)
+                    .with_closest_match(&field.name, field.name_loc, declared.iter().map(|(name, _)| name.as_str()));
+                self.errors.push(err);
+                expected.push(Type::Unknown);
+                continue;
+            };
+
+            if let Some(first_loc) = initialized.get(field.name.as_str()) {
+                let msg = format!("Field '{}' is initialized more than once", field.name);
+                let err = SourceError::new(msg, field.name_loc)
+                    .with_context_note(*first_loc, "first initialized here");
+                self.errors.push(err);
+                expected.push(Type::Unknown);
+                continue;
+            }
+
+            initialized.insert(&field.name, field.name_loc);
+            expected.push(tp.clone());
+        }
+
+        let mut missing = declared.iter()
+            .filter(|(name, _)| !initialized.contains_key(name.as_str()))
+            .map(|(name, _)| format!("'{}'", name))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            missing.sort();
+            let noun = if missing.len() == 1 { "field" } else { "fields" };
+            let msg = format!("Missing {} {} in construction of '{}'", noun, missing.join(", "), constructed);
+            self.error(msg, loc);
+        }
+
+        expected
+    }
+
+    fn check_cast(&mut self, node: &mut HirNode<CastHIR>) -> Type {
+        let target = node.ty.clone();
+        let expr_type = self.check_expecting(&mut node.inner.expr, &target);
+        let lookup = |name: &str| self.symtab.lookup_object(name);
+        if !matches!(expr_type, Type::Unknown) && !expr_type.casts_to(&target, &lookup) {
+            let msg = format!("Cannot cast a value of type '{}' to '{}'", expr_type, target);
+            self.error(msg, node.loc);
+        }
+
+        target
+    }
+
+    fn check_length(&mut self, node: &mut HirNode<LengthHIR>) -> Type {
+        let array_type = self.check(&mut node.inner.array);
+        if !matches!(array_type, Type::Unknown | Type::Array(..) | Type::View(_)) {
+            let msg = format!("Cannot take the length of a value of type '{}'", array_type);
+            self.error(msg, node.inner.array.source_range());
+        }
+
+        Type::Int
+    }
+
+    /// a value that cannot be iterated is reported once here, and stands in as unknown for the
+    /// length checks and element accesses the for loop was lowered into
+    fn check_iterable(&mut self, node: &mut HirNode<IterableHIR>) -> Type {
+        let value_type = self.check(&mut node.inner.value);
+        match value_type {
+            Type::Array(..) | Type::View(_) | Type::Unknown => value_type,
+            other => {
+                let msg = format!("Cannot iterate over a value of type '{}'", other);
+                self.error(msg, node.loc);
+                Type::Unknown
+            }
+        }
+    }
+
+    fn check_match(&mut self, node: &mut HirNode<MatchHIR>, expected: &Type) -> Type {
+        let subject = self.check(&mut node.inner.scrutinee);
+        let mut ty = Type::Unknown;
+        let mut typed_arm_loc = None;
+        let mut patterns_match = true;
+        for arm in &mut node.inner.arms {
+            self.symtab.push_scope();
+            let error_count = self.errors.len();
+            self.check_pattern(&arm.inner.pattern, &subject);
+            patterns_match &= self.errors.len() == error_count;
+            if let Some(guard) = &mut arm.inner.guard {
+                let guard_type = self.check(guard);
+                if !matches!(guard_type, Type::Unknown | Type::Boolean) {
+                    let msg = format!("A match guard must be a bool, but has type '{}'", guard_type);
+                    self.error(msg, guard.source_range());
+                }
+            }
+
+            let body_type = match arm.inner.body.as_ref() {
+                Hir::Return(_) | Hir::Break(_) | Hir::Continue(_) => {
+                    self.check(&mut arm.inner.body);
+                    Type::Unknown
+                }
+                _ => self.check_expecting(&mut arm.inner.body, expected),
+            };
+            self.symtab.pop_scope();
+
+            arm.ty = body_type.clone();
+            match ty.lub(&body_type) {
+                Some(unified) => {
+                    if typed_arm_loc.is_none() && !matches!(body_type, Type::Unknown) {
+                        typed_arm_loc = Some(arm.inner.body.source_range());
+                    }
+
+                    ty = unified;
+                }
+                None => {
+                    let msg = format!("Match arms have different types. Expected '{}' but got '{}'", ty, body_type);
+                    let mut err = SourceError::new(msg, arm.inner.body.source_range());
+                    if let Some(first_loc) = typed_arm_loc {
+                        err = err.with_context_note(first_loc, "expected because of this");
+                    }
+
+                    self.errors.push(err);
+                }
+            }
+        }
+
+        // which values the arms cover only makes sense once every pattern fits the subject
+        if patterns_match {
+            let errors = ExhaustivenessChecker::new(&self.symtab).check(&node.inner.arms, node.loc);
+            self.errors.extend(errors);
+        }
+
+        ty
+    }
+
+    /// check that a pattern can match a value of the given type, and declare the bindings it
+    /// makes
+    fn check_pattern(&mut self, pattern: &HirNode<PatternHIR>, tp: &Type) {
+        match &pattern.inner {
+            PatternHIR::Wildcard => {}
+            PatternHIR::Binding(name) => self.declare(name, tp.clone(), pattern.loc),
+            PatternHIR::Literal(lit) => self.check_literal_pattern(lit, tp, pattern.loc),
+            PatternHIR::Some(inner) => {
+                let inner_type = match tp {
+                    Type::Optional(inner) => inner.as_ref().clone(),
+                    Type::Unknown => Type::Unknown,
+                    tp => {
+                        let msg = format!("'some' patterns only match optional values, but the value has type '{}'", tp);
+                        self.error(msg, pattern.loc);
+                        Type::Unknown
+                    }
+                };
+                self.check_pattern(inner, &inner_type);
+            }
+            PatternHIR::Object { name, fields } => {
+                self.check_pattern_subject(name, tp, pattern.loc);
+                let object = self.symtab.lookup_object(name).cloned();
+                for (field, pattern) in fields {
+                    let field_type = object.as_ref()
+                        .and_then(|object| self.field_type(object, field))
+                        .unwrap_or(Type::Unknown);
+                    self.check_pattern(pattern, &field_type);
+                }
+            }
+            PatternHIR::Variant { enum_name, variant, values } => {
+                self.check_pattern_subject(enum_name, tp, pattern.loc);
+                let payload = self.symtab.lookup_enum(enum_name)
+                    .and_then(|enum_type| enum_type.variant(variant))
+                    .map(|variant| variant.payload.clone());
+                for (name, pattern) in values {
+                    let value_type = match &payload {
+                        Some(VariantType::Tuple(values)) => name.parse::<usize>().ok()
+                            .and_then(|idx| values.get(idx))
+                            .cloned(),
+                        Some(VariantType::Struct(fields)) => fields.get(name).map(|tp| tp.as_ref().clone()),
+                        _ => None,
+                    }.unwrap_or(Type::Unknown);
+                    self.check_pattern(pattern, &value_type);
+                }
+            }
+        }
+    }
+
+    /// check that a literal pattern can match a value of the given type. Integer literals without
+    /// a suffix match any integer type
+    fn check_literal_pattern(&mut self, lit: &Literal, tp: &Type, loc: SourceRange) {
+        let lit_type = Type::from(lit.clone());
+        match (lit, tp) {
+            (_, Type::Unknown) | (Literal::Null, Type::Optional(_)) => {}
+            (Literal::Null, tp) => {
+                let msg = format!("Cannot match null against a value of type '{}', which is not optional", tp);
+                self.error(msg, loc);
+            }
+            (Literal::Int(_), tp) if tp.is_integer() => {}
+            (_, tp) if lit_type == *tp => {}
+            (_, tp) => {
+                let msg = format!("A pattern of type '{}' cannot match a value of type '{}'", lit_type, tp);
+                self.error(msg, loc);
+            }
+        }
+    }
+
+    /// check that a pattern for the named object or enum can match a value of the given type
+    fn check_pattern_subject(&mut self, name: &str, tp: &Type, loc: SourceRange) {
+        let subject = match tp {
+            Type::Unknown => return,
+            Type::Object(object) => &object.name,
+            Type::Enum(enum_type) => &enum_type.name,
+            Type::UserDefined(subject) => subject,
+            _ => "",
+        };
+
+        if subject != name {
+            let msg = format!("A pattern for '{}' cannot match a value of type '{}'", name, tp);
+            self.error(msg, loc);
+        }
+    }
+}
+
+/// give an integer literal with no suffix the expected integer type, along with the operators
+/// computing a value from such literals alone. Returns if the value was computed that way
+fn adapt_int_literal(hir: &mut Hir, expected: &Type) -> bool {
+    if !is_int_literal_expr(hir) {
+        return false;
+    }
+
+    match hir {
+        Hir::UnaryOp(node) => {
+            adapt_int_literal(&mut node.inner.child, expected);
+        }
+        // the shift amount keeps its own type
+        Hir::BinaryOp(node) => {
+            adapt_int_literal(&mut node.inner.lhs, expected);
+            if !matches!(node.inner.op, BinaryOp::ShiftLeft | BinaryOp::ShiftRight) {
+                adapt_int_literal(&mut node.inner.rhs, expected);
+            }
+        }
+        _ => {}
+    }
+
+    *hir.ty_mut() = expected.clone();
+    true
+}
+
+/// if a value is an integer literal with no suffix, or computed from such literals alone by
+/// negations and integer arithmetic
+fn is_int_literal_expr(hir: &Hir) -> bool {
+    match hir {
+        Hir::Literal(node) => matches!(node.inner, Literal::Int(_)),
+        Hir::UnaryOp(node) => matches!(node.inner.op, UnaryOp::Neg | UnaryOp::BitNeg) && is_int_literal_expr(&node.inner.child),
+        Hir::BinaryOp(node) => match node.inner.op {
+            BinaryOp::Plus | BinaryOp::Minus | BinaryOp::Times | BinaryOp::Divides | BinaryOp::Modulo | BinaryOp::Exp
+            | BinaryOp::BitAnd | BinaryOp::BitOr | BinaryOp::BitXor => is_int_literal_expr(&node.inner.lhs) && is_int_literal_expr(&node.inner.rhs),
+            BinaryOp::ShiftLeft | BinaryOp::ShiftRight => is_int_literal_expr(&node.inner.lhs),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// the value of an integer literal that was given the integer type, along with any negations of
+/// it. A bitwise negation flips the bits of the type the literal was given. Unsigned values can't
+/// be negated, which is reported where the operator is checked, so they have no value
+fn int_literal_value(hir: &Hir, tp: &Type) -> Option<i128> {
+    match hir {
+        Hir::Literal(node) => match node.inner {
+            Literal::Int(value) => Some(value as i128),
+            _ => None,
+        },
+        Hir::UnaryOp(node) => {
+            let value = int_literal_value(&node.inner.child, tp)?;
+            match node.inner.op {
+                UnaryOp::Neg if matches!(tp, Type::UInt | Type::ULong) => None,
+                UnaryOp::Neg => Some(-value),
+                UnaryOp::BitNeg if matches!(tp, Type::UInt | Type::ULong) => Some(int_range(tp)?.1 - value),
+                UnaryOp::BitNeg => Some(!value),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// an integer literal along with any negations of it, the way it is written in source
+fn int_literal_text(hir: &Hir) -> String {
+    match hir {
+        Hir::UnaryOp(node) => format!("{}{}", node.inner.op.symbol(), int_literal_text(&node.inner.child)),
+        Hir::Literal(node) => match node.inner {
+            Literal::Int(value) => value.to_string(),
+            _ => String::new(),
+        },
+        _ => String::new(),
+    }
+}
+
+/// the smallest and largest value of an integer type
+fn int_range(tp: &Type) -> Option<(i128, i128)> {
+    match tp {
+        Type::Int => Some((i32::MIN as i128, i32::MAX as i128)),
+        Type::UInt => Some((0, u32::MAX as i128)),
+        Type::Long => Some((i64::MIN as i128, i64::MAX as i128)),
+        Type::ULong => Some((0, u64::MAX as i128)),
+        _ => None,
+    }
+}
+
+fn fits_in(value: i128, tp: &Type) -> bool {
+    int_range(tp).is_some_and(|(min, max)| (min..=max).contains(&value))
+}
+
+fn is_numeric(tp: &Type) -> bool {
+    tp.is_integer() || *tp == Type::Double
+}
+
+/// where the field name of a field access is. The name is the last thing in the access, and
+/// can't span lines
+fn field_name_range(access: SourceRange, field: &str) -> SourceRange {
+    let start = SourceLocation {
+        line: access.end.line,
+        col: access.end.col.saturating_sub(field.chars().count()),
+    };
+
+    SourceRange { start, end: access.end }
 }
@@ -0,0 +1,358 @@
+use crate::analysis::{analyze_source, error_messages};
+
+#[test]
+fn call_function_typed_param() {
+    let result = analyze_source("fun apply(f: fun(int): int, x: int): int { return f(x); }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn call_function_typed_param_with_wrong_argument() {
+    let errors = error_messages("fun apply(f: fun(n: int): int): int { return f(true); }");
+    assert_eq!(errors, ["Parameter 'n' has type 'int', but the argument has type 'bool'"]);
+}
+
+#[test]
+fn call_function_typed_param_with_wrong_argument_count() {
+    let errors = error_messages("fun apply(f: fun(int): int): int { return f(1, 2); }");
+    assert_eq!(errors, ["Expected 1 argument, but got 2"]);
+}
+
+#[test]
+fn return_from_function_typed_param() {
+    let errors = error_messages("fun apply(f: fun(int): int): bool { return f(1); }");
+    assert_eq!(errors, ["The function returns 'bool', but this returns a value of type 'int'"]);
+}
+
+#[test]
+fn check_array_literal_elements() {
+    let result = analyze_source("fun main() { let xs = [1, 2, 3]; let ys: [2]bool = [true, false]; }");
+    assert!(result.is_ok());
+
+    let errors = error_messages("fun main() { let xs = [1, true]; }");
+    assert_eq!(errors, ["Array elements must all have the same type. Expected 'int' but got 'bool'"]);
+}
+
+#[test]
+fn check_array_literal_against_declared_element_type() {
+    let errors = error_messages("fun main() { let xs: [2]bool = [1, 2]; }");
+    assert_eq!(errors, ["Cannot initialize 'xs' of type '[2]bool' with a value of type '[2]int'"]);
+}
+
+#[test]
+fn check_array_access() {
+    let result = analyze_source("fun main() { let xs: [2][3]uint = [[1, 2, 3], [4, 5, 6]]; let i: long = 1; let x: uint = xs[i][2]; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn check_array_access_into_non_array() {
+    let errors = error_messages("fun main() { let x = 3; let y = x[0]; }");
+    assert_eq!(errors, ["Cannot index into a value of type 'int'"]);
+}
+
+#[test]
+fn check_array_access_with_non_integer_offset() {
+    let errors = error_messages("fun main() { let xs = [1, 2]; let y = xs[true]; }");
+    assert_eq!(errors, ["Array index must be an integer, but got 'bool'"]);
+}
+
+#[test]
+fn check_field_access_and_method_calls() {
+    let result = analyze_source("object Person { age: uint; greet: fun(int): bool; } object Student composes Person { gpa: double; } \
+        fun get(): Student { return Student { Person = Person { age = 3, greet = check }, gpa = 1.0 }; } fun check(n: int): bool { return true; } \
+        fun main() { let age: uint = get().age; let ok: bool = get().greet(4); }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn check_access_to_missing_field() {
+    let errors = error_messages("object Person { age: uint; } fun main() { let p = Person { age = 3 }; let x = p.name; }");
+    assert_eq!(errors, ["No field 'name' on type 'Person'"]);
+}
+
+#[test]
+fn check_every_access_to_a_missing_field() {
+    let errors = error_messages("object Person { age: uint; } fun main() { let p = Person { age = 3 }; let x = p.name; let y = p.height; }");
+    assert_eq!(errors, ["No field 'name' on type 'Person'", "No field 'height' on type 'Person'"]);
+}
+
+#[test]
+fn check_access_to_field_of_non_object() {
+    let errors = error_messages("fun main() { let x = 3; let y = x.value; }");
+    assert_eq!(errors, ["Type 'int' has no fields"]);
+}
+
+#[test]
+fn check_call_of_non_function() {
+    let errors = error_messages("object Person { age: uint; } fun main() { let p = Person { age = 3 }; let x = p.age(1); }");
+    assert_eq!(errors, ["Cannot call a value of type 'uint'"]);
+}
+
+#[test]
+fn check_casts() {
+    let result = analyze_source("object Person { age: uint; } object Student composes Person { gpa: double; } \
+        fun main() { let c: char = 'a'; let l = c as long; let d = 3 as double; let o = d as double?; let n = 2.5 as uint; \
+        let s = Student { Person = Person { age = 3 }, gpa = 1.0 }; let p = &s as &Person; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn check_cast_between_unrelated_types() {
+    let errors = error_messages("fun main() { let o: int? = 3; let x = o as int; }");
+    assert_eq!(errors, ["Cannot cast a value of type 'int?' to 'int'"]);
+
+    let errors = error_messages("fun main() { let b = true as int; }");
+    assert_eq!(errors, ["Cannot cast a value of type 'bool' to 'int'"]);
+}
+
+#[test]
+fn check_cast_of_reference_to_unrelated_object() {
+    let errors = error_messages("object Person { age: uint; } object Pet { age: uint; } \
+        fun main() { let p = Person { age = 3 }; let q = &p as &Pet; }");
+    assert_eq!(errors, ["Cannot cast a value of type '&Person' to '&Pet'"]);
+}
+
+#[test]
+fn check_object_literals() {
+    let result = analyze_source("object Person { name: str; age: uint; } object Student composes Person { gpa: double; } \
+        fun main() { let age: uint = 3; let p = Person { name = \"a\", age }; let s = Student { Person = p, gpa = 1.0 }; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn check_object_literal_with_missing_fields() {
+    let errors = error_messages("object Person { name: str; age: uint; } fun main() { let p = Person { }; }");
+    assert_eq!(errors, ["Missing fields 'age', 'name' in construction of 'Person'"]);
+}
+
+#[test]
+fn check_object_literal_with_duplicate_field() {
+    let errors = error_messages("object Person { age: uint; } fun main() { let p = Person { age = 1, age = 2 }; }");
+    assert_eq!(errors, ["Field 'age' is initialized more than once"]);
+}
+
+#[test]
+fn check_object_literal_with_unknown_field() {
+    let errors = error_messages("object Person { age: uint; } fun main() { let p = Person { age = 1, height = 2 }; }");
+    assert_eq!(errors, ["No field 'height' on type 'Person'"]);
+}
+
+#[test]
+fn check_object_literal_reports_every_bad_field() {
+    let errors = error_messages("object Person { name: str; age: uint; } \
+        fun main() { let p = Person { age = 1, height = 2, age = 3 }; }");
+    assert_eq!(errors, [
+        "Missing field 'name' in construction of 'Person'",
+        "No field 'height' on type 'Person'",
+        "Field 'age' is initialized more than once",
+    ]);
+}
+
+#[test]
+fn check_object_literal_of_non_object() {
+    let errors = error_messages("enum Color { Red } fun main() { let c = Color { Red = 1 }; }");
+    assert_eq!(errors, ["Cannot construct 'Color', which is not an object type"]);
+}
+
+#[test]
+fn check_object_literal_with_wrong_field_type() {
+    let errors = error_messages("object Person { age: uint; } fun main() { let p = Person { age = true }; }");
+    assert_eq!(errors, ["Field 'age' has type 'uint', but is given a value of type 'bool'"]);
+}
+
+#[test]
+fn check_enum_variants() {
+    let result = analyze_source("enum Shape { Circle(double), Rect { w: double; h: double; }, Empty } \
+        fun main() { let c = Shape.Circle(1.0); let r = Shape.Rect(w = 1.0, h = 2.0); let e: Shape = Shape.Empty; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn check_missing_enum_variant() {
+    let errors = error_messages("enum Shape { Circle(double), Empty } fun main() { let s = Shape.Square(1.0); }");
+    assert_eq!(errors, ["No variant 'Square' on enum 'Shape'"]);
+}
+
+#[test]
+fn check_enum_variant_payload_shape() {
+    let errors = error_messages("enum Shape { Circle(double), Empty } fun main() { let s = Shape.Empty(); }");
+    assert_eq!(errors, ["Variant 'Shape.Empty' carries no values, so it is constructed without parentheses"]);
+
+    let errors = error_messages("enum Shape { Circle(double), Empty } fun main() { let s = Shape.Circle; }");
+    assert_eq!(errors, ["Variant 'Shape.Circle' carries values, so it has to be constructed with 'Shape.Circle(...)'"]);
+
+    let errors = error_messages("enum Shape { Circle(double), Empty } fun main() { let s = Shape.Circle(1.0, 2.0); }");
+    assert_eq!(errors, ["Variant 'Shape.Circle' takes 1 value, but got 2"]);
+
+    let errors = error_messages("enum Shape { Rect { w: double; h: double; } } fun main() { let s = Shape.Rect(w = 1.0); }");
+    assert_eq!(errors, ["Missing field 'h' in construction of 'Shape.Rect'"]);
+}
+
+#[test]
+fn check_enum_variant_payload_type() {
+    let errors = error_messages("enum Shape { Circle(double) } fun main() { let s = Shape.Circle(true); }");
+    assert_eq!(errors, ["Value '0' of variant 'Circle' has type 'double', but is given a value of type 'bool'"]);
+}
+
+#[test]
+fn check_duplicate_enum_variant() {
+    let errors = error_messages("enum Shape { Circle(double), Circle(int) }");
+    assert_eq!(errors, ["Duplicate variant 'Circle' on 'Shape'"]);
+}
+
+#[test]
+fn check_match_arms() {
+    let result = analyze_source("fun main() { let o: int? = 3; let x: long = match o { some v if v > 2 => 1, _ => 2 }; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn check_match_guard_type() {
+    let errors = error_messages("fun main() { let o: int? = 3; let x = match o { some v if v => 1, _ => 2 }; }");
+    assert_eq!(errors, ["A match guard must be a bool, but has type 'int'"]);
+}
+
+#[test]
+fn check_match_arm_types() {
+    let errors = error_messages("fun main() { let o: int? = 3; let x = match o { some v => v, null => true }; }");
+    assert_eq!(errors, ["Match arms have different types. Expected 'int' but got 'bool'"]);
+}
+
+#[test]
+fn check_patterns_against_subject_type() {
+    // the type of the matched value is only known once it is inferred
+    let errors = error_messages("fun main() { let n = 3; let x = match n { some v => v, _ => 0 }; }");
+    assert_eq!(errors, ["'some' patterns only match optional values, but the value has type 'int'"]);
+
+    let errors = error_messages("fun f(n: int): int { return match n { null => 1, _ => 0 }; }");
+    assert_eq!(errors, ["Cannot match null against a value of type 'int', which is not optional"]);
+
+    let errors = error_messages("fun f(n: int): int { return match n { true => 1, _ => 0 }; }");
+    assert_eq!(errors, ["A pattern of type 'bool' cannot match a value of type 'int'"]);
+
+    let errors = error_messages("object Point { x: int; } object Size { x: int; } \
+        fun f(p: Point): int { return match p { Size { x } => x }; }");
+    assert_eq!(errors, ["A pattern for 'Size' cannot match a value of type 'Point'"]);
+}
+
+#[test]
+fn check_match_against_expected_type() {
+    let errors = error_messages("fun main() { let o: int? = 3; let x: uint = match o { some v => 1, null => -1 }; }");
+    assert_eq!(errors, ["Cannot apply '-' to a value of type 'uint'"]);
+}
+
+#[test]
+fn check_if_expressions() {
+    let result = analyze_source("fun main() { let a = true; let x: long = if (a) { 1 } else if (!a) { 2 } else { 3 }; \
+        let o: int? = if (a) { null } else { 4 }; if (a) { let y = x; }; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn check_if_branches_with_different_types() {
+    let errors = error_messages("fun main() { let a = true; let x = if (a) { 1 } else { true }; }");
+    assert_eq!(errors, ["The branches of an 'if' have different types. Expected 'int' but got 'bool'"]);
+
+    let errors = error_messages("fun main() { let a = true; let x = if (a) { 1 } else if (a) { 2 } else { 'c' }; }");
+    assert_eq!(errors, ["The branches of an 'if' have different types. Expected 'int' but got 'char'"]);
+}
+
+#[test]
+fn check_if_without_else_with_value() {
+    let errors = error_messages("fun main() { let a = true; let x = if (a) { 1 }; }");
+    assert_eq!(errors, ["An 'if' without an 'else' must evaluate to (), but its branch has type 'int'"]);
+}
+
+#[test]
+fn check_if_condition_type() {
+    let errors = error_messages("fun main() { let x = if (1) { 1 } else { 2 }; }");
+    assert_eq!(errors, ["A condition must be a bool, but has type 'int'"]);
+}
+
+#[test]
+fn check_loop_break_values() {
+    let result = analyze_source("fun main() { let c = true; let v: uint? = while (true) { break 7; }; \
+        let w: long? = while (c) { if (c) { break 1; }; break 2; }; let u = while (c) { break; }; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn check_loop_with_break_value_is_optional() {
+    let errors = error_messages("fun main() { let w = while (false) { break 7; }; let x: int = w; }");
+    assert_eq!(errors, ["Cannot initialize 'x' of type 'int' with a value of type 'int?'"]);
+
+    let errors = error_messages("fun main() { let v2: uint = while (true) { break 7; }; }");
+    assert_eq!(errors, ["Cannot initialize 'v2' of type 'uint' with a value of type 'uint?'"]);
+}
+
+#[test]
+fn check_loop_breaks_with_different_types() {
+    let errors = error_messages("fun main() { let c = true; let w = while (c) { if (c) { break 1; }; break true; }; }");
+    assert_eq!(errors, ["Loop was given a value of type 'int' before, but this 'break' gives it 'bool'"]);
+}
+
+#[test]
+fn check_while_condition_type() {
+    let errors = error_messages("fun main() { while (1) { break; }; }");
+    assert_eq!(errors, ["A condition must be a bool, but has type 'int'"]);
+}
+
+#[test]
+fn check_operators() {
+    let result = analyze_source("fun main() { let a: long = 3; let b = a * 2 + 1; let c = !(b > a) && true; let d = ~a << 2; }");
+    assert!(result.is_ok());
+
+    let errors = error_messages("fun main() { let a: long = 1; let b: int = 2; let c = a + b; }");
+    assert_eq!(errors, ["The operands of '+' must have the same type, but got 'long' and 'int'"]);
+
+    let errors = error_messages("fun main() { let a = !3; }");
+    assert_eq!(errors, ["Cannot apply '!' to a value of type 'int'"]);
+
+    // each operand that can't be used is reported
+    let errors = error_messages("fun main() { let a = true && true; let b = a + a; }");
+    assert_eq!(errors, ["Cannot apply '+' to a value of type 'bool'", "Cannot apply '+' to a value of type 'bool'"]);
+}
+
+#[test]
+fn check_null_coalescing() {
+    let result = analyze_source("fun main() { let o: long? = null; let x: long = o ?? 1; }");
+    assert!(result.is_ok());
+
+    let errors = error_messages("fun main() { let o = 3; let x = o ?? 1; }");
+    assert_eq!(errors, ["The left side of '??' must be an optional value, but has type 'int'"]);
+
+    let errors = error_messages("fun main() { let o: int? = null; let x = o ?? true; }");
+    assert_eq!(errors, ["The default value for '??' has type 'bool', but the optional holds 'int'"]);
+}
+
+#[test]
+fn check_assignments() {
+    let result = analyze_source("fun main() { let mut x: long = 1; x = 2; let mut o: int? = null; o = 3; }");
+    assert!(result.is_ok());
+
+    let errors = error_messages("fun main() { let mut x: long = 1; x = true; }");
+    assert_eq!(errors, ["Cannot assign a value of type 'bool' to a place of type 'long'"]);
+
+    let errors = error_messages("fun main() { let x: bool = 1; }");
+    assert_eq!(errors, ["Cannot initialize 'x' of type 'bool' with a value of type 'int'"]);
+}
+
+#[test]
+fn check_returns() {
+    let result = analyze_source("fun f(a: bool): long { if (a) { return 1; }; return 2; } fun g() { return; }");
+    assert!(result.is_ok());
+
+    let errors = error_messages("fun f(): long { return true; }");
+    assert_eq!(errors, ["The function returns 'long', but this returns a value of type 'bool'"]);
+}
+
+#[test]
+fn check_collects_every_error() {
+    let errors = error_messages("fun f(): long { let x: bool = 1; let y = 1 + true; return true; }");
+    assert_eq!(errors, [
+        "Cannot initialize 'x' of type 'bool' with a value of type 'int'",
+        "Cannot apply '+' to a value of type 'bool'",
+        "The function returns 'long', but this returns a value of type 'bool'",
+    ]);
+}
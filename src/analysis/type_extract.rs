@@ -1,7 +1,7 @@
 use std::collections::HashMap;
-use crate::frontend::ast::{ArrayAccessNode, AssignmentNode, Ast, BinaryOpNode, CompilationUnitNode, CompositionSpecNode, CondExprNode, FieldDeclarationNode, FunCallNode, FunctionDeclarationNode, IdentNode, LitNode, NamedArgNode, ObjectDeclarationNode, ParamNode, ReturnNode, TypeSpecNode, UnaryOpNode, VariableDeclarationNode, WhileNode};
+use crate::frontend::ast::{ArrayAccessNode, ArrayLiteralNode, ArrayRepeatNode, AssignmentNode, Ast, BinaryOpNode, BreakNode, CastNode, CompilationUnitNode, CompositionSpecNode, CondExprNode, ContinueNode, EnumDeclarationNode, EnumVariantNode, FieldAccessNode, FieldDeclarationNode, ForNode, FunCallNode, FunctionDeclarationNode, IdentNode, LitNode, MatchNode, MethodCallNode, NamedArgNode, ObjectDeclarationNode, ObjectLiteralNode, ParamNode, ReturnNode, TypeSpecNode, UnaryOpNode, VariableDeclarationNode, VariantPayload, WhileNode};
 use crate::frontend::ast::visitor::AstVisitor;
-use crate::types::{FunParam, FunType, ObjectType, Type};
+use crate::types::{ComposedObject, EnumType, EnumVariant, FunParam, FunType, ObjectType, Type, VariantType};
 
 /// Pulls type info from an AST node
 pub struct TypeExtractor;
@@ -11,15 +11,18 @@ impl AstVisitor for TypeExtractor {
     type ErrT = ();
     type CtxT = ();
 
-    fn visit_compilation_unit(&self, node: CompilationUnitNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+    fn visit_compilation_unit(&self, _node: CompilationUnitNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         Ok(Type::Unknown)
     }
 
     fn visit_function_declaration(&self, node: FunctionDeclarationNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         let mut params = Vec::<FunParam>::new();
         for param in node.params {
-            let param_type = self.visit(param, ctx.clone())?;
-            let param_name = node.name.into_ident();
+            let Ast::Param(param) = param else {
+                panic!("Function declaration has a non-parameter node as a parameter")
+            };
+            let param_name = param.name.clone().into_ident();
+            let param_type = self.visit_param(param, ctx)?;
             let param = FunParam {
                 tp: param_type.into(),
                 name: param_name.ident,
@@ -27,7 +30,7 @@ impl AstVisitor for TypeExtractor {
             params.push(param)
         }
 
-        let ret_tp = self.visit(node.ret_tp, ctx)?;
+        let ret_tp = self.visit(*node.ret_tp, ctx)?;
 
         Ok(Type::Function(FunType {
             ret: ret_tp.into(),
@@ -39,36 +42,53 @@ impl AstVisitor for TypeExtractor {
         let mut fields = HashMap::<String, Box<Type>>::new();
         for field in node.fields {
             let field_name = field.clone().into_field_decl().name.into_ident().ident;
-            let field_type = self.visit(field, ctx.clone())?;
+            let field_type = self.visit(field, ctx)?;
             fields.insert(field_name, field_type.into());
         }
 
+        let mut comps = HashMap::<String, ComposedObject>::new();
+        for spec in node.composition_specs {
+            let Ast::CompositionSpec(spec) = spec else {
+                panic!("Object declaration has a non-composition node as a composition spec")
+            };
+
+            let alias = spec.alias.clone().map(|alias| alias.into_ident().ident);
+            // composing anything but an object is reported by the composition checker
+            let Type::UserDefined(composed_name) = self.visit_composition_spec(spec, ctx)? else {
+                continue;
+            };
+
+            let composed = ComposedObject {
+                name: composed_name.clone(),
+                aliased: alias.is_some(),
+            };
+            comps.insert(alias.unwrap_or(composed_name), composed);
+        }
+
         Ok(Type::Object(ObjectType {
-            name: "".to_string(),
+            name: node.name.into_ident().ident,
             props: fields,
-            comps: Default::default(),
+            comps,
         }))
     }
 
     fn visit_field_declaration(&self, node: FieldDeclarationNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        self.visit(node.tp, ctx)
+        self.visit(*node.tp, ctx)
     }
 
     fn visit_composition_spec(&self, node: CompositionSpecNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        // I think this is always going to be a user-defined type...
-        Ok(Type::Unknown)
+        self.visit(*node.composed_type, ctx)
     }
 
     fn visit_variable_declaration(&self, node: VariableDeclarationNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        self.visit(node.tp, ctx)
+        self.visit(*node.tp, ctx)
     }
 
     fn visit_param(&self, node: ParamNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        self.visit(node.tp, ctx)
+        self.visit(*node.tp, ctx)
     }
 
-    fn visit_block(&self, stmts: Vec<Box<Ast>>, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        let mut stmts = stmts;
+    fn visit_block(&self, stmts: Vec<Ast>, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         let Some(last_stmt) = stmts.into_iter().last() else {
             return Ok(Type::Unit)
         };
@@ -77,55 +97,76 @@ impl AstVisitor for TypeExtractor {
     }
 
     fn visit_assignment(&self, node: AssignmentNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        self.visit(node.rhs, ctx)
+        self.visit(*node.rhs, ctx)
     }
 
-    fn visit_unary_op(&self, node: UnaryOpNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+    fn visit_unary_op(&self, _node: UnaryOpNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         Ok(Type::Unknown)
     }
 
-    fn visit_binary_op(&self, node: BinaryOpNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+    fn visit_binary_op(&self, _node: BinaryOpNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         Ok(Type::Unknown)
     }
 
-    fn visit_cond_expr(&self, node: CondExprNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        // TODO
-        // 1. check both branches and find conflicting values
-        // 2. only check false in case it's a unit
-        self.visit(node.true_branch, ctx)
+    fn visit_cond_expr(&self, _node: CondExprNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        // like operators, conditionals are typed by the type checker
+        Ok(Type::Unknown)
     }
 
-    fn visit_while(&self, node: WhileNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+    fn visit_while(&self, _node: WhileNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         // loops return nothing
         // TODO subject to change if I include breaks
         Ok(Type::Unit)
     }
 
-    fn visit_identifier(&self, node: IdentNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+    fn visit_for(&self, _node: ForNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        // like while loops, for loops return nothing
+        Ok(Type::Unit)
+    }
+
+    fn visit_identifier(&self, _node: IdentNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         // we don't know an identifier's type until we perform symbol lookups
         Ok(Type::Unknown)
     }
 
-    fn visit_literal(&self, node: LitNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+    fn visit_literal(&self, node: LitNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         Ok(node.lit.into())
     }
 
-    fn visit_fun_call(&self, node: FunCallNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+    fn visit_fun_call(&self, _node: FunCallNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         // function calls are not know syntactically. We have to analyze/infer the return type once
         // we have symbol information. So, we return unknown
         Ok(Type::Unknown)
     }
 
+    fn visit_field_access(&self, _node: FieldAccessNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        // like function calls, we need the object's type from the symbol table first
+        Ok(Type::Unknown)
+    }
+
+    fn visit_method_call(&self, _node: MethodCallNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(Type::Unknown)
+    }
+
     fn visit_named_arg(&self, node: NamedArgNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        self.visit(node.value, ctx)
+        self.visit(*node.value, ctx)
     }
 
     fn visit_return(&self, node: ReturnNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        self.visit(node.expr, ctx)
+        self.visit(*node.expr, ctx)
+    }
+
+    fn visit_break(&self, _node: BreakNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        // control leaves the current block, so the break itself produces nothing
+        Ok(Type::Unit)
+    }
+
+    fn visit_continue(&self, _node: ContinueNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(Type::Unit)
     }
 
     fn visit_array_access(&self, node: ArrayAccessNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        let derefed_type = self.visit(node.derefed, ctx)?;
+        let derefed_type = self.visit(*node.derefed, ctx)?;
         let inner_type = match derefed_type {
             Type::Array(inner, _) => *inner,
             Type::View(inner) => *inner,
@@ -135,7 +176,86 @@ impl AstVisitor for TypeExtractor {
         Ok(inner_type)
     }
 
-    fn visit_type_spec(&self, node: TypeSpecNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+    fn visit_array_literal(&self, node: ArrayLiteralNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        // the element type is taken from the first element. Whether the rest agree is checked
+        // during lowering
+        let len = node.elements.len();
+        let element_type = match node.elements.into_iter().next() {
+            Some(first) => self.visit(first, ctx)?,
+            None => Type::Unknown,
+        };
+
+        Ok(Type::Array(element_type.into(), len))
+    }
+
+    fn visit_array_repeat(&self, node: ArrayRepeatNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        let element_type = self.visit(*node.value, ctx)?;
+        Ok(Type::Array(element_type.into(), node.count))
+    }
+
+    fn visit_object_literal(&self, node: ObjectLiteralNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(Type::UserDefined(node.name.into_ident().ident))
+    }
+
+    fn visit_cast(&self, node: CastNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        self.visit(*node.tp, ctx)
+    }
+
+    fn visit_enum_declaration(&self, node: EnumDeclarationNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        let mut variants = Vec::<EnumVariant>::new();
+        for variant in node.variants {
+            let Ast::EnumVariant(variant) = variant else {
+                panic!("Enum declaration has a non-variant node as a variant")
+            };
+
+            let payload = match variant.payload {
+                VariantPayload::Unit => VariantType::Unit,
+                VariantPayload::Tuple(values) => {
+                    let mut value_types = Vec::<Type>::new();
+                    for value in values {
+                        value_types.push(self.visit(value, ctx)?);
+                    }
+
+                    VariantType::Tuple(value_types)
+                }
+                VariantPayload::Struct(fields) => {
+                    let mut field_types = HashMap::<String, Box<Type>>::new();
+                    for field in fields {
+                        let field_name = field.clone().into_field_decl().name.into_ident().ident;
+                        let field_type = self.visit(field, ctx)?;
+                        field_types.insert(field_name, field_type.into());
+                    }
+
+                    VariantType::Struct(field_types)
+                }
+            };
+
+            variants.push(EnumVariant {
+                name: variant.name.into_ident().ident,
+                payload,
+            });
+        }
+
+        Ok(Type::Enum(EnumType {
+            name: node.name.into_ident().ident,
+            variants,
+        }))
+    }
+
+    fn visit_enum_variant(&self, _node: EnumVariantNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        // a variant only has a type as part of its enum
+        Ok(Type::Unknown)
+    }
+
+    fn visit_match(&self, node: MatchNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        // arms having to agree is checked during lowering
+        match node.arms.into_iter().next() {
+            Some(arm) => self.visit(*arm.body, ctx),
+            None => Ok(Type::Unit),
+        }
+    }
+
+    fn visit_type_spec(&self, node: TypeSpecNode, _ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         Ok(node.tp)
     }
 }
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use crate::error::source::SourceError;
+use crate::frontend::location::SourceRange;
+use crate::symtab::SymbolId;
+use crate::types::Type;
+
+#[cfg(test)]
+mod test;
+
+/// identifies a binding being inferred
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BindingKey {
+    /// a binding written in source, by the symbol it was resolved to
+    Symbol(SymbolId),
+    /// a temporary made up while lowering, by its name and where it was declared. A few of them
+    /// share the location of the statement they came from, so the name is needed too
+    Temporary(String, SourceRange),
+}
+
+/// what is known so far about the type of a binding declared without one
+struct InferredBinding {
+    /// the name of the binding, for errors
+    name: String,
+    /// where the binding was declared
+    loc: SourceRange,
+    /// the type so far. Parts of it that are still unknown are yet to be inferred
+    tp: Type,
+    /// set when the initializer is built from integer literals without a suffix. The type is then
+    /// only a default, and may still become any other integer type
+    defaulted: bool,
+    /// if the initializer's type wasn't known in the first place. That is an error of its own, so
+    /// failing to infer the binding isn't reported on top of it
+    unknown_initializer: bool,
+}
+
+/// Local type inference for bindings declared with `let` but no type. A binding starts out with
+/// the type of its initializer, and every later use of it fills in the parts of that type that
+/// are still unknown, like the optional's type for `let x = null;`. Bindings initialized with
+/// integer literals default to `int`, but the first use that needs another integer type settles
+/// them on that type instead. Defaulted bindings computed from each other, or used together,
+/// have to end up with the same type, so they are settled together. Once the uses have been
+/// collected the inference is settled, and the inferred types can be looked up
+pub struct LocalInference {
+    /// the bindings being inferred
+    bindings: HashMap<BindingKey, InferredBinding>,
+    /// defaulted bindings that have to end up with the same type, as a union-find forest
+    parents: HashMap<BindingKey, BindingKey>,
+    /// where each binding was declared, in order, so that errors come out in order too
+    declared: Vec<BindingKey>,
+    /// if every use has been collected
+    settled: bool,
+}
+
+impl LocalInference {
+    pub fn new() -> Self {
+        Self {
+            bindings: HashMap::new(),
+            parents: HashMap::new(),
+            declared: Vec::new(),
+            settled: false,
+        }
+    }
+
+    /// start inferring a binding from the type of its initializer. `defaulted` lists the bindings
+    /// the initializer was computed from when it is made of integer literals with no suffix
+    pub fn declare(&mut self, key: BindingKey, name: &str, loc: SourceRange, init_type: Type, defaulted: Option<Vec<BindingKey>>) {
+        if self.settled || self.bindings.contains_key(&key) {
+            return;
+        }
+
+        let binding = InferredBinding {
+            name: name.to_string(),
+            loc,
+            unknown_initializer: matches!(init_type, Type::Unknown),
+            tp: init_type,
+            defaulted: defaulted.is_some(),
+        };
+
+        self.declared.push(key.clone());
+        self.bindings.insert(key.clone(), binding);
+        if let Some(mut sources) = defaulted {
+            sources.push(key);
+            self.link(&sources);
+        }
+    }
+
+    /// record that the given defaulted bindings have to end up with the same type
+    pub fn link(&mut self, keys: &[BindingKey]) {
+        if self.settled {
+            return;
+        }
+
+        let mut roots = keys.iter()
+            .filter(|key| self.is_defaulted(key))
+            .map(|key| self.root(key));
+        let Some(first) = roots.next() else {
+            return;
+        };
+
+        for root in roots.collect::<Vec<_>>() {
+            if root != first {
+                self.parents.insert(root, first.clone());
+            }
+        }
+    }
+
+    fn root(&self, key: &BindingKey) -> BindingKey {
+        let mut key = key;
+        while let Some(parent) = self.parents.get(key) {
+            key = parent;
+        }
+
+        key.clone()
+    }
+
+    /// record that a binding is used where a value of the given type is needed
+    pub fn constrain(&mut self, key: &BindingKey, used_as: &Type) {
+        if self.settled {
+            return;
+        }
+
+        if self.is_defaulted(key) {
+            // an integer can also be stored as an optional of that integer
+            let used_as = match used_as {
+                Type::Optional(inner) => inner.as_ref(),
+                tp => tp,
+            };
+
+            if !used_as.is_integer() {
+                return;
+            }
+
+            let root = self.root(key);
+            let linked = self.bindings.keys()
+                .filter(|other| self.is_defaulted(other) && self.root(other) == root)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            for other in linked {
+                let binding = self.bindings.get_mut(&other).unwrap();
+                binding.tp = used_as.clone();
+                binding.defaulted = false;
+            }
+
+            return;
+        }
+
+        let Some(binding) = self.bindings.get_mut(key) else {
+            return;
+        };
+
+        if let Some(refined) = refine(&binding.tp, used_as) {
+            binding.tp = refined;
+        }
+    }
+
+    /// stop collecting uses. Reports every binding whose type still has unknown parts
+    pub fn settle(&mut self) -> Vec<SourceError> {
+        self.settled = true;
+        self.declared.iter()
+            .map(|key| &self.bindings[key])
+            .filter(|binding| !binding.unknown_initializer && has_unknown(&binding.tp))
+            .map(|binding| {
+                let msg = format!("Cannot infer type for '{}'. Give it a type with 'let {}: <type>'", binding.name, binding.name);
+                SourceError::new(msg, binding.loc)
+            })
+            .collect()
+    }
+
+    /// the type inferred for a binding, once the inference is settled
+    pub fn binding_type(&self, key: &BindingKey) -> Option<&Type> {
+        if !self.settled {
+            return None;
+        }
+
+        self.bindings.get(key).map(|binding| &binding.tp)
+    }
+
+    /// the type inferred for a binding so far
+    pub fn inferred_type(&self, key: &BindingKey) -> Option<&Type> {
+        self.bindings.get(key).map(|binding| &binding.tp)
+    }
+
+    /// if a binding's type is still only a default from integer literals
+    pub fn is_defaulted(&self, key: &BindingKey) -> bool {
+        self.bindings.get(key).is_some_and(|binding| binding.defaulted)
+    }
+}
+
+/// if part of a type is still unknown
+fn has_unknown(tp: &Type) -> bool {
+    match tp {
+        Type::Unknown => true,
+        Type::Reference(inner) | Type::Optional(inner) | Type::View(inner) | Type::Array(inner, _) => has_unknown(inner),
+        _ => false,
+    }
+}
+
+/// fill in the unknown parts of a type from the type it is used as. Gives nothing when the use
+/// doesn't fit the type, which is left for the type checker to report
+fn refine(tp: &Type, used_as: &Type) -> Option<Type> {
+    let refined = match (tp, used_as) {
+        (Type::Unknown, used_as) => used_as.clone(),
+        (tp, Type::Unknown) => tp.clone(),
+        (Type::Optional(inner), Type::Optional(used_as)) => Type::Optional(refine(inner, used_as)?.into()),
+        // a value stored into an optional binding tells what the optional holds
+        (Type::Optional(inner), used_as) => Type::Optional(refine(inner, used_as)?.into()),
+        (Type::Reference(inner), Type::Reference(used_as)) => Type::Reference(refine(inner, used_as)?.into()),
+        (Type::View(inner), Type::View(used_as)) => Type::View(refine(inner, used_as)?.into()),
+        (Type::Array(inner, len), Type::Array(used_as, used_len)) if len == used_len => Type::Array(refine(inner, used_as)?.into(), *len),
+        // sized arrays are used as views, which keeps the size
+        (Type::Array(inner, len), Type::View(used_as)) => Type::Array(refine(inner, used_as)?.into(), *len),
+        (tp, used_as) if tp == used_as => tp.clone(),
+        _ => return None,
+    };
+
+    Some(refined)
+}
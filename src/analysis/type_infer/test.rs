@@ -0,0 +1,103 @@
+use crate::analysis::{analyze_source, error_messages};
+use crate::analysis::type_infer::{BindingKey, LocalInference};
+use crate::frontend::location::SourceRange;
+use crate::types::Type;
+
+fn key(name: &str) -> BindingKey {
+    BindingKey::Temporary(name.to_string(), SourceRange::default())
+}
+
+#[test]
+fn infer_from_initializer() {
+    let mut inference = LocalInference::new();
+    inference.declare(key("x"), "x", SourceRange::default(), Type::Boolean, None);
+
+    assert!(inference.binding_type(&key("x")).is_none(), "Types should only be given out once settled");
+    assert!(inference.settle().is_empty());
+    assert_eq!(inference.binding_type(&key("x")), Some(&Type::Boolean));
+}
+
+#[test]
+fn infer_unknown_parts_from_use() {
+    let mut inference = LocalInference::new();
+    inference.declare(key("x"), "x", SourceRange::default(), Type::Optional(Type::Unknown.into()), None);
+    inference.constrain(&key("x"), &Type::Optional(Type::Char.into()));
+
+    assert!(inference.settle().is_empty());
+    assert_eq!(inference.binding_type(&key("x")), Some(&Type::Optional(Type::Char.into())));
+}
+
+#[test]
+fn infer_linked_defaults_together() {
+    let mut inference = LocalInference::new();
+    inference.declare(key("a"), "a", SourceRange::default(), Type::Int, Some(Vec::new()));
+    inference.declare(key("b"), "b", SourceRange::default(), Type::Int, Some(vec![key("a")]));
+    inference.declare(key("c"), "c", SourceRange::default(), Type::Int, Some(Vec::new()));
+    inference.constrain(&key("b"), &Type::ULong);
+
+    assert!(inference.settle().is_empty());
+    assert_eq!(inference.binding_type(&key("a")), Some(&Type::ULong));
+    assert_eq!(inference.binding_type(&key("b")), Some(&Type::ULong));
+    assert_eq!(inference.binding_type(&key("c")), Some(&Type::Int));
+}
+
+#[test]
+fn infer_defaults_only_from_integer_uses() {
+    let mut inference = LocalInference::new();
+    inference.declare(key("a"), "a", SourceRange::default(), Type::Int, Some(Vec::new()));
+    inference.constrain(&key("a"), &Type::Double);
+    assert!(inference.is_defaulted(&key("a")));
+
+    inference.constrain(&key("a"), &Type::Optional(Type::Long.into()));
+    assert!(!inference.is_defaulted(&key("a")));
+    assert!(inference.settle().is_empty());
+    assert_eq!(inference.binding_type(&key("a")), Some(&Type::Long));
+}
+
+#[test]
+fn infer_nothing_after_settling() {
+    let mut inference = LocalInference::new();
+    inference.declare(key("x"), "x", SourceRange::default(), Type::Optional(Type::Unknown.into()), None);
+    let errors = inference.settle();
+    inference.constrain(&key("x"), &Type::Optional(Type::Int.into()));
+
+    assert_eq!(errors.iter().map(|err| err.msg()).collect::<Vec<_>>(), ["Cannot infer type for 'x'. Give it a type with 'let x: <type>'"]);
+    assert_eq!(inference.binding_type(&key("x")), Some(&Type::Optional(Type::Unknown.into())));
+}
+
+#[test]
+fn infer_types_in_programs() {
+    let result = analyze_source("fun take(n: uint) { } fun main() { let x = 5; take(x); let y = x + 1; take(y); \
+        let o = null; let p: long? = o; let u: ulong = 5000000000 - 1000000000; let l: long = 1 + 2 * 3; }");
+    assert!(result.is_ok());
+}
+
+#[test]
+fn infer_type_never_given() {
+    let errors = error_messages("fun main() { let x = null; }");
+    assert_eq!(errors, ["Cannot infer type for 'x'. Give it a type with 'let x: <type>'"]);
+}
+
+#[test]
+fn infer_type_used_inconsistently() {
+    let errors = error_messages("fun take(n: uint) { } fun main() { let x = 5; take(x); let y: int = x; }");
+    assert_eq!(errors, ["Cannot initialize 'y' of type 'int' with a value of type 'uint'"]);
+}
+
+#[test]
+fn literal_out_of_range() {
+    let errors = error_messages("fun main() { let x: int = 99999999999999; }");
+    assert_eq!(errors, ["Literal 99999999999999 does not fit in 'int'"]);
+
+    let errors = error_messages("fun main() { let x: long = 1 + 9999999999999999999; }");
+    assert_eq!(errors, ["Literal 9999999999999999999 does not fit in 'long'"]);
+
+    let errors = error_messages("fun main() { let x: uint = 5000000000; }");
+    assert_eq!(errors, ["Literal 5000000000 does not fit in 'uint'"]);
+}
+
+#[test]
+fn infer_reports_errors_in_inferred_bindings_once() {
+    let errors = error_messages("fun main() { let x = 1; let y: bool = x; let z = x.value; }");
+    assert_eq!(errors, ["Cannot initialize 'y' of type 'bool' with a value of type 'int'", "Type 'int' has no fields"]);
+}
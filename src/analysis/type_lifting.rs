@@ -1,8 +1,8 @@
 use crate::analysis::type_extract::TypeExtractor;
-use crate::frontend::ast::{ArrayAccessNode, AssignmentNode, Ast, BinaryOpNode, CompilationUnitNode, CompositionSpecNode, CondExprNode, FieldDeclarationNode, FunCallNode, FunctionDeclarationNode, IdentNode, LitNode, NamedArgNode, ObjectDeclarationNode, ParamNode, ReturnNode, TypeSpecNode, UnaryOpNode, VariableDeclarationNode, WhileNode};
+use crate::frontend::ast::{ArrayAccessNode, ArrayLiteralNode, ArrayRepeatNode, AssignmentNode, Ast, BinaryOpNode, BreakNode, CastNode, CompilationUnitNode, CompositionSpecNode, CondExprNode, ContinueNode, EnumDeclarationNode, EnumVariantNode, FieldAccessNode, FieldDeclarationNode, ForNode, FunCallNode, FunctionDeclarationNode, IdentNode, LitNode, MatchNode, MethodCallNode, NamedArgNode, ObjectDeclarationNode, ObjectLiteralNode, ParamNode, ReturnNode, TypeSpecNode, UnaryOpNode, VariableDeclarationNode, WhileNode};
 use crate::frontend::ast::visitor::{AstVisitor};
 use crate::symtab::{Symbol, SymbolTable};
-use crate::types::Type;
+use crate::types::{Type, VariableDeclarationMode};
 
 pub struct TypeLifter {
     extractor: TypeExtractor,
@@ -31,16 +31,12 @@ impl AstVisitor for TypeLifter {
 
     fn visit_function_declaration(&self, node: FunctionDeclarationNode, mut ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
 
-        let loc = node.location.clone();
-        let name = node.name.into_ident().ident;
+        let loc = node.location;
+        let name = node.name.clone().into_ident().ident;
 
         let function_type = self.extractor.visit_function_declaration(node, ())?;
 
-        let symbol = Symbol {
-            name,
-            tp: function_type,
-            loc,
-        };
+        let symbol = Symbol::new(name, function_type, loc, VariableDeclarationMode::Const);
 
         ctx.add_symbol(symbol);
 
@@ -48,24 +44,37 @@ impl AstVisitor for TypeLifter {
     }
 
     fn visit_object_declaration(&self, node: ObjectDeclarationNode, mut ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        let loc = node.location.clone();
+        let loc = node.location;
         let Type::Object(function_type) = self.extractor.visit_object_declaration(node, ())? else {
             panic!()
         };
 
         let name = function_type.name.clone();
 
-        let symbol = Symbol {
-            name,
-            tp: Type::Object(function_type),
-            loc,
+        let symbol = Symbol::new(name, Type::Object(function_type), loc, VariableDeclarationMode::Const);
+
+        ctx.add_symbol(symbol);
+
+        Ok(ctx)
+    }
+
+    fn visit_enum_declaration(&self, node: EnumDeclarationNode, mut ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        let loc = node.location;
+        let Type::Enum(enum_type) = self.extractor.visit_enum_declaration(node, ())? else {
+            panic!()
         };
 
+        let symbol = Symbol::new(enum_type.name.clone(), Type::Enum(enum_type), loc, VariableDeclarationMode::Const);
+
         ctx.add_symbol(symbol);
 
         Ok(ctx)
     }
 
+    fn visit_enum_variant(&self, _node: EnumVariantNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(ctx)
+    }
+
     fn visit_field_declaration(&self, _node: FieldDeclarationNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         Ok(ctx)
     }
@@ -82,7 +91,7 @@ impl AstVisitor for TypeLifter {
         Ok(ctx)
     }
 
-    fn visit_block(&self, _stmts: Vec<Box<Ast>>, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+    fn visit_block(&self, _stmts: Vec<Ast>, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         Ok(ctx)
     }
 
@@ -106,6 +115,10 @@ impl AstVisitor for TypeLifter {
         Ok(ctx)
     }
 
+    fn visit_for(&self, _node: ForNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(ctx)
+    }
+
     fn visit_identifier(&self, _node: IdentNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         Ok(ctx)
     }
@@ -118,6 +131,14 @@ impl AstVisitor for TypeLifter {
         Ok(ctx)
     }
 
+    fn visit_field_access(&self, _node: FieldAccessNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(ctx)
+    }
+
+    fn visit_method_call(&self, _node: MethodCallNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(ctx)
+    }
+
     fn visit_named_arg(&self, _node: NamedArgNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         Ok(ctx)
     }
@@ -126,10 +147,38 @@ impl AstVisitor for TypeLifter {
         Ok(ctx)
     }
 
+    fn visit_break(&self, _node: BreakNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(ctx)
+    }
+
+    fn visit_continue(&self, _node: ContinueNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(ctx)
+    }
+
     fn visit_array_access(&self, _node: ArrayAccessNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         Ok(ctx)
     }
 
+    fn visit_array_literal(&self, _node: ArrayLiteralNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(ctx)
+    }
+
+    fn visit_array_repeat(&self, _node: ArrayRepeatNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(ctx)
+    }
+
+    fn visit_object_literal(&self, _node: ObjectLiteralNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(ctx)
+    }
+
+    fn visit_cast(&self, _node: CastNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(ctx)
+    }
+
+    fn visit_match(&self, _node: MatchNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        Ok(ctx)
+    }
+
     fn visit_type_spec(&self, _node: TypeSpecNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
         Ok(ctx)
     }
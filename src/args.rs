@@ -1,9 +1,13 @@
 use std::path::PathBuf;
 use clap::Parser;
+use crate::analysis::ShadowingLevel;
 
 #[derive(Debug, Clone, Parser)]
 #[command(author, version, about)]
 pub struct ProgramArgs {
     /// the input files to compile
-    pub input_files: Vec<PathBuf>
+    pub input_files: Vec<PathBuf>,
+    /// how to report a `let` that shadows a binding declared earlier in the same scope
+    #[arg(long, value_enum, default_value_t)]
+    pub shadowing: ShadowingLevel,
 }
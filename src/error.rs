@@ -1,3 +1,4 @@
 pub mod internal;
 pub mod source;
 pub mod parse;
+pub mod suggest;
@@ -22,13 +22,6 @@ impl InternalError {
         self.cause = Some(cause.into());
         self
     }
-
-    pub fn new_with_cause<StrT: Into<String>, ErrT: Into<Box<dyn Error>>>(msg: StrT, cause: ErrT) -> Self {
-        Self {
-            msg: msg.into(),
-            cause: Some(cause.into())
-        }
-    }
 }
 
 impl Display for InternalError {
@@ -42,5 +35,9 @@ impl Error for InternalError {
     fn description(&self) -> &str {
         &self.msg
     }
+
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.cause.as_deref()
+    }
 }
 
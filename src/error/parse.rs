@@ -25,9 +25,9 @@ impl ParseErr {
     }
 }
 
-impl Into<SourceError> for ParseErr {
-    fn into(self) -> SourceError {
-        match self {
+impl From<ParseErr> for SourceError {
+    fn from(val: ParseErr) -> Self {
+        match val {
             ParseErr::Fatal(err) => err,
             ParseErr::NonFatal(err) => err,
         }
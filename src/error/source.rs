@@ -1,15 +1,55 @@
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use crate::error::suggest::closest_match;
 use crate::frontend::location::{HasLocation, SourceRange};
 
+/// how serious a diagnostic is. Warnings are reported, but don't stop compilation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "Error"),
+            Severity::Warning => write!(f, "Warning"),
+        }
+    }
+}
+
+/// a suggested fix for an error: the source in a range replaced with new text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixIt {
+    /// the source to replace
+    loc: SourceRange,
+    /// what to replace it with
+    replacement: String,
+}
+
+impl FixIt {
+    pub fn loc(&self) -> SourceRange {
+        self.loc
+    }
+
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SourceError {
     /// the actual error message to display to the user
     msg: String,
     /// The location where the error occurs
     err_loc: SourceRange,
-    /// optional source range to show around the error for context
-    context_loc: Option<SourceRange>,
+    /// optional source range to show around the error for context, with a note on what it shows
+    context: Option<(SourceRange, &'static str)>,
+    /// if this is an error or only a warning
+    severity: Severity,
+    /// a fix for the error, if one can be suggested. Boxed, since most errors have none
+    fix: Option<Box<FixIt>>,
 }
 
 impl SourceError {
@@ -17,12 +57,49 @@ impl SourceError {
         Self {
             msg: msg.into(),
             err_loc: loc.source_range(),
-            context_loc: None,
+            context: None,
+            severity: Severity::Error,
+            fix: None,
+        }
+    }
+
+    /// create a warning instead of an error
+    pub fn warning<StrT: Into<String>, RangeT: HasLocation>(msg: StrT, loc: RangeT) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::new(msg, loc)
         }
     }
 
-    pub fn with_context_location(mut self, loc: SourceRange) -> Self {
-        self.context_loc = Some(loc);
+    /// point at where the thing the error is about was declared
+    pub fn with_context_location(self, loc: SourceRange) -> Self {
+        self.with_context_note(loc, "declared here")
+    }
+
+    /// point at another part of the source that explains the error
+    pub fn with_context_note(mut self, loc: SourceRange, note: &'static str) -> Self {
+        self.context = Some((loc, note));
+        self
+    }
+
+    /// suggest replacing a name that could not be found with the closest of the names that could
+    /// have been meant, if one is close enough. The name's location is what gets replaced
+    pub fn with_closest_match<'a, IterT>(self, name: &str, loc: SourceRange, candidates: IterT) -> Self
+    where
+        IterT: IntoIterator<Item = &'a str>
+    {
+        match closest_match(name, candidates) {
+            Some(closest) => self.with_fix(loc, closest),
+            None => self,
+        }
+    }
+
+    /// suggest replacing the source in a range to fix the error
+    pub fn with_fix<StrT: Into<String>>(mut self, loc: SourceRange, replacement: StrT) -> Self {
+        self.fix = Some(Box::new(FixIt {
+            loc,
+            replacement: replacement.into(),
+        }));
         self
     }
 
@@ -33,21 +110,34 @@ impl SourceError {
         self.err_loc
     }
     pub fn context_loc(&self) -> Option<SourceRange> {
-        self.context_loc
+        self.context.map(|(loc, _)| loc)
+    }
+    pub fn context_note(&self) -> Option<&str> {
+        self.context.map(|(_, note)| note)
+    }
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+    pub fn fix(&self) -> Option<&FixIt> {
+        self.fix.as_deref()
     }
 }
 
 impl Display for SourceError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Error at {}: ", self.err_loc)?;
-        write!(f, "{}", self.msg)
+        write!(f, "{} at {}: ", self.severity, self.err_loc)?;
+        write!(f, "{}", self.msg)?;
+        match &self.fix {
+            Some(fix) => write!(f, ". Did you mean '{}'?", fix.replacement),
+            None => Ok(()),
+        }
     }
 }
 
 impl Error for SourceError {}
 
-impl Into<Vec<SourceError>> for SourceError {
-    fn into(self) -> Vec<SourceError> {
-        vec![self]
+impl From<SourceError> for Vec<SourceError> {
+    fn from(val: SourceError) -> Self {
+        vec![val]
     }
 }
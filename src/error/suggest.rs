@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod test;
+
+/// the candidate closest to a name that could not be found, if one is close enough that the name
+/// is likely a typo of it. Closeness is the number of characters that have to be inserted,
+/// removed, replaced or swapped to turn one into the other, and about one edit is allowed for
+/// every three characters. Candidates that are equally close are decided by name
+pub fn closest_match<'a, IterT>(name: &str, candidates: IterT) -> Option<&'a str>
+where
+    IterT: IntoIterator<Item = &'a str>
+{
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates.into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// the optimal string alignment distance between two strings, counted in characters. Like the
+/// Levenshtein distance, but swapping two neighbouring characters counts as a single edit
+fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let lhs = lhs.chars().collect::<Vec<_>>();
+    let rhs = rhs.chars().collect::<Vec<_>>();
+    // distances[i][j] is the distance between the first i characters of lhs and the first j of rhs
+    let mut distances = vec![vec![0usize; rhs.len() + 1]; lhs.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=lhs.len() {
+        for j in 1..=rhs.len() {
+            let replaced = distances[i - 1][j - 1] + usize::from(lhs[i - 1] != rhs[j - 1]);
+            let mut distance = replaced
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && lhs[i - 1] == rhs[j - 2] && lhs[i - 2] == rhs[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[lhs.len()][rhs.len()]
+}
@@ -0,0 +1,42 @@
+use crate::error::suggest::closest_match;
+
+#[test]
+fn closest_match_typos() {
+    assert_eq!(closest_match("lenght", ["length", "width"]), Some("length"));
+    assert_eq!(closest_match("widht", ["length", "width"]), Some("width"));
+    assert_eq!(closest_match("Persn", ["Person", "Point"]), Some("Person"));
+    assert_eq!(closest_match("ag", ["age", "name"]), Some("age"));
+}
+
+#[test]
+fn closest_match_counts_swaps_as_one_edit() {
+    // a swap would be two replacements, more than a four character name allows
+    assert_eq!(closest_match("aeg", ["age"]), Some("age"));
+    assert_eq!(closest_match("nmae", ["name"]), Some("name"));
+}
+
+#[test]
+fn closest_match_rejects_distant_names() {
+    assert_eq!(closest_match("x", ["length", "width"]), None);
+    assert_eq!(closest_match("color", ["length", "width"]), None);
+    assert_eq!(closest_match("nmea", ["name"]), None);
+    assert_eq!(closest_match("age", []), None);
+}
+
+#[test]
+fn closest_match_decides_ties_by_name() {
+    assert_eq!(closest_match("bat", ["cat", "bar", "hat"]), Some("bar"));
+    assert_eq!(closest_match("bat", ["hat", "cat"]), Some("cat"));
+}
+
+#[test]
+fn closest_match_prefers_closer_names() {
+    assert_eq!(closest_match("person", ["Persons", "person2", "persn"]), Some("persn"));
+    assert_eq!(closest_match("coutner", ["counter", "count"]), Some("counter"));
+}
+
+#[test]
+fn closest_match_skips_the_name_itself() {
+    assert_eq!(closest_match("age", ["age"]), None);
+    assert_eq!(closest_match("age", ["age", "ages"]), Some("ages"));
+}
@@ -8,10 +8,10 @@ pub mod ast;
 pub mod input;
 mod lexer;
 pub mod location;
-mod token;
+pub(crate) mod token;
 mod parser;
 
-pub fn parse_input_source(input: &SourceInput) -> Result<Box<Ast>, Vec<SourceError>> {
+pub fn parse_input_source(input: &SourceInput) -> Result<Ast, Vec<SourceError>> {
     // eagerly read into tokens
     let token_stream = Lexer::new(input)
         .into_token_stream();
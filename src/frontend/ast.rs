@@ -8,7 +8,7 @@ use crate::types::{Type, VariableDeclarationMode};
 #[derive(Debug, Clone)]
 pub struct CompilationUnitNode {
     /// list of declarations
-    pub(crate) declarations: Vec<Box<Ast>>,
+    pub(crate) declarations: Vec<Ast>,
 }
 
 #[derive(Debug, Clone)]
@@ -16,7 +16,7 @@ pub struct FunctionDeclarationNode {
     /// function name
     pub(crate) name: Box<Ast>,
     /// the parameters given to this function
-    pub(crate) params: Vec<Box<Ast>>,
+    pub(crate) params: Vec<Ast>,
     /// the return type of this function
     pub(crate) ret_tp: Box<Ast>,
     /// The function body
@@ -30,9 +30,39 @@ pub struct ObjectDeclarationNode {
     /// the name of this type
     pub(crate) name: Box<Ast>,
     /// list of composition specifications
-    pub(crate) composition_specs: Vec<Box<Ast>>,
+    pub(crate) composition_specs: Vec<Ast>,
     /// the fields found in this type
-    pub(crate) fields: Vec<Box<Ast>>,
+    pub(crate) fields: Vec<Ast>,
+    /// location in source where this node occurs
+    pub(crate) location: SourceRange,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumDeclarationNode {
+    /// the name of this type
+    pub(crate) name: Box<Ast>,
+    /// the variants of this type, in declaration order
+    pub(crate) variants: Vec<Ast>,
+    /// location in source where this node occurs
+    pub(crate) location: SourceRange,
+}
+
+#[derive(Debug, Clone)]
+pub enum VariantPayload {
+    /// the variant carries no data
+    Unit,
+    /// the variant carries positional values, given as type specs
+    Tuple(Vec<Ast>),
+    /// the variant carries named fields, given as field declarations
+    Struct(Vec<Ast>),
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumVariantNode {
+    /// the name of this variant
+    pub(crate) name: Box<Ast>,
+    /// the data this variant carries
+    pub(crate) payload: VariantPayload,
     /// location in source where this node occurs
     pub(crate) location: SourceRange,
 }
@@ -83,6 +113,8 @@ pub struct ParamNode {
 pub struct AssignmentNode {
     pub(crate) decl: Box<Ast>,
     pub(crate) rhs: Box<Ast>,
+    /// the operator of a compound assignment such as `+=`, which combines the old value with rhs
+    pub(crate) op: Option<BinaryOp>,
     /// location in source where this node occurs
     pub(crate) location: SourceRange,
 }
@@ -110,18 +142,44 @@ pub struct CondExprNode {
     pub(crate) cond: Box<Ast>,
     /// the true branch
     pub(crate) true_branch: Box<Ast>,
-    /// the false branch
-    pub(crate) false_branch: Box<Ast>,
+    /// the false branch. Missing when there is no `else`
+    pub(crate) false_branch: Option<Box<Ast>>,
     /// location in source where this node occurs
     pub(crate) location: SourceRange,
 }
 
 #[derive(Debug, Clone)]
 pub struct FunCallNode {
-    /// the function being called
+    /// the expression being called
     pub(crate) fun_name: Box<Ast>,
     /// the arguments passed
-    pub(crate) args: Vec<Box<Ast>>,
+    pub(crate) args: Vec<Ast>,
+    /// location in source where this node occurs
+    pub(crate) location: SourceRange,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldAccessNode {
+    /// the expression whose field is accessed
+    pub(crate) object: Box<Ast>,
+    /// the name of the field
+    pub(crate) field: Box<Ast>,
+    /// if the access was written with '?.', which skips the access when the object is null
+    pub(crate) chained: bool,
+    /// location in source where this node occurs
+    pub(crate) location: SourceRange,
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodCallNode {
+    /// the expression the method is called on
+    pub(crate) receiver: Box<Ast>,
+    /// the name of the method
+    pub(crate) method: Box<Ast>,
+    /// if the call was written with '?.', which skips the call when the receiver is null
+    pub(crate) chained: bool,
+    /// the arguments passed
+    pub(crate) args: Vec<Ast>,
     /// location in source where this node occurs
     pub(crate) location: SourceRange,
 }
@@ -146,6 +204,18 @@ pub struct WhileNode {
     pub(crate) location: SourceRange,
 }
 
+#[derive(Debug, Clone)]
+pub struct ForNode {
+    /// the variable bound to each element
+    pub(crate) binding: Box<Ast>,
+    /// the expression being iterated over
+    pub(crate) iterable: Box<Ast>,
+    /// the body of the loop
+    pub(crate) body: Box<Ast>,
+    /// location in source where this node occurs
+    pub(crate) location: SourceRange,
+}
+
 #[derive(Debug, Clone)]
 pub struct IdentNode {
     pub(crate) ident: String,
@@ -166,6 +236,18 @@ pub struct ReturnNode {
     pub(crate) location: SourceRange,
 }
 
+#[derive(Debug, Clone)]
+pub struct BreakNode {
+    /// the value given to the enclosing loop, if any
+    pub(crate) value: Option<Box<Ast>>,
+    pub(crate) location: SourceRange,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContinueNode {
+    pub(crate) location: SourceRange,
+}
+
 #[derive(Debug, Clone)]
 pub struct ArrayAccessNode {
     /// the expression being accessed
@@ -176,6 +258,102 @@ pub struct ArrayAccessNode {
     pub(crate) location: SourceRange,
 }
 
+#[derive(Debug, Clone)]
+pub struct ArrayLiteralNode {
+    /// the elements of the array, in order
+    pub(crate) elements: Vec<Ast>,
+    /// source location
+    pub(crate) location: SourceRange,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrayRepeatNode {
+    /// the value every element of the array starts out as
+    pub(crate) value: Box<Ast>,
+    /// how many elements the array has
+    pub(crate) count: usize,
+    /// source location
+    pub(crate) location: SourceRange,
+}
+
+#[derive(Debug, Clone)]
+pub struct PatternField {
+    /// the field this pattern matches against. Positional values have no name, and a lone binding
+    /// in an object or field pattern is shorthand for matching the field of the same name
+    pub(crate) name: Option<IdentNode>,
+    /// the pattern the field has to match
+    pub(crate) pattern: Pattern,
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// `_` matches any value without binding it
+    Wildcard(SourceRange),
+    /// matches a value equal to the literal. `null` matches an empty optional
+    Literal(LitNode),
+    /// matches any value and binds it to a name
+    Binding(IdentNode),
+    /// `some <pattern>` matches an optional holding a value that matches the inner pattern
+    Some {
+        inner: Box<Pattern>,
+        location: SourceRange,
+    },
+    /// `Name { field = <pattern>, ... }` matches an object whose fields match. Fields that are
+    /// left out match anything
+    Object {
+        name: IdentNode,
+        fields: Vec<PatternField>,
+        location: SourceRange,
+    },
+    /// `Enum.Variant`, or `Enum.Variant(<pattern>, ...)` for variants that carry values
+    Variant {
+        enum_name: IdentNode,
+        variant: IdentNode,
+        values: Option<Vec<PatternField>>,
+        location: SourceRange,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    /// the pattern the matched value is tested against
+    pub(crate) pattern: Pattern,
+    /// an extra condition that has to hold for the arm to be taken
+    pub(crate) guard: Option<Box<Ast>>,
+    /// the value of the match when this arm is taken
+    pub(crate) body: Box<Ast>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchNode {
+    /// the value being matched
+    pub(crate) scrutinee: Box<Ast>,
+    /// the arms, tried in order
+    pub(crate) arms: Vec<MatchArm>,
+    /// source location
+    pub(crate) location: SourceRange,
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectLiteralNode {
+    /// the name of the object being constructed
+    pub(crate) name: Box<Ast>,
+    /// the initializers for the fields and composed parts of the object, as named args
+    pub(crate) fields: Vec<Ast>,
+    /// source location
+    pub(crate) location: SourceRange,
+}
+
+#[derive(Debug, Clone)]
+pub struct CastNode {
+    /// the value being converted
+    pub(crate) expr: Box<Ast>,
+    /// the type the value is converted to
+    pub(crate) tp: Box<Ast>,
+    /// source location
+    pub(crate) location: SourceRange,
+}
+
 #[derive(Debug, Clone)]
 pub struct TypeSpecNode {
     /// the type of this spec
@@ -192,21 +370,64 @@ pub enum Ast {
     CompositionSpec(CompositionSpecNode),
     VariableDeclaration(VariableDeclarationNode),
     Param(ParamNode),
-    Block(Vec<Box<Ast>>),
+    Block(Vec<Ast>),
     Assignment(AssignmentNode),
     UnaryOp(UnaryOpNode),
     BinaryOp(BinaryOpNode),
     CondExpr(CondExprNode),
     While(WhileNode),
+    For(ForNode),
     Identifier(IdentNode),
     Literal(LitNode),
     FunCall(FunCallNode),
     NamedArg(NamedArgNode),
     Return(ReturnNode),
+    Break(BreakNode),
+    Continue(ContinueNode),
     ArrayAccess(ArrayAccessNode),
+    ArrayLiteral(ArrayLiteralNode),
+    ArrayRepeat(ArrayRepeatNode),
+    FieldAccess(FieldAccessNode),
+    MethodCall(MethodCallNode),
+    Cast(CastNode),
+    ObjectLiteral(ObjectLiteralNode),
+    EnumDeclaration(EnumDeclarationNode),
+    EnumVariant(EnumVariantNode),
+    Match(MatchNode),
     TypeSpec(TypeSpecNode),
 }
 
+impl Pattern {
+    /// the names this pattern binds, in the order they appear
+    pub fn bindings(&self) -> Vec<&IdentNode> {
+        match self {
+            Pattern::Wildcard(_) | Pattern::Literal(_) => Vec::new(),
+            Pattern::Binding(name) => vec![name],
+            Pattern::Some { inner, .. } => inner.bindings(),
+            Pattern::Object { fields, .. } => fields.iter()
+                .flat_map(|field| field.pattern.bindings())
+                .collect(),
+            Pattern::Variant { values, .. } => values.iter()
+                .flatten()
+                .flat_map(|value| value.pattern.bindings())
+                .collect(),
+        }
+    }
+}
+
+impl HasLocation for Pattern {
+    fn source_range(&self) -> SourceRange {
+        match self {
+            Pattern::Wildcard(location) => *location,
+            Pattern::Literal(node) => node.location,
+            Pattern::Binding(node) => node.location,
+            Pattern::Some { location, .. } => *location,
+            Pattern::Object { location, .. } => *location,
+            Pattern::Variant { location, .. } => *location,
+        }
+    }
+}
+
 impl HasLocation for Ast {
     fn source_range(&self) -> SourceRange {
         match self {
@@ -218,21 +439,35 @@ impl HasLocation for Ast {
             Ast::VariableDeclaration(node) => node.location,
             Ast::Param(node) => node.location,
             Ast::Block(node) => {
-                let start = node.first().map(|ast| ast.source_range()).unwrap();
-                let end = node.last().map(|ast| ast.source_range()).unwrap();
-                SourceRange::spanned(&start, &end)
+                // empty blocks, including ones emptied by error recovery, have nothing to point at
+                let (Some(first), Some(last)) = (node.first(), node.last()) else {
+                    return SourceRange::default();
+                };
+                SourceRange::spanned(first, last)
             },
             Ast::Assignment(node) => node.location,
             Ast::UnaryOp(node) => node.location,
             Ast::BinaryOp(node) => node.location,
             Ast::CondExpr(node) => node.location,
             Ast::While(node) => node.location,
+            Ast::For(node) => node.location,
             Ast::Identifier(node) => node.location,
             Ast::Literal(node) => node.location,
             Ast::FunCall(node) => node.location,
             Ast::NamedArg(node) => node.location,
             Ast::Return(node) => node.location,
+            Ast::Break(node) => node.location,
+            Ast::Continue(node) => node.location,
             Ast::ArrayAccess(node) => node.location,
+            Ast::ArrayLiteral(node) => node.location,
+            Ast::ArrayRepeat(node) => node.location,
+            Ast::FieldAccess(node) => node.location,
+            Ast::MethodCall(node) => node.location,
+            Ast::Cast(node) => node.location,
+            Ast::ObjectLiteral(node) => node.location,
+            Ast::EnumDeclaration(node) => node.location,
+            Ast::EnumVariant(node) => node.location,
+            Ast::Match(node) => node.location,
             Ast::TypeSpec(node) => node.location,
         }
     }
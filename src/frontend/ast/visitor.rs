@@ -1,4 +1,4 @@
-use crate::frontend::ast::{ArrayAccessNode, AssignmentNode, Ast, BinaryOpNode, CompilationUnitNode, CompositionSpecNode, CondExprNode, FieldDeclarationNode, FunCallNode, FunctionDeclarationNode, IdentNode, LitNode, NamedArgNode, ObjectDeclarationNode, ParamNode, ReturnNode, TypeSpecNode, UnaryOpNode, VariableDeclarationNode, WhileNode};
+use crate::frontend::ast::{ArrayAccessNode, ArrayLiteralNode, ArrayRepeatNode, AssignmentNode, Ast, BinaryOpNode, BreakNode, CastNode, CompilationUnitNode, CompositionSpecNode, CondExprNode, ContinueNode, EnumDeclarationNode, EnumVariantNode, FieldAccessNode, FieldDeclarationNode, ForNode, FunCallNode, FunctionDeclarationNode, IdentNode, LitNode, MatchNode, MethodCallNode, NamedArgNode, ObjectDeclarationNode, ObjectLiteralNode, ParamNode, ReturnNode, TypeSpecNode, UnaryOpNode, VariableDeclarationNode, WhileNode};
 
 pub trait AstVisitor {
     type ResT;
@@ -12,22 +12,34 @@ pub trait AstVisitor {
     fn visit_composition_spec(&self, node: CompositionSpecNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_variable_declaration(&self, node: VariableDeclarationNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_param(&self, node: ParamNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
-    fn visit_block(&self, stmts: Vec<Box<Ast>>, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_block(&self, stmts: Vec<Ast>, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_assignment(&self, node: AssignmentNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_unary_op(&self, node: UnaryOpNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_binary_op(&self, node: BinaryOpNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_cond_expr(&self, node: CondExprNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_while(&self, node: WhileNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_for(&self, node: ForNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_identifier(&self, node: IdentNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_literal(&self, node: LitNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_fun_call(&self, node: FunCallNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_named_arg(&self, node: NamedArgNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_return(&self, node: ReturnNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_break(&self, node: BreakNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_continue(&self, node: ContinueNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_array_access(&self, node: ArrayAccessNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_array_literal(&self, node: ArrayLiteralNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_array_repeat(&self, node: ArrayRepeatNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_field_access(&self, node: FieldAccessNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_method_call(&self, node: MethodCallNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_cast(&self, node: CastNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_object_literal(&self, node: ObjectLiteralNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_enum_declaration(&self, node: EnumDeclarationNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_enum_variant(&self, node: EnumVariantNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_match(&self, node: MatchNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
     fn visit_type_spec(&self, node: TypeSpecNode, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT>;
 
-    fn visit(&self, ast: Box<Ast>, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
-        match *ast {
+    fn visit(&self, ast: Ast, ctx: Self::CtxT) -> Result<Self::ResT, Self::ErrT> {
+        match ast {
             Ast::CompilationUnit(node) => self.visit_compilation_unit(node, ctx),
             Ast::FunctionDeclaration(node) => self.visit_function_declaration(node, ctx),
             Ast::ObjectDeclaration(node) => self.visit_object_declaration(node, ctx),
@@ -41,12 +53,24 @@ pub trait AstVisitor {
             Ast::BinaryOp(node) => self.visit_binary_op(node, ctx),
             Ast::CondExpr(node) => self.visit_cond_expr(node, ctx),
             Ast::While(node) => self.visit_while(node, ctx),
+            Ast::For(node) => self.visit_for(node, ctx),
             Ast::Identifier(node) => self.visit_identifier(node, ctx),
             Ast::Literal(node) => self.visit_literal(node, ctx),
             Ast::FunCall(node) => self.visit_fun_call(node, ctx),
             Ast::NamedArg(node) => self.visit_named_arg(node, ctx),
             Ast::Return(node) => self.visit_return(node, ctx),
+            Ast::Break(node) => self.visit_break(node, ctx),
+            Ast::Continue(node) => self.visit_continue(node, ctx),
             Ast::ArrayAccess(node) => self.visit_array_access(node, ctx),
+            Ast::ArrayLiteral(node) => self.visit_array_literal(node, ctx),
+            Ast::ArrayRepeat(node) => self.visit_array_repeat(node, ctx),
+            Ast::FieldAccess(node) => self.visit_field_access(node, ctx),
+            Ast::MethodCall(node) => self.visit_method_call(node, ctx),
+            Ast::Cast(node) => self.visit_cast(node, ctx),
+            Ast::ObjectLiteral(node) => self.visit_object_literal(node, ctx),
+            Ast::EnumDeclaration(node) => self.visit_enum_declaration(node, ctx),
+            Ast::EnumVariant(node) => self.visit_enum_variant(node, ctx),
+            Ast::Match(node) => self.visit_match(node, ctx),
             Ast::TypeSpec(node) => self.visit_type_spec(node, ctx),
         }
     }
@@ -63,22 +87,34 @@ pub trait AstVisitorMut {
     fn visit_composition_spec(&mut self, node: CompositionSpecNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_variable_declaration(&mut self, node: VariableDeclarationNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_param(&mut self, node: ParamNode) -> Result<Self::ResT, Self::ErrT>;
-    fn visit_block(&mut self, stmts: Vec<Box<Ast>>) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_block(&mut self, stmts: Vec<Ast>) -> Result<Self::ResT, Self::ErrT>;
     fn visit_assignment(&mut self, node: AssignmentNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_unary_op(&mut self, node: UnaryOpNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_binary_op(&mut self, node: BinaryOpNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_cond_expr(&mut self, node: CondExprNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_while(&mut self, node: WhileNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_for(&mut self, node: ForNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_identifier(&mut self, node: IdentNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_literal(&mut self, node: LitNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_fun_call(&mut self, node: FunCallNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_named_arg(&mut self, node: NamedArgNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_return(&mut self, node: ReturnNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_break(&mut self, node: BreakNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_continue(&mut self, node: ContinueNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_array_access(&mut self, node: ArrayAccessNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_array_literal(&mut self, node: ArrayLiteralNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_array_repeat(&mut self, node: ArrayRepeatNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_field_access(&mut self, node: FieldAccessNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_method_call(&mut self, node: MethodCallNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_cast(&mut self, node: CastNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_object_literal(&mut self, node: ObjectLiteralNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_enum_declaration(&mut self, node: EnumDeclarationNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_enum_variant(&mut self, node: EnumVariantNode) -> Result<Self::ResT, Self::ErrT>;
+    fn visit_match(&mut self, node: MatchNode) -> Result<Self::ResT, Self::ErrT>;
     fn visit_type_spec(&mut self, node: TypeSpecNode) -> Result<Self::ResT, Self::ErrT>;
 
-    fn visit(&mut self, ast: Box<Ast>) -> Result<Self::ResT, Self::ErrT> {
-        match *ast {
+    fn visit(&mut self, ast: Ast) -> Result<Self::ResT, Self::ErrT> {
+        match ast {
             Ast::CompilationUnit(node) => self.visit_compilation_unit(node),
             Ast::FunctionDeclaration(node) => self.visit_function_declaration(node),
             Ast::ObjectDeclaration(node) => self.visit_object_declaration(node),
@@ -92,12 +128,24 @@ pub trait AstVisitorMut {
             Ast::BinaryOp(node) => self.visit_binary_op(node),
             Ast::CondExpr(node) => self.visit_cond_expr(node),
             Ast::While(node) => self.visit_while(node),
+            Ast::For(node) => self.visit_for(node),
             Ast::Identifier(node) => self.visit_identifier(node),
             Ast::Literal(node) => self.visit_literal(node),
             Ast::FunCall(node) => self.visit_fun_call(node),
             Ast::NamedArg(node) => self.visit_named_arg(node),
             Ast::Return(node) => self.visit_return(node),
+            Ast::Break(node) => self.visit_break(node),
+            Ast::Continue(node) => self.visit_continue(node),
             Ast::ArrayAccess(node) => self.visit_array_access(node),
+            Ast::ArrayLiteral(node) => self.visit_array_literal(node),
+            Ast::ArrayRepeat(node) => self.visit_array_repeat(node),
+            Ast::FieldAccess(node) => self.visit_field_access(node),
+            Ast::MethodCall(node) => self.visit_method_call(node),
+            Ast::Cast(node) => self.visit_cast(node),
+            Ast::ObjectLiteral(node) => self.visit_object_literal(node),
+            Ast::EnumDeclaration(node) => self.visit_enum_declaration(node),
+            Ast::EnumVariant(node) => self.visit_enum_variant(node),
+            Ast::Match(node) => self.visit_match(node),
             Ast::TypeSpec(node) => self.visit_type_spec(node),
         }
     }
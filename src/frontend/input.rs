@@ -3,10 +3,8 @@ mod reporting;
 use std::error::Error;
 use std::fs::File;
 use std::io::{BufReader, Read};
-use std::ops::{Range, RangeInclusive};
 use std::path::PathBuf;
 use crate::error::internal::InternalError;
-use crate::frontend::location::{SourceLocation, SourceRange};
 
 pub enum SourceInputKind {
     /// The input came from a file
@@ -15,6 +13,7 @@ pub enum SourceInputKind {
         path: PathBuf,
     },
     /// The input came from a raw string
+    #[cfg_attr(not(test), allow(dead_code))]
     Raw
 }
 
@@ -48,6 +47,8 @@ impl SourceInput {
         })
     }
 
+    /// input given directly as a string, which only tests do
+    #[cfg_attr(not(test), allow(dead_code))]
     pub fn raw<ContentT: Into<String>>(content: ContentT) -> Self {
         Self {
             buffer: content.into(),
@@ -55,16 +56,6 @@ impl SourceInput {
         }
     }
 
-    pub fn contains_non_ascii(&self) -> bool {
-        for ch in self.buffer.chars() {
-            if !ch.is_ascii() {
-                return true;
-            }
-        }
-
-        false
-    }
-
     pub fn source(&self) -> &str {
         &self.buffer
     }
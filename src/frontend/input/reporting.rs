@@ -2,8 +2,11 @@ use std::fmt::Write;
 use std::iter::zip;
 use std::ops::Range;
 use crate::error::source::SourceError;
-use crate::frontend::input::SourceInput;
-use crate::frontend::location::{SourceLocation, SourceRange};
+use crate::frontend::input::{SourceInput, SourceInputKind};
+use crate::frontend::location::SourceRange;
+
+#[cfg(test)]
+mod test;
 
 struct AnnotationRange {
     lines: Range<usize>,
@@ -12,59 +15,114 @@ struct AnnotationRange {
 
 impl SourceInput {
 
-    pub fn create_error_report(&self, err: SourceError) -> String {
-        let source_slice_range = self.source_range_to_range(err.err_loc());
-        let error_slice = self.annotate_error_slice(source_slice_range, &err);
+    pub fn create_error_report(&self, err: &SourceError) -> String {
+        // the context is shown in the same slice of source as the error
+        let shown_range = match err.context_loc() {
+            Some(context_loc) => SourceRange {
+                start: err.err_loc().start.min(context_loc.start),
+                end: err.err_loc().end.max(context_loc.end),
+            },
+            None => err.err_loc(),
+        };
+        let source_slice_range = self.source_range_to_range(shown_range);
+        let error_slice = self.annotate_error_slice(source_slice_range, err);
+        let suggestion = match err.fix() {
+            Some(fix) => format!("\nDid you mean '{}'?", fix.replacement()),
+            None => String::new(),
+        };
+        let input_name = match &self.kind {
+            SourceInputKind::File { path } => format!("{}:", path.display()),
+            SourceInputKind::Raw => String::new(),
+        };
         format!(r#"
-Error at {}:
-{}
+{} at {}{}:
 {}
+{}{}
         "#,
+            err.severity(),
+            input_name,
             err.err_loc(),
             error_slice,
-            err.msg()
+            err.msg(),
+            suggestion
         )
     }
 
+    /// number the lines of the slice, underlining the error with carets and the context, if any,
+    /// with dashes followed by its note
     fn annotate_error_slice(&self, annotation_range: AnnotationRange, error: &SourceError) -> String {
         let source_slice = &self.buffer[annotation_range.slice_idx];
         let mut buffer = String::new();
-        let error_lines = error.err_loc().start.line..=error.err_loc().end.line;
+        let err_loc = error.err_loc();
+        let error_lines = err_loc.start.line..=err_loc.end.line;
+        let context = error.context_loc().zip(error.context_note());
         for (line_no, line) in zip(annotation_range.lines, source_slice.split("\n")) {
+            let line = line.trim_end_matches('\r');
             let delim_char = if error_lines.contains(&line_no) {
                 '>'
             } else {
                 '|'
             };
-            writeln!(buffer, "{} {} {}", line_no, delim_char, line).unwrap()
+            let gutter = format!("{} {} ", line_no, delim_char);
+            writeln!(buffer, "{}{}", gutter, line).unwrap();
+
+            if error_lines.contains(&line_no) {
+                let underline = Self::underline_columns(line, line_no, err_loc, '^');
+                writeln!(buffer, "{}{}", " ".repeat(gutter.len()), underline).unwrap();
+            }
+
+            if let Some((context_loc, note)) = context {
+                if (context_loc.start.line..=context_loc.end.line).contains(&line_no) {
+                    let mut underline = Self::underline_columns(line, line_no, context_loc, '-');
+                    if line_no == context_loc.end.line {
+                        write!(underline, " {}", note).unwrap();
+                    }
+                    writeln!(buffer, "{}{}", " ".repeat(gutter.len()), underline).unwrap();
+                }
+            }
         }
 
         buffer
     }
 
+    /// builds a line of markers under the columns of the given source line that are in the range.
+    /// Columns count characters, so the padding is built character by character from the source
+    /// line itself. Tabs are kept so the markers line up however the terminal renders them
+    fn underline_columns(line: &str, line_no: usize, range: SourceRange, marker: char) -> String {
+        let line_len = line.chars().count();
+        let start_col = if line_no == range.start.line { range.start.col } else { 0 };
+        let end_col = if line_no == range.end.line { range.end.col } else { line_len };
+        let marker_count = end_col.saturating_sub(start_col).max(1);
+
+        let mut underline = line.chars()
+            .take(start_col)
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        underline.extend(std::iter::repeat_n(marker, marker_count));
+        underline
+    }
+
+    /// find the byte range of the lines surrounding the given source range
     fn source_range_to_range(&self, range: SourceRange) -> AnnotationRange {
-        let mut counter = self.buffer.chars().enumerate();
         let mut start_idx = 0;
         let mut end_idx = self.buffer.len();
-        let mut loc = SourceLocation::default();
-        let (start_line, end_line) = (range.start.line - 1, range.end.line + 3);
-        loop {
-            let Some((idx, ch)) = counter.next() else {
-                break;
-            };
-
-            // bump the line and column
-            loc.update_with_char(ch);
+        let (start_line, end_line) = (range.start.line.saturating_sub(1), range.end.line + 3);
+        let mut line = 0;
+        for (idx, ch) in self.buffer.char_indices() {
+            if ch != '\n' {
+                continue;
+            }
 
-            if loc.line == start_line && loc.col == 0 {
-                start_idx = idx;
+            line += 1;
+            if line == start_line {
+                start_idx = idx + 1;
             }
 
-            if loc.line == end_line && loc.col == 0 {
+            if line == end_line {
                 end_idx = idx;
                 break;
             }
-        };
+        }
 
         AnnotationRange {
             lines: start_line..end_line,
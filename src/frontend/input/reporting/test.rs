@@ -0,0 +1,36 @@
+use crate::analysis::check_mutability;
+use crate::frontend::input::SourceInput;
+use crate::frontend::parse_input_source;
+
+/// report the first error the mutability check finds in a program
+fn mutability_report(source: &str) -> String {
+    let input = SourceInput::raw(source);
+    let ast = parse_input_source(&input).expect("Parser should not error");
+    let errors = check_mutability(ast).expect_err("Mutability check should error");
+    input.create_error_report(&errors[0])
+}
+
+#[test]
+fn report_underlines_error() {
+    let report = mutability_report("fun main() {\n    let x = 1;\n    x = 2;\n}");
+    assert!(report.contains("2 >     x = 2;\n        ^\n"));
+    assert!(report.contains("Cannot assign twice to immutable binding 'x'"));
+}
+
+#[test]
+fn report_shows_context_as_declared_here() {
+    let report = mutability_report("fun main() {\n    let x = 1;\n    x = 2;\n}");
+    assert!(report.contains("\
+1 |     let x = 1;
+            - declared here
+2 >     x = 2;
+        ^
+"));
+}
+
+#[test]
+fn report_widens_slice_to_context() {
+    let report = mutability_report("fun main() {\n    let x = 1;\n\n\n\n\n    x = 2;\n}");
+    assert!(report.contains("1 |     let x = 1;\n            - declared here\n"));
+    assert!(report.contains("6 >     x = 2;\n        ^\n"));
+}
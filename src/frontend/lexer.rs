@@ -1,10 +1,9 @@
-mod input_reader;
 pub mod token_stream;
 
 #[cfg(test)]
 mod test;
 
-use std::error::Error;
+use std::borrow::Cow;
 use std::iter::Peekable;
 use std::str::Chars;
 use crate::error::source::SourceError;
@@ -19,9 +18,10 @@ pub struct Lexer<'input> {
     source: &'input str,
     /// the input we are consuming
     input: Peekable<Chars<'input>>,
-    /// where in the input we are, index
+    /// byte offset of the next unconsumed character in the source
     index: usize,
-    /// where in the source we are in terms of lines and columns
+    /// where in the source we are in terms of lines and columns. Columns count characters, not
+    /// bytes, so they stay independent of the byte index
     location: SourceLocation,
 }
 
@@ -41,10 +41,7 @@ impl<'input> Lexer<'input> {
         let mut found_eof = false;
         while !found_eof {
             let next_token = self.scan_next()?;
-            match &next_token.kind {
-                TokenKind::EOF => found_eof = true,
-                _ => {}
-            }
+            if next_token.kind == TokenKind::Eof { found_eof = true }
 
             tokens.push(next_token);
         }
@@ -63,65 +60,305 @@ impl<'input> Lexer<'input> {
         }
     }
 
-    fn take_until(&mut self, stop_char: char, start_idx: usize) -> &'input str {
-        while let Some(_) = self.input.next_if(|ch| *ch != stop_char) {
-            self.index += 1;
-            self.location.bump();
+    /// take the contents of a quoted literal up to the closing quote, decoding any escape
+    /// sequences along the way. The opening quote must already be consumed. If the contents
+    /// contain no escapes, the source text is borrowed directly
+    fn take_quoted(&mut self, quote: char, start: SourceLocation) -> Result<Cow<'input, str>, SourceError> {
+        let content_start = self.index;
+        let mut decoded: Option<String> = None;
+        loop {
+            match self.input.peek().copied() {
+                Some(next_ch) if next_ch == quote => {
+                    let content = &self.source[content_start..self.index];
+                    self.consume();
+                    return Ok(match decoded {
+                        Some(decoded) => Cow::Owned(decoded),
+                        None => Cow::Borrowed(content),
+                    });
+                }
+                Some('\\') => {
+                    let decoded = decoded.get_or_insert_with(|| self.source[content_start..self.index].to_string());
+                    let escaped = self.take_escape()?;
+                    decoded.push(escaped);
+                }
+                Some(next_ch) => {
+                    self.consume();
+                    if let Some(decoded) = decoded.as_mut() {
+                        decoded.push(next_ch);
+                    }
+                }
+                None => {
+                    let kind = if quote == '"' { "string" } else { "character" };
+                    return Err(SourceError::new(format!("Unterminated {} literal", kind), SourceRange { start, end: self.location }));
+                }
+            }
         }
-
-        &self.source[start_idx..self.index]
     }
 
-    fn take_numerical_literal(&mut self, start: usize) -> Result<LiteralRef<'input>, Box<dyn Error>> {
-        let mut has_dot = false;
-        while let Some(next_ch) = self.input.next_if(|next_ch| next_ch.is_numeric() || *next_ch == '.') {
-            if next_ch == '.' {
-                has_dot = true;
+    /// decode a single escape sequence starting at the backslash
+    fn take_escape(&mut self) -> Result<char, SourceError> {
+        let start_idx = self.index;
+        let start = self.location;
+        self.consume();
+
+        let Some(escape_ch) = self.consume() else {
+            return Err(SourceError::new("Unterminated escape sequence", SourceRange { start, end: self.location }));
+        };
+
+        let escaped = match escape_ch {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '0' => Some('\0'),
+            '\\' => Some('\\'),
+            '\'' => Some('\''),
+            '"' => Some('"'),
+            'x' => {
+                let digits = self.take_hex_digits(2);
+                u32::from_str_radix(digits, 16).ok()
+                    .filter(|value| digits.len() == 2 && *value <= 0x7F)
+                    .and_then(char::from_u32)
+            }
+            'u' => {
+                if self.input.next_if_eq(&'{').is_some() {
+                    self.index += 1;
+                    self.location.bump();
+                    let digits = self.take_hex_digits(6);
+                    let closed = self.input.next_if_eq(&'}').is_some();
+                    if closed {
+                        self.index += 1;
+                        self.location.bump();
+                    }
+
+                    u32::from_str_radix(digits, 16).ok()
+                        .filter(|_| closed)
+                        .and_then(char::from_u32)
+                } else {
+                    None
+                }
             }
+            _ => None,
+        };
 
+        escaped.ok_or_else(|| {
+            let escape = &self.source[start_idx..self.index];
+            SourceError::new(format!("Invalid escape sequence '{}'", escape), SourceRange { start, end: self.location })
+        })
+    }
+
+    /// take at most max_count hexadecimal digits
+    fn take_hex_digits(&mut self, max_count: usize) -> &'input str {
+        let start = self.index;
+        while self.index - start < max_count && self.input.peek().is_some_and(|next_ch| next_ch.is_ascii_hexdigit()) {
+            self.consume();
+        }
+
+        &self.source[start..self.index]
+    }
+
+    /// take the digits of a numerical literal in the given radix, skipping '_' separators
+    fn take_digits(&mut self, radix: u32, digits: &mut String) {
+        while let Some(next_ch) = self.input.next_if(|next_ch| next_ch.is_digit(radix) || *next_ch == '_') {
             self.index += 1;
             self.location.bump();
+            if next_ch != '_' {
+                digits.push(next_ch);
+            }
         }
+    }
+
+    /// the character after the next unconsumed character
+    fn peek_second(&self) -> Option<char> {
+        self.source[self.index..].chars().nth(1)
+    }
 
-        let literal_buffer = &self.source[start..self.index];
+    /// take a numerical literal. The first digit has already been consumed. Supports radix
+    /// prefixes (0x, 0o, 0b), '_' separators, fractions and exponents on decimal literals, and a
+    /// type suffix (u, l, ul, d)
+    fn take_numerical_literal(&mut self, first: char, start_idx: usize, start: SourceLocation) -> Result<LiteralRef<'input>, SourceError> {
+        let prefix_radix = match (first, self.input.peek()) {
+            ('0', Some('x' | 'X')) => Some(16),
+            ('0', Some('o' | 'O')) => Some(8),
+            ('0', Some('b' | 'B')) => Some(2),
+            _ => None,
+        };
 
-        if has_dot {
-            literal_buffer.parse::<f64>()
-                .map(|lit| LiteralRef::Double(lit))
-                .map_err(|err| err.into())
+        let mut digits = String::new();
+        let mut is_float = false;
+        let radix = if let Some(radix) = prefix_radix {
+            self.consume();
+            radix
         } else {
-            literal_buffer.parse::<u64>()
-                .map(|lit| LiteralRef::Int(lit))
-                .map_err(|err| err.into())
+            digits.push(first);
+            10
+        };
+
+        self.take_digits(radix, &mut digits);
+        if digits.is_empty() {
+            let prefix = &self.source[start_idx..self.index];
+            return Err(SourceError::new(format!("Expected digits after '{}'", prefix), SourceRange { start, end: self.location }));
+        }
+
+        if radix == 10 {
+            // only treat '.' as a decimal point if a digit follows, so member access still works
+            if self.input.peek() == Some(&'.') && self.peek_second().is_some_and(|ch| ch.is_ascii_digit()) {
+                self.consume();
+                digits.push('.');
+                self.take_digits(10, &mut digits);
+                is_float = true;
+            }
+
+            let exponent_follows = match self.peek_second() {
+                Some('+' | '-') => self.source[self.index..].chars().nth(2).is_some_and(|ch| ch.is_ascii_digit()),
+                Some(ch) => ch.is_ascii_digit(),
+                None => false,
+            };
+            if self.input.peek().is_some_and(|ch| *ch == 'e' || *ch == 'E') && exponent_follows {
+                self.consume();
+                digits.push('e');
+                if let Some(sign) = self.input.next_if(|ch| *ch == '+' || *ch == '-') {
+                    self.index += 1;
+                    self.location.bump();
+                    digits.push(sign);
+                }
+                self.take_digits(10, &mut digits);
+                is_float = true;
+            }
+        }
+
+        let suffix_start = self.index;
+        let suffix = self.take_identifier(suffix_start);
+        let range = SourceRange { start, end: self.location };
+        let literal_text = &self.source[start_idx..self.index];
+
+        if is_float || suffix == "d" {
+            if !matches!(suffix, "" | "d") {
+                return Err(SourceError::new(format!("Invalid suffix '{}' for floating point literal {}", suffix, literal_text), range));
+            }
+
+            let value = if is_float {
+                digits.parse::<f64>().ok()
+            } else {
+                u64::from_str_radix(&digits, radix).ok().map(|value| value as f64)
+            };
+
+            return value
+                .filter(|value| value.is_finite())
+                .map(LiteralRef::Double)
+                .ok_or_else(|| SourceError::new(format!("Literal {} is out of range for type double", literal_text), range));
         }
+
+        // a literal without a suffix is an int, but takes on any other integer type it is used as,
+        // so here it only has to fit the widest of them. It is checked against the type it ends
+        // up with once that is known
+        let (max_value, type_name): (u64, &str) = match suffix {
+            "" => (u64::MAX, "ulong, the widest type a literal without a suffix can take"),
+            "u" => (u32::MAX as u64, "uint"),
+            "l" => (i64::MAX as u64, "long"),
+            "ul" => (u64::MAX, "ulong"),
+            other => return Err(SourceError::new(format!("Invalid suffix '{}' for integer literal {}", other, literal_text), range)),
+        };
+
+        let value = u64::from_str_radix(&digits, radix).ok()
+            .filter(|value| *value <= max_value)
+            .ok_or_else(|| SourceError::new(format!("Literal {} is out of range for type {}", literal_text, type_name), range))?;
+
+        Ok(match suffix {
+            "u" => LiteralRef::UInt(value),
+            "l" => LiteralRef::Long(value),
+            "ul" => LiteralRef::ULong(value),
+            _ => LiteralRef::Int(value),
+        })
     }
 
     fn take_identifier(&mut self, start: usize) -> &'input str {
-        while let Some(_) = self.input.next_if(|next_ch| next_ch.is_alphanumeric() || *next_ch == '_') {
-            self.index += 1;
+        while let Some(next_ch) = self.input.next_if(|next_ch| next_ch.is_alphanumeric() || *next_ch == '_') {
+            self.index += next_ch.len_utf8();
             self.location.bump();
         }
 
         &self.source[start..self.index]
     }
 
-    pub(crate) fn scan_next(&mut self) -> Result<Token<'input>, SourceError> {
-        // each whitespace
-        while let Some(next_ch) = self.input.next_if(|next_ch| next_ch.is_whitespace()) {
-            self.index += 1;
-            self.location.update_with_char(next_ch);
+    /// consume a single character, keeping the index and location in sync
+    fn consume(&mut self) -> Option<char> {
+        let next_ch = self.input.next()?;
+        self.index += next_ch.len_utf8();
+        self.location.update_with_char(next_ch);
+        Some(next_ch)
+    }
+
+    /// checks if the unconsumed input starts with the given pattern
+    fn lookahead_is(&self, pattern: &str) -> bool {
+        self.source[self.index..].starts_with(pattern)
+    }
+
+    /// skip over whitespace and comments until we reach the start of the next token
+    fn skip_trivia(&mut self) -> Result<(), SourceError> {
+        loop {
+            while let Some(next_ch) = self.input.next_if(|next_ch| next_ch.is_whitespace()) {
+                self.index += next_ch.len_utf8();
+                self.location.update_with_char(next_ch);
+            }
+
+            if self.lookahead_is("//") {
+                self.skip_line_comment();
+            } else if self.lookahead_is("/*") {
+                self.skip_block_comment()?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// skips a line comment up to, but not including, the terminating newline
+    fn skip_line_comment(&mut self) {
+        while self.input.peek().is_some_and(|next_ch| *next_ch != '\n') {
+            self.consume();
         }
+    }
+
+    /// skips a block comment. Block comments may be nested, so each opening delimiter must have
+    /// a matching closing delimiter
+    fn skip_block_comment(&mut self) -> Result<(), SourceError> {
+        let start = self.location;
+        self.consume();
+        self.consume();
+        let opening = SourceRange { start, end: self.location };
+
+        let mut depth = 1usize;
+        while depth > 0 {
+            if self.lookahead_is("/*") {
+                self.consume();
+                self.consume();
+                depth += 1;
+            } else if self.lookahead_is("*/") {
+                self.consume();
+                self.consume();
+                depth -= 1;
+            } else if self.consume().is_none() {
+                return Err(SourceError::new("Unterminated block comment", opening));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn scan_next(&mut self) -> Result<Token<'input>, SourceError> {
+        // skip whitespace and comments
+        self.skip_trivia()?;
 
         let Some(next_ch) = self.input.next() else {
             return Ok(Token {
-                kind: TokenKind::EOF,
+                kind: TokenKind::Eof,
                 location: SourceRange::default(),
             });
         };
 
         let start_idx = self.index;
-        let start = self.location.clone();
-        self.index += 1;
+        let start = self.location;
+        self.index += next_ch.len_utf8();
+        self.location.bump();
 
         let matched_token = match next_ch {
             ',' => Ok(TokenKind::Comma),
@@ -133,71 +370,71 @@ impl<'input> Lexer<'input> {
             '{' => Ok(TokenKind::LBrace),
             '}' => Ok(TokenKind::RBrace),
             ':' => Ok(TokenKind::Colon),
-            '+' => Ok(TokenKind::Plus),
-            '-' => Ok(TokenKind::Minus),
-            '*' => Ok(TokenKind::Times),
-            '/' => Ok(TokenKind::Divides),
-            '&' => Ok(self.decide_next('&', TokenKind::And, TokenKind::Ref)),
+            '+' => Ok(self.decide_next('=', TokenKind::PlusAssign, TokenKind::Plus)),
+            '-' => Ok(self.decide_next('=', TokenKind::MinusAssign, TokenKind::Minus)),
+            '*' => Ok(match self.input.peek() {
+                Some('*') => self.decide_next('*', TokenKind::Exp, TokenKind::Times),
+                _ => self.decide_next('=', TokenKind::TimesAssign, TokenKind::Times),
+            }),
+            '/' => Ok(self.decide_next('=', TokenKind::DividesAssign, TokenKind::Divides)),
+            '%' => Ok(self.decide_next('=', TokenKind::ModuloAssign, TokenKind::Modulo)),
+            '^' => Ok(self.decide_next('=', TokenKind::BitXorAssign, TokenKind::BitXor)),
+            '~' => Ok(TokenKind::BitNeg),
+            '&' => Ok(match self.input.peek() {
+                Some('&') => self.decide_next('&', TokenKind::And, TokenKind::Ref),
+                _ => self.decide_next('=', TokenKind::BitAndAssign, TokenKind::Ref),
+            }),
             '.' => Ok(TokenKind::Access),
-            '=' => Ok(self.decide_next('=', TokenKind::Eq, TokenKind::Assign)),
+            '=' => Ok(match self.input.peek() {
+                Some('>') => self.decide_next('>', TokenKind::FatArrow, TokenKind::Assign),
+                _ => self.decide_next('=', TokenKind::Eq, TokenKind::Assign),
+            }),
             '!' => Ok(self.decide_next('=', TokenKind::Neq, TokenKind::Not)),
-            '>' => Ok(self.decide_next('=', TokenKind::Lte, TokenKind::Lt)),
-            '<' => Ok(self.decide_next('=', TokenKind::Gte, TokenKind::Gt)),
-            '|' => {
-                if self.input.peek().is_some_and(|next_ch| *next_ch == '|') {
-                    self.input.next();
-                    self.index += 1;
-                    self.location.bump();
-                    Ok(TokenKind::Or)
-                } else {
-                    // TODO this is bad and needs to be fixed...
-                    Ok(TokenKind::Or)
+            '>' => Ok(match self.input.peek() {
+                Some('>') => {
+                    self.decide_next('>', TokenKind::ShiftRight, TokenKind::Gt);
+                    self.decide_next('=', TokenKind::ShiftRightAssign, TokenKind::ShiftRight)
                 }
-            }
-            '?' => Ok(TokenKind::Nullable),
+                _ => self.decide_next('=', TokenKind::Gte, TokenKind::Gt),
+            }),
+            '<' => Ok(match self.input.peek() {
+                Some('<') => {
+                    self.decide_next('<', TokenKind::ShiftLeft, TokenKind::Lt);
+                    self.decide_next('=', TokenKind::ShiftLeftAssign, TokenKind::ShiftLeft)
+                }
+                _ => self.decide_next('=', TokenKind::Lte, TokenKind::Lt),
+            }),
+            '|' => Ok(match self.input.peek() {
+                Some('|') => self.decide_next('|', TokenKind::Or, TokenKind::BitOr),
+                _ => self.decide_next('=', TokenKind::BitOrAssign, TokenKind::BitOr),
+            }),
+            '?' => Ok(match self.input.peek() {
+                Some('.') => self.decide_next('.', TokenKind::ChainedAccess, TokenKind::Nullable),
+                _ => self.decide_next('?', TokenKind::NullCoalesce, TokenKind::Nullable),
+            }),
             '\'' => {
                 // start taking a character literal
-                let buffer = self.take_until('\'', start_idx);
+                let buffer = self.take_quoted('\'', start)?;
+                let end = self.location;
+                let mut chars = buffer.chars();
+                let char_literal = match (chars.next(), chars.next()) {
+                    (None, _) => Err(SourceError::new("Char literal cannot be empty", SourceRange { start, end })),
+                    (Some(ch), None) => Ok(ch),
+                    _ => Err(SourceError::new("Char literal can only contain one character", SourceRange { start, end }))
+                }?;
 
-                let ending_char = self.input.next();
-                if ending_char.is_some_and(|ch| ch == '\'') {
-                    self.index += 1;
-                    self.location.bump();
-                    let end = self.location.clone();
-                    let char_literal = match buffer.len() {
-                        0 => Err(SourceError::new("Char literal cannot be empty", (start, end).into())),
-                        1 => Ok(buffer.chars().nth(0).unwrap()),
-                        _ => Err(SourceError::new("Char literal can only contain one character", (start, end).into()))
-                    }?;
-
-                    Ok(TokenKind::Lit(LiteralRef::Char(char_literal)))
-                } else {
-                    Err(SourceError::new("Unterminated character literal", (start, self.location.clone()).into()))
-                }
+                Ok(TokenKind::Lit(LiteralRef::Char(char_literal)))
             }
             '"' => {
-                // start taking a character literal
-                let buffer = self.take_until('"', start_idx);
-
-                let ending_char = self.input.next();
-                if ending_char.is_some_and(|ch| ch == '"') {
-                    self.index += 1;
-                    self.location.bump();
-                    Ok(TokenKind::Lit(LiteralRef::String(buffer)))
-                } else {
-                    Err(SourceError::new("Unterminated character literal", (start, self.location.clone()).into()))
-                }
+                // start taking a string literal
+                let buffer = self.take_quoted('"', start)?;
+                Ok(TokenKind::Lit(LiteralRef::String(buffer)))
             }
             other => {
-                if other.is_numeric() {
+                if other.is_ascii_digit() {
                     // try a digit literal
-                    match self.take_numerical_literal(start_idx) {
-                        Ok(lit) => Ok(TokenKind::Lit(lit)),
-                        Err(parse_error) => {
-                            let end = self.location.clone();
-                            Err(SourceError::new(format!("Invalid numerical literal: {}", parse_error), (start, end).into()))
-                        }
-                    }
+                    self.take_numerical_literal(other, start_idx, start)
+                        .map(TokenKind::Lit)
                 } else if other.is_alphabetic() || other == '_' {
                     // try an identifier
                     let ident = self.take_identifier(start_idx);
@@ -207,13 +444,13 @@ impl<'input> Lexer<'input> {
                     }
                 } else {
                     // unidentified syntax
-                    let end = self.location.clone();
-                    Err(SourceError::new(format!("Unidentified character: {}", other), (start, end).into()))
+                    let end = self.location;
+                    Err(SourceError::new(format!("Unidentified character: {}", other), SourceRange { start, end }))
                 }
             }
         }?;
 
-        let end = self.location.clone();
+        let end = self.location;
         let location = SourceRange { start, end };
 
         Ok(Token {
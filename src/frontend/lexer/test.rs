@@ -1,14 +1,15 @@
-use crate::input::SourceInput;
-use crate::lexer::Lexer;
-use crate::literal::Literal;
-use crate::token::TokenKind;
+use crate::frontend::input::SourceInput;
+use crate::frontend::lexer::Lexer;
+use crate::frontend::location::SourceLocation;
+use crate::literal::LiteralRef;
+use crate::frontend::token::TokenKind;
 
 #[test]
 fn lex_eof() {
     let input = SourceInput::raw("");
     let mut lexer = Lexer::new(&input);
     let result = lexer.scan_next().expect("Lexer should not error");
-    assert_eq!(result.kind, TokenKind::EOF)
+    assert_eq!(result.kind, TokenKind::Eof)
 }
 
 #[test]
@@ -16,5 +17,238 @@ fn lex_str_literal() {
     let input = SourceInput::raw("\"charlie sale\"");
     let mut lexer = Lexer::new(&input);
     let result = lexer.scan_next().expect("Lexer should not error");
-    assert_eq!(result.kind, TokenKind::Lit(Literal::String("charlie sale".to_string())));
+    assert_eq!(result.kind, TokenKind::Lit(LiteralRef::String("charlie sale".into())));
+}
+
+#[test]
+fn lex_str_literal_escapes() {
+    let input = SourceInput::raw(r#""a\"b\n\t\\\0\x41\u{e9}""#);
+    let mut lexer = Lexer::new(&input);
+    let result = lexer.scan_next().expect("Lexer should not error");
+    assert_eq!(result.kind, TokenKind::Lit(LiteralRef::String("a\"b\n\t\\\0A\u{e9}".into())));
+}
+
+#[test]
+fn lex_char_literal_escapes() {
+    let input = SourceInput::raw(r"'\'' '\n' '\u{41}'");
+    let mut lexer = Lexer::new(&input);
+    let result = lexer.scan_next().expect("Lexer should not error");
+    assert_eq!(result.kind, TokenKind::Lit(LiteralRef::Char('\'')));
+    let result = lexer.scan_next().expect("Lexer should not error");
+    assert_eq!(result.kind, TokenKind::Lit(LiteralRef::Char('\n')));
+    let result = lexer.scan_next().expect("Lexer should not error");
+    assert_eq!(result.kind, TokenKind::Lit(LiteralRef::Char('A')));
+}
+
+#[test]
+fn lex_invalid_escape() {
+    let input = SourceInput::raw(r#""ab\q""#);
+    let mut lexer = Lexer::new(&input);
+    let err = lexer.scan_next().expect_err("Lexer should reject invalid escape");
+    assert_eq!(err.err_loc().start, SourceLocation { line: 0, col: 3 });
+    assert_eq!(err.err_loc().end, SourceLocation { line: 0, col: 5 });
+}
+
+#[test]
+fn lex_out_of_range_hex_escape() {
+    let input = SourceInput::raw(r#""\xff""#);
+    let mut lexer = Lexer::new(&input);
+    let err = lexer.scan_next().expect_err("Lexer should reject non-ascii hex escape");
+    assert_eq!(err.msg(), r"Invalid escape sequence '\xff'");
+}
+
+#[test]
+fn lex_skips_line_comment() {
+    let input = SourceInput::raw("// a comment\nname");
+    let mut lexer = Lexer::new(&input);
+    let result = lexer.scan_next().expect("Lexer should not error");
+    assert_eq!(result.kind, TokenKind::Ident("name"));
+    assert_eq!(result.location.start, SourceLocation { line: 1, col: 0 });
+}
+
+#[test]
+fn lex_skips_nested_block_comment() {
+    let input = SourceInput::raw("/* outer /* inner */ still outer */ / name");
+    let mut lexer = Lexer::new(&input);
+    let result = lexer.scan_next().expect("Lexer should not error");
+    assert_eq!(result.kind, TokenKind::Divides);
+    let result = lexer.scan_next().expect("Lexer should not error");
+    assert_eq!(result.kind, TokenKind::Ident("name"));
+}
+
+#[test]
+fn lex_unterminated_block_comment() {
+    let input = SourceInput::raw("\n  /* /* */");
+    let mut lexer = Lexer::new(&input);
+    let err = lexer.scan_next().expect_err("Lexer should report unterminated comment");
+    assert_eq!(err.err_loc().start, SourceLocation { line: 1, col: 2 });
+    assert_eq!(err.err_loc().end, SourceLocation { line: 1, col: 4 });
+}
+
+#[test]
+fn lex_utf8_identifiers_and_strings() {
+    let input = SourceInput::raw("\"héllo wörld\" größe");
+    let mut lexer = Lexer::new(&input);
+    let result = lexer.scan_next().expect("Lexer should not error");
+    assert_eq!(result.kind, TokenKind::Lit(LiteralRef::String("héllo wörld".into())));
+    let result = lexer.scan_next().expect("Lexer should not error");
+    assert_eq!(result.kind, TokenKind::Ident("größe"));
+    assert_eq!(result.location.start, SourceLocation { line: 0, col: 14 });
+    assert_eq!(result.location.end, SourceLocation { line: 0, col: 19 });
+}
+
+#[test]
+fn lex_numeric_literal_prefixes_and_separators() {
+    let input = SourceInput::raw("0xFF 0o17 0b1010 1_000_000");
+    let mut lexer = Lexer::new(&input);
+    for expected in [255, 15, 10, 1_000_000] {
+        let result = lexer.scan_next().expect("Lexer should not error");
+        assert_eq!(result.kind, TokenKind::Lit(LiteralRef::Int(expected)));
+    }
+}
+
+#[test]
+fn lex_numeric_literal_exponents_and_suffixes() {
+    let input = SourceInput::raw("1.5e-3 2.0d 10u 10l 0xFFul 3d");
+    let mut lexer = Lexer::new(&input);
+    let expected = [
+        LiteralRef::Double(1.5e-3),
+        LiteralRef::Double(2.0),
+        LiteralRef::UInt(10),
+        LiteralRef::Long(10),
+        LiteralRef::ULong(255),
+        LiteralRef::Double(3.0),
+    ];
+    for expected in expected {
+        let result = lexer.scan_next().expect("Lexer should not error");
+        assert_eq!(result.kind, TokenKind::Lit(expected));
+    }
+}
+
+#[test]
+fn lex_numeric_literal_out_of_range() {
+    let input = SourceInput::raw("4294967296u");
+    let mut lexer = Lexer::new(&input);
+    let err = lexer.scan_next().expect_err("Lexer should reject out of range literal");
+    assert_eq!(err.msg(), "Literal 4294967296u is out of range for type uint");
+    assert_eq!(err.err_loc().end, SourceLocation { line: 0, col: 11 });
+}
+
+#[test]
+fn lex_unsuffixed_literal_out_of_range() {
+    let input = SourceInput::raw("18446744073709551616");
+    let mut lexer = Lexer::new(&input);
+    let err = lexer.scan_next().expect_err("Lexer should reject out of range literal");
+    assert_eq!(err.msg(), "Literal 18446744073709551616 is out of range for type ulong, the widest type a literal without a suffix can take");
+
+    // fits a ulong, so it is left to the type it is used as
+    let input = SourceInput::raw("18446744073709551615");
+    let mut lexer = Lexer::new(&input);
+    let result = lexer.scan_next().expect("Lexer should accept literal that fits a ulong");
+    assert_eq!(result.kind, TokenKind::Lit(LiteralRef::Int(u64::MAX)));
+}
+
+#[test]
+fn lex_numeric_literal_invalid_suffix() {
+    let input = SourceInput::raw("1.5u");
+    let mut lexer = Lexer::new(&input);
+    let err = lexer.scan_next().expect_err("Lexer should reject invalid suffix");
+    assert_eq!(err.msg(), "Invalid suffix 'u' for floating point literal 1.5u");
+}
+
+#[test]
+fn lex_optional_operators() {
+    let input = SourceInput::raw("a?.b ?? c int?");
+    let mut lexer = Lexer::new(&input);
+    let expected = [
+        TokenKind::Ident("a"),
+        TokenKind::ChainedAccess,
+        TokenKind::Ident("b"),
+        TokenKind::NullCoalesce,
+        TokenKind::Ident("c"),
+        TokenKind::Ident("int"),
+        TokenKind::Nullable,
+    ];
+    for expected in expected {
+        let result = lexer.scan_next().expect("Lexer should not error");
+        assert_eq!(result.kind, expected);
+    }
+}
+
+#[test]
+fn lex_comparison_and_bitwise_operators() {
+    let input = SourceInput::raw("< <= > >= << >> | || & && ^ ~ ! % * **");
+    let mut lexer = Lexer::new(&input);
+    let expected = [
+        TokenKind::Lt,
+        TokenKind::Lte,
+        TokenKind::Gt,
+        TokenKind::Gte,
+        TokenKind::ShiftLeft,
+        TokenKind::ShiftRight,
+        TokenKind::BitOr,
+        TokenKind::Or,
+        TokenKind::Ref,
+        TokenKind::And,
+        TokenKind::BitXor,
+        TokenKind::BitNeg,
+        TokenKind::Not,
+        TokenKind::Modulo,
+        TokenKind::Times,
+        TokenKind::Exp,
+    ];
+    for expected in expected {
+        let result = lexer.scan_next().expect("Lexer should not error");
+        assert_eq!(result.kind, expected);
+    }
+}
+
+#[test]
+fn lex_compound_assignments() {
+    let input = SourceInput::raw("+= -= *= /= %= &= |= ^= <<= >>= = ==");
+    let mut lexer = Lexer::new(&input);
+    let expected = [
+        TokenKind::PlusAssign,
+        TokenKind::MinusAssign,
+        TokenKind::TimesAssign,
+        TokenKind::DividesAssign,
+        TokenKind::ModuloAssign,
+        TokenKind::BitAndAssign,
+        TokenKind::BitOrAssign,
+        TokenKind::BitXorAssign,
+        TokenKind::ShiftLeftAssign,
+        TokenKind::ShiftRightAssign,
+        TokenKind::Assign,
+        TokenKind::Eq,
+    ];
+    for expected in expected {
+        let result = lexer.scan_next().expect("Lexer should not error");
+        assert_eq!(result.kind, expected);
+    }
+}
+
+#[test]
+fn lex_match_arms() {
+    let input = SourceInput::raw("match x { some y => y, null => 0 } = ==");
+    let mut lexer = Lexer::new(&input);
+    let expected = [
+        TokenKind::Match,
+        TokenKind::Ident("x"),
+        TokenKind::LBrace,
+        TokenKind::Some,
+        TokenKind::Ident("y"),
+        TokenKind::FatArrow,
+        TokenKind::Ident("y"),
+        TokenKind::Comma,
+        TokenKind::Lit(LiteralRef::Null),
+        TokenKind::FatArrow,
+        TokenKind::Lit(LiteralRef::Int(0)),
+        TokenKind::RBrace,
+        TokenKind::Assign,
+        TokenKind::Eq,
+    ];
+    for expected in expected {
+        let result = lexer.scan_next().expect("Lexer should not error");
+        assert_eq!(result.kind, expected);
+    }
 }
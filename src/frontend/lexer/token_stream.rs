@@ -21,7 +21,7 @@ impl<'input> TokenStream<'input> {
         }
     }
 
-    pub fn tokens(&self) -> &[Token] {
+    pub fn tokens(&self) -> &[Token<'_>] {
         &self.tokens
     }
 
@@ -43,6 +43,11 @@ impl<'input> TokenStream<'input> {
         self.cursor
     }
 
+    /// drop the most recently saved cursor without restoring it
+    pub fn discard_saved(&mut self) {
+        self.cursor_stack.pop();
+    }
+
     /// restore a saved cursor into the stored cursor
     pub fn restore(&mut self) {
         if let Some(saved_cursor) = self.cursor_stack.pop() {
@@ -54,25 +59,35 @@ impl<'input> TokenStream<'input> {
         self.cursor += amount
     }
 
-    pub fn next(&mut self) -> Option<&Token> {
+    pub fn next(&mut self) -> Option<&Token<'_>> {
         let token = self.tokens.get(self.cursor);
         self.cursor += 1;
         token
     }
 
-    pub fn peek(&self) -> Option<&Token> {
+    /// the most recently consumed token
+    pub fn previous(&self) -> Option<&Token<'_>> {
+        self.cursor.checked_sub(1).and_then(|idx| self.tokens.get(idx))
+    }
+
+    pub fn peek(&self) -> Option<&Token<'_>> {
         self.tokens.get(self.cursor)
     }
 
     pub fn check_next<PredT: Fn(&Token) -> bool>(&self, pred: PredT) -> bool {
-        if let Some(next) = self.tokens.get(self.cursor) {
+        self.check_nth(0, pred)
+    }
+
+    /// check the token n positions past the next one, without consuming anything
+    pub fn check_nth<PredT: Fn(&Token) -> bool>(&self, n: usize, pred: PredT) -> bool {
+        if let Some(next) = self.tokens.get(self.cursor + n) {
             pred(next)
         } else {
             false
         }
     }
 
-    pub fn accept(&mut self, expected: TokenKind) -> Result<&Token, SourceError> {
+    pub fn accept(&mut self, expected: TokenKind) -> Result<&Token<'_>, SourceError> {
         if let Some(tok) = self.next() {
             if tok.kind == expected {
                 Ok(tok)
@@ -88,7 +103,7 @@ impl<'input> TokenStream<'input> {
         if let Some(next) = self.next() {
             pred(next)
         } else {
-            let err = SourceError::new(format!("Unexpected end of token stream instead"), SourceRange::default());
+            let err = SourceError::new("Unexpected end of token stream instead".to_string(), SourceRange::default());
             Err(ParseErr::Fatal(err))
         }
     }
@@ -2,11 +2,11 @@ use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 
 /// represents a single source location in the program
-#[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Eq, Default)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
 pub struct SourceLocation {
     /// the line of the input
     pub line: usize,
-    /// the column/character in that line
+    /// the column in that line, counted in characters rather than bytes
     pub col: usize,
 }
 
@@ -18,12 +18,12 @@ impl SourceLocation {
 
     pub fn bump(&mut self) -> Self {
         self.col += 1;
-        self.clone()
+        *self
     }
 
     pub fn advance(&mut self, n: usize) -> Self {
         self.col += n;
-        self.clone()
+        *self
     }
 
     pub fn cr(&mut self) {
@@ -39,6 +39,12 @@ impl SourceLocation {
     }
 }
 
+impl PartialOrd for SourceLocation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for SourceLocation {
     fn cmp(&self, other: &Self) -> Ordering {
         match self.line.cmp(&other.line) {
@@ -57,7 +63,7 @@ impl Display for SourceLocation {
 }
 
 /// A source range
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
 pub struct SourceRange {
     /// The start of the range inclusive
     pub start: SourceLocation,
@@ -3,6 +3,9 @@ mod funs;
 mod stmt;
 mod tp;
 mod object;
+mod pattern;
+#[cfg(test)]
+mod test;
 
 use std::collections::HashMap;
 use crate::frontend::ast::{Ast, CompilationUnitNode};
@@ -18,6 +21,9 @@ pub struct Parser<'input> {
     tokens: TokenStream<'input>,
     /// source errors we have encountered along the way
     errors: Vec<SourceError>,
+    /// if `Name {` may start an object literal. It can't where a block directly follows an
+    /// expression, like the iterable of a for loop
+    object_literals_allowed: bool,
 }
 
 // Basic utility functions for parser
@@ -26,40 +32,41 @@ impl<'input> Parser<'input> {
     pub fn new(tokens: TokenStream<'input>) -> Self {
         Self {
             tokens,
-            errors: Vec::default()
+            errors: Vec::default(),
+            object_literals_allowed: true,
         }
     }
 
     // parse rules
-    pub fn parse_compilation_unit(mut self) -> Result<Box<Ast>, Vec<SourceError>> {
+    pub fn parse_compilation_unit(mut self) -> Result<Ast, Vec<SourceError>> {
         match self.parse_top_level_decls() {
-            Ok(ast) => {
-                Ok(ast)
+            Ok(ast) if self.errors.is_empty() => {
+                Ok(*ast)
             }
-            Err(_) => {
+            _ => {
                 Err(self.errors)
             }
         }
     }
 
     fn parse_top_level_decls(&mut self) -> ParseResult {
-        let mut decls = Vec::<Box<Ast>>::new();
+        let mut decls = Vec::<Ast>::new();
         loop {
-            let next = self.tokens.peek();
-            if next.is_none() {
-                break;
-            }
+            let next = match self.tokens.peek() {
+                Some(next) if next.kind != TokenKind::Eof => next,
+                _ => break,
+            };
 
-            let next = next.unwrap();
-            let next_defn = match next.kind {
+            let next_defn = match &next.kind {
                 TokenKind::FunDecl => self.parse_fun_defn(),
                 TokenKind::ObjDecl => self.parse_object_decl(),
+                TokenKind::EnumDecl => self.parse_enum_decl(),
                 tok => Err(ParseErr::Fatal(SourceError::new(format!("Unexpected token {:?}", tok), next.location)))
             };
 
             match next_defn {
                 Ok(valid_def) => {
-                    decls.push(valid_def);
+                    decls.push(*valid_def);
                 }
                 Err(parse_err) => {
                     let err = parse_err.into();
@@ -67,7 +74,7 @@ impl<'input> Parser<'input> {
                     self.errors.push(err);
 
                     // skip to the next def
-                    self.tokens.skip_to(|tok| tok.kind == TokenKind::FunDecl || tok.kind == TokenKind::ObjDecl);
+                    self.tokens.skip_to(|tok| matches!(tok.kind, TokenKind::FunDecl | TokenKind::ObjDecl | TokenKind::EnumDecl));
                 }
             }
         }
@@ -87,11 +94,15 @@ impl<'input> Parser<'input> {
             let result = action(self);
             match result {
                 Ok(matched) => {
+                    // keep the cursor stack balanced, otherwise an enclosing rule would restore
+                    // a cursor saved by this one
+                    self.tokens.discard_saved();
                     return Ok(matched)
                 }
                 Err(err) => {
                     match err {
                         ParseErr::Fatal(fatal_error) => {
+                            self.tokens.discard_saved();
                             return Err(ParseErr::Fatal(fatal_error))
                         }
                         ParseErr::NonFatal(non_fatal_error) => {
@@ -115,6 +126,14 @@ impl<'input> Parser<'input> {
         Err(ParseErr::Fatal(err))
     }
 
+    /// run a rule with object literals allowed or forbidden, restoring the previous setting after
+    pub(crate) fn with_object_literals<ResT>(&mut self, allowed: bool, rule: impl FnOnce(&mut Self) -> ResT) -> ResT {
+        let prev = std::mem::replace(&mut self.object_literals_allowed, allowed);
+        let result = rule(self);
+        self.object_literals_allowed = prev;
+        result
+    }
+
     /// parse a repeated rule
     /// rule: the rule to parse each time
     /// delimiter: the token to break between the two
@@ -124,8 +143,8 @@ impl<'input> Parser<'input> {
         rule: fn(&mut Self) -> ParseResult,
         delimiter: TokenKind,
         stop_token: TokenKind
-    ) -> Result<Vec<Box<Ast>>, ParseErr> {
-        let mut items = Vec::<Box<Ast>>::new();
+    ) -> Result<Vec<Ast>, ParseErr> {
+        let mut items = Vec::<Ast>::new();
         loop {
             // if next token is the stop token, we are done
             if self.tokens.check_next(|next_tok| next_tok.kind == stop_token) {
@@ -136,7 +155,7 @@ impl<'input> Parser<'input> {
             let item = rule(self);
             match item {
                 Ok(item) => {
-                    items.push(item);
+                    items.push(*item);
                 }
                 Err(parse_err) => {
                     match parse_err {
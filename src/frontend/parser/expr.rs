@@ -1,19 +1,21 @@
-use crate::frontend::ast::{ArrayAccessNode, Ast, BinaryOpNode, CondExprNode, IdentNode, LitNode, UnaryOpNode};
+use crate::frontend::ast::{ArrayAccessNode, ArrayLiteralNode, ArrayRepeatNode, Ast, BinaryOpNode, CastNode, CondExprNode, FieldAccessNode, IdentNode, LitNode, MatchArm, MatchNode, MethodCallNode, UnaryOpNode};
 use crate::error::parse::ParseErr;
 use crate::error::source::SourceError;
-use crate::literal::{Literal};
+use crate::literal::LiteralRef;
 use crate::frontend::location::SourceRange;
 use crate::operators::{BinaryOp, UnaryOp};
 use crate::frontend::parser::{Parser, ParseResult};
 use crate::frontend::token::TokenKind;
 
 /// The expression parser
-
 impl<'input> Parser<'input> {
     /// <expr> ::= TODO
     pub(crate) fn parse_expr(&mut self) -> ParseResult {
         self.one_of([
             Self::parse_conditional_expr,
+            Self::parse_match_expr,
+            Self::parse_while,
+            Self::parse_for,
             Self::parse_binary_expr,
         ])
     }
@@ -21,38 +23,42 @@ impl<'input> Parser<'input> {
     fn parse_conditional_expr(&mut self) -> ParseResult {
         let if_tok_location = self.tokens.accept(TokenKind::If)
             .map(|tok| tok.location)
-            .map_err(|err| ParseErr::NonFatal(err))?;
+            .map_err(ParseErr::NonFatal)?;
 
         self.tokens.accept(TokenKind::LParen)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         let conditional = self.parse_expr()?;
 
         self.tokens.accept(TokenKind::RParen)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
-        let true_block = self.parse_block()?;
+        let true_block = self.parse_block()
+            .map_err(|err| err.into_fatal())?;
 
-        let (false_block, loc) = if self.tokens.check_next(|token| match token.kind {
-            TokenKind::Else => true,
-            _ => false
-        }) {
+        let false_block = if self.tokens.check_next(|token| token.kind == TokenKind::Else) {
             // take an else branch
             self.tokens.next();
 
-            let ast = self.parse_block()?;
-            let loc = SourceRange::spanned(&if_tok_location, ast.as_ref());
-            (ast, loc)
+            // `else if` chains are an else branch holding nothing but the next conditional
+            let ast = if self.tokens.check_next(|token| token.kind == TokenKind::If) {
+                let chained = self.parse_conditional_expr()
+                    .map_err(|err| err.into_fatal())?;
+                Box::new(Ast::Block(vec![*chained]))
+            } else {
+                self.parse_block()
+                    .map_err(|err| err.into_fatal())?
+            };
+
+            Some(ast)
         } else {
-            let ast = Box::new(Ast::Block(vec![Ast::Literal(LitNode {
-                lit: Literal::Unit,
-                location: Default::default(),
-            }).into()]));
-
-            let loc = SourceRange::spanned(&if_tok_location, true_block.as_ref());
-            (ast, loc)
+            None
         };
 
+        // blocks can be empty, so the conditional ends where its last closing brace is
+        let end_loc = self.tokens.previous().unwrap().location;
+        let loc = SourceRange::spanned(&if_tok_location, &end_loc);
+
         Ok(Ast::CondExpr(CondExprNode {
             cond: conditional,
             true_branch: true_block,
@@ -61,8 +67,45 @@ impl<'input> Parser<'input> {
         }).into())
     }
 
+    /// <match_expr> ::= "match" <expr> "{" ( <match_arm> ( "," <match_arm> )* ","? )? "}"
+    fn parse_match_expr(&mut self) -> ParseResult {
+        let start_loc = self.tokens.accept(TokenKind::Match)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::NonFatal)?;
+
+        // the brace after the matched value opens the arms, not an object literal
+        let scrutinee = self.with_object_literals(false, Self::parse_expr)
+            .map_err(|err| err.into_fatal())?;
+
+        self.tokens.accept(TokenKind::LBrace)
+            .map_err(ParseErr::Fatal)?;
+
+        let arms = self.with_object_literals(true, |parser| {
+            let mut arms = Vec::<MatchArm>::new();
+            while !parser.tokens.check_next(|tok| tok.kind == TokenKind::RBrace) {
+                arms.push(parser.parse_match_arm()?);
+                if !parser.tokens.check_next(|tok| tok.kind == TokenKind::RBrace) {
+                    parser.tokens.accept(TokenKind::Comma)
+                        .map_err(ParseErr::Fatal)?;
+                }
+            }
+
+            Ok(arms)
+        })?;
+
+        let end_loc = self.tokens.accept(TokenKind::RBrace)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::Fatal)?;
+
+        Ok(Ast::Match(MatchNode {
+            scrutinee,
+            arms,
+            location: SourceRange::spanned(&start_loc, &end_loc),
+        }).into())
+    }
+
     fn parse_binary_expr(&mut self) -> ParseResult {
-        let lhs = self.parse_unary_op()?;
+        let lhs = self.parse_cast_expr()?;
         self.parse_binary_expr_rec(lhs, 0)
     }
 
@@ -72,20 +115,23 @@ impl<'input> Parser<'input> {
                 .cloned()
                 .and_then(|token| BinaryOp::try_from(token).ok());
 
-            if !lookahead.as_ref().is_some_and(|op| op.precedence() >= min_prec) {
+            if lookahead.as_ref().is_none_or(|op| op.precedence() < min_prec) {
                 break lhs;
             }
 
             let op = lookahead.unwrap();
             self.tokens.advance(1);
 
-            let mut rhs = self.parse_unary_op()?;
+            let mut rhs = self.parse_cast_expr()?;
 
             let mut inner_op = self.tokens.peek()
                 .cloned()
                 .and_then(|token| BinaryOp::try_from(token).ok());
 
-            while inner_op.as_ref().is_some_and(|inner_op| inner_op.precedence() > op.precedence()) {
+            while inner_op.as_ref().is_some_and(|inner_op| {
+                inner_op.precedence() > op.precedence()
+                    || (inner_op.is_right_associative() && inner_op.precedence() == op.precedence())
+            }) {
                 let prec = op.precedence() + if inner_op.unwrap().precedence() > op.precedence() { 1 } else { 0 };
                 rhs = self.parse_binary_expr_rec(rhs, prec)?;
                 inner_op = self.tokens.peek()
@@ -106,12 +152,33 @@ impl<'input> Parser<'input> {
         Ok(result_ast)
     }
 
-    /// <unary_op> ::= <member_access> | UNARY_OP <unary_op>
+    /// <cast_expr> ::= <unary_op> ( 'as' <type> )*
+    fn parse_cast_expr(&mut self) -> ParseResult {
+        let mut expr = self.parse_unary_op()?;
+        while self.tokens.check_next(|tok| tok.kind == TokenKind::As) {
+            self.tokens.next();
+            let tp = self.parse_type_spec()
+                .map_err(|err| err.into_fatal())?;
+
+            let loc = SourceRange::spanned(expr.as_ref(), tp.as_ref());
+            expr = Ast::Cast(CastNode {
+                expr,
+                tp,
+                location: loc,
+            }).into();
+        }
+
+        Ok(expr)
+    }
+
+    /// <unary_op> ::= <member_access> | ( '-' | '!' | '~' | '*' | '&' ) <unary_op>
     fn parse_unary_op(&mut self) -> ParseResult {
 
         let next_token = self.tokens.next().unwrap();
         let unary_operator = match next_token.kind {
             TokenKind::Minus => Some(UnaryOp::Neg),
+            TokenKind::Not => Some(UnaryOp::Not),
+            TokenKind::BitNeg => Some(UnaryOp::BitNeg),
             TokenKind::Times => Some(UnaryOp::Deref),
             TokenKind::Ref => Some(UnaryOp::Ref),
             _ => None
@@ -131,21 +198,64 @@ impl<'input> Parser<'input> {
         }
     }
 
-    /// <member_access> ::= <atom> | <member_access> '.' ( <fun_call> | <ident> )
+    /// <member_access> ::= <atom> | <field_access> | <method_call> | <fun_call> | <array_access>
     fn parse_member_access(&mut self) -> ParseResult {
-        self.parse_infix_op(
-            [BinaryOp::Access, BinaryOp::ChainedAccess],
-            Self::parse_atom,
-            Self::parse_member_access,
-        )
+        let mut expr = self.parse_atom()?;
+        while let Some(next_tok) = self.tokens.peek() {
+            expr = match next_tok.kind {
+                TokenKind::LBracket => self.parse_array_access(expr)?,
+                TokenKind::LParen => self.parse_fun_call(expr)?,
+                TokenKind::Access | TokenKind::ChainedAccess => self.parse_field_access(expr)?,
+                _ => break,
+            };
+        }
+
+        Ok(expr)
+    }
+
+    /// <field_access> ::= <member_access> ( '.' | '?.' ) <ident>
+    /// <method_call> ::= <member_access> ( '.' | '?.' ) <ident> <call_args>
+    fn parse_field_access(&mut self, object: Box<Ast>) -> ParseResult {
+        let chained = self.tokens.accept_if_map(|tok| match tok.kind {
+            TokenKind::Access => Ok(false),
+            TokenKind::ChainedAccess => Ok(true),
+            _ => {
+                let err = SourceError::new(format!("Expected '.' or '?.' but got '{:?}' instead", tok.kind), tok.location);
+                Err(ParseErr::NonFatal(err))
+            }
+        })?;
+
+        let name = self.parse_ident()
+            .map_err(|err| err.into_fatal())?;
+
+        if self.tokens.check_next(|tok| tok.kind == TokenKind::LParen) {
+            let (args, end_loc) = self.parse_call_args()?;
+            let loc = SourceRange::spanned(object.as_ref(), &end_loc);
+            Ok(Ast::MethodCall(MethodCallNode {
+                receiver: object,
+                method: name,
+                chained,
+                args,
+                location: loc,
+            }).into())
+        } else {
+            let loc = SourceRange::spanned(object.as_ref(), name.as_ref());
+            Ok(Ast::FieldAccess(FieldAccessNode {
+                object,
+                field: name,
+                chained,
+                location: loc,
+            }).into())
+        }
     }
 
-    /// <atom> ::= <literal> | <ident> | <parens_expr> | <fun_call> | <array_access>
+    /// <atom> ::= <literal> | <ident> | <parens_expr> | <array_literal>
     fn parse_atom(&mut self) -> ParseResult {
         let result = self.one_of([
             Self::parse_parens_expr,
-            Self::parse_fun_call,
+            Self::parse_array_literal,
             Self::parse_literal,
+            Self::parse_object_literal,
             Self::parse_ident
         ]);
 
@@ -160,19 +270,16 @@ impl<'input> Parser<'input> {
         }
     }
 
-    /// <array_access> ::= <expr> '\[' <expr> '\]'
-    fn parse_array_access(&mut self) -> ParseResult {
-        let derefed = self.parse_expr()
-            .map_err(|err| err.into_non_fatal())?;
-
+    /// <array_access> ::= <member_access> '\[' <expr> '\]'
+    fn parse_array_access(&mut self, derefed: Box<Ast>) -> ParseResult {
         self.tokens.accept(TokenKind::LBracket)
-            .map_err(|err| ParseErr::NonFatal(err))?;
+            .map_err(ParseErr::NonFatal)?;
 
         let access = self.parse_expr()
             .map_err(|err| err.into_fatal())?;
 
         let end_tok = self.tokens.accept(TokenKind::RBracket)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         let loc = SourceRange::spanned(derefed.as_ref(), end_tok);
 
@@ -183,26 +290,83 @@ impl<'input> Parser<'input> {
         }).into())
     }
 
+    /// <array_literal> ::= '\[' ( <expr> ';' INT_LITERAL | ( <expr> ( ',' <expr> )* )? ) '\]'
+    fn parse_array_literal(&mut self) -> ParseResult {
+        let start_loc = self.tokens.accept(TokenKind::LBracket)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::NonFatal)?;
+
+        let mut elements = Vec::<Ast>::new();
+        if !self.tokens.check_next(|tok| tok.kind == TokenKind::RBracket) {
+            let first = self.parse_expr()
+                .map_err(|err| err.into_fatal())?;
+
+            // a value followed by ';' is repeated for every element
+            if self.tokens.check_next(|tok| tok.kind == TokenKind::Semicolon) {
+                self.tokens.next();
+                let count = self.parse_array_length()?;
+                let end_tok = self.tokens.accept(TokenKind::RBracket)
+                    .map_err(ParseErr::Fatal)?;
+                let loc = SourceRange::spanned(&start_loc, end_tok);
+
+                return Ok(Ast::ArrayRepeat(ArrayRepeatNode {
+                    value: first,
+                    count,
+                    location: loc,
+                }).into());
+            }
+
+            elements.push(*first);
+            if self.tokens.check_next(|tok| tok.kind == TokenKind::Comma) {
+                self.tokens.next();
+                elements.extend(self.parse_repeated(Self::parse_expr, TokenKind::Comma, TokenKind::RBracket)?);
+            }
+        }
+
+        let end_tok = self.tokens.accept(TokenKind::RBracket)
+            .map_err(ParseErr::Fatal)?;
+        let loc = SourceRange::spanned(&start_loc, end_tok);
+
+        Ok(Ast::ArrayLiteral(ArrayLiteralNode {
+            elements,
+            location: loc,
+        }).into())
+    }
+
+    /// the length of an array type or repeated array literal, which has to be an integer literal
+    pub(crate) fn parse_array_length(&mut self) -> Result<usize, ParseErr> {
+        self.tokens.accept_if_map(|token| {
+            match &token.kind {
+                TokenKind::Lit(LiteralRef::Int(len)) => Ok(*len as usize),
+                other => {
+                    let err = SourceError::new(format!("Expected an integer literal for the array length but got '{:?}' instead", other), token.location);
+                    Err(ParseErr::Fatal(err))
+                }
+            }
+        })
+    }
+
     /// <parens_result> ::= '(' <expr> ')'
     fn parse_parens_expr(&mut self) -> ParseResult {
         // try to accept an lparen. If doesn't match, then non-fatal cause it could be something else
         self.tokens.accept(TokenKind::LParen)
-            .map_err(|err| ParseErr::NonFatal(err))?;
-        let expr = self.parse_expr()?;
+            .map_err(ParseErr::NonFatal)?;
+        // parentheses make it clear where the expression ends
+        let expr = self.with_object_literals(true, Self::parse_expr)?;
         // however, if we started a parenthesized expr, then we need to finish it, so this one would
         // be fatal
         self.tokens.accept(TokenKind::RParen)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         Ok(expr)
     }
 
-    fn parse_literal(&mut self) -> ParseResult {
+    pub(crate) fn parse_literal(&mut self) -> ParseResult {
         self.tokens.accept_if_map(|token| {
-            match token.kind {
+            match &token.kind {
                 TokenKind::Lit(lit_ref) => {
                     Ok(Ast::Literal(LitNode {
-                        lit: lit_ref.into(),
+                        lit: lit_ref.clone().into(),
                         location: token.location
                     }).into())
                 }
@@ -216,7 +380,7 @@ impl<'input> Parser<'input> {
 
     pub(crate) fn parse_ident(&mut self) -> ParseResult {
         self.tokens.accept_if_map(|token| {
-            match token.kind {
+            match &token.kind {
                 TokenKind::Ident(ident) => {
                     Ok(Ast::Identifier(IdentNode {
                         ident: ident.to_string(),
@@ -231,37 +395,3 @@ impl<'input> Parser<'input> {
         })
     }
 }
-
-/// binary operator infix power stuff
-impl<'input> Parser<'input> {
-    pub fn parse_infix_op<const COUNT: usize>(
-        &mut self,
-        accepted_ops: [BinaryOp; COUNT],
-        lhs_rule: fn(&mut Self) -> ParseResult,
-        rhs_rule: fn(&mut Self) -> ParseResult,
-    ) -> ParseResult {
-        let lhs = lhs_rule(self)?;
-        // try to get an infix token
-        let Some(infix_tok) = self.tokens.peek() else { return Ok(lhs) };
-        let Ok(op) = BinaryOp::try_from(infix_tok.clone()) else { return Ok(lhs) };
-
-        if !accepted_ops.contains(&op) {
-            return Ok(lhs);
-        }
-
-        // we kept the infix token, advance by one
-        self.tokens.advance(1);
-
-        // parse the right hand
-        let rhs = rhs_rule(self)?;
-
-        let loc = SourceRange::spanned(lhs.as_ref(), rhs.as_ref());
-
-        Ok(Ast::BinaryOp(BinaryOpNode {
-            op,
-            lhs,
-            rhs,
-            location: loc,
-        }).into())
-    }
-}
@@ -6,33 +6,29 @@ use crate::frontend::token::{TokenKind};
 use crate::types::Type;
 
 /// parsers related to functions
-
 impl<'input> Parser<'input> {
 
     /// <fun_defn> ::= "fun" <ident> "(" <param_list> ")" <ret_type>? <block>
     pub(crate) fn parse_fun_defn(&mut self) -> ParseResult {
         let start_loc = self.tokens.accept(TokenKind::FunDecl)
             .map(|tok| tok.location)
-            .map_err(|err| ParseErr::NonFatal(err))?;
+            .map_err(ParseErr::NonFatal)?;
 
         let ident = self.parse_ident()
             .map_err(|err| err.into_fatal())?;
 
         self.tokens.accept(TokenKind::LParen)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         let params = self.parse_repeated(Self::parse_param, TokenKind::Comma, TokenKind::RParen)?;
 
         self.tokens.accept(TokenKind::RParen)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         // parse an optional return type
-        let ret_type = if self.tokens.check_next(|tok| match tok.kind {
-            TokenKind::Colon => true,
-            _ => false
-        }) {
+        let ret_type = if self.tokens.check_next(|tok| tok.kind == TokenKind::Colon) {
             self.tokens.accept(TokenKind::Colon)
-                .map_err(|err| ParseErr::Fatal(err))?;
+                .map_err(ParseErr::Fatal)?;
             self.parse_type_spec()
         } else {
             Ok(Ast::TypeSpec(TypeSpecNode {
@@ -62,7 +58,7 @@ impl<'input> Parser<'input> {
             .map_err(|err| err.into_fatal())?;
 
         self.tokens.accept(TokenKind::Colon)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         let type_spec = self.parse_type_spec()?;
 
@@ -75,24 +71,32 @@ impl<'input> Parser<'input> {
         }).into())
     }
 
-    /// <fun_call> ::= <ident> '(' <args> ')'
-    pub(crate) fn parse_fun_call(&mut self) -> ParseResult {
-        let ident = self.parse_ident()
-            .map_err(|err| err.into_non_fatal())?;
-        self.tokens.accept(TokenKind::LParen)
-            .map_err(|err| ParseErr::NonFatal(err))?;
-
-        let args = self.parse_repeated(Self::parse_arg, TokenKind::Comma, TokenKind::RParen)?;
-        let end_paren = self.tokens.accept(TokenKind::RParen)
-            .map_err(|err| ParseErr::Fatal(err))?;
-        let loc = SourceRange::spanned(ident.as_ref(), &end_paren.location);
+    /// <fun_call> ::= <member_access> <call_args>
+    pub(crate) fn parse_fun_call(&mut self, callee: Box<Ast>) -> ParseResult {
+        let (args, end_loc) = self.parse_call_args()?;
+        let loc = SourceRange::spanned(callee.as_ref(), &end_loc);
         Ok(Ast::FunCall(FunCallNode {
-            fun_name: ident,
+            fun_name: callee,
             args,
             location: loc,
         }).into())
     }
 
+    /// <call_args> ::= '(' <args> ')'. Also returns where the closing paren is
+    pub(crate) fn parse_call_args(&mut self) -> Result<(Vec<Ast>, SourceRange), ParseErr> {
+        self.tokens.accept(TokenKind::LParen)
+            .map_err(ParseErr::NonFatal)?;
+
+        let args = self.with_object_literals(true, |parser| {
+            parser.parse_repeated(Self::parse_arg, TokenKind::Comma, TokenKind::RParen)
+        })?;
+        let end_loc = self.tokens.accept(TokenKind::RParen)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::Fatal)?;
+
+        Ok((args, end_loc))
+    }
+
     /// <arg> ::= <expr> | <ident> '=' <expr>
     fn parse_arg(&mut self) -> ParseResult {
         self.one_of([
@@ -104,7 +108,7 @@ impl<'input> Parser<'input> {
     fn parse_named_arg(&mut self) -> ParseResult {
         let named_arg = self.parse_ident()?;
         self.tokens.accept(TokenKind::Assign)
-            .map_err(|err| ParseErr::NonFatal(err))?;
+            .map_err(ParseErr::NonFatal)?;
 
         let value = self.parse_expr()
             .map_err(|err| err.into_fatal())?;
@@ -1,5 +1,6 @@
-use crate::frontend::ast::{Ast, CompositionSpecNode, FieldDeclarationNode, ObjectDeclarationNode};
+use crate::frontend::ast::{Ast, CompositionSpecNode, EnumDeclarationNode, EnumVariantNode, FieldDeclarationNode, NamedArgNode, ObjectDeclarationNode, ObjectLiteralNode, VariantPayload};
 use crate::error::parse::ParseErr;
+use crate::error::source::SourceError;
 use crate::frontend::location::{HasLocation, SourceRange};
 use crate::frontend::parser::{Parser, ParseResult};
 use crate::frontend::token::TokenKind;
@@ -10,7 +11,7 @@ impl<'input> Parser<'input> {
     pub(crate) fn parse_object_decl(&mut self) -> ParseResult {
         let start_loc = self.tokens.accept(TokenKind::ObjDecl)
             .map(|tok| tok.location)
-            .map_err(|err| ParseErr::NonFatal(err))?;
+            .map_err(ParseErr::NonFatal)?;
 
         let obj_name = self.parse_ident()
             .map_err(|err| err.into_fatal())?;
@@ -18,12 +19,12 @@ impl<'input> Parser<'input> {
         let comp_specs = self.parse_composition_specs()?;
 
         self.tokens.accept(TokenKind::LBrace)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         let props = self.parse_repeated(Self::parse_prop, TokenKind::Semicolon, TokenKind::RBrace)?;
         let end_loc = self.tokens.accept(TokenKind::RBrace)
             .map(|tok| tok.location)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         let loc = SourceRange::spanned(&start_loc, &end_loc);
 
@@ -35,10 +36,10 @@ impl<'input> Parser<'input> {
         }).into())
     }
 
-    fn parse_composition_specs(&mut self) -> Result<Vec<Box<Ast>>, ParseErr> {
+    fn parse_composition_specs(&mut self) -> Result<Vec<Ast>, ParseErr> {
         if self.tokens.check_next(|tok| tok.kind == TokenKind::Composes) {
             self.tokens.accept(TokenKind::Composes)
-                .map_err(|err| ParseErr::Fatal(err))?;
+                .map_err(ParseErr::Fatal)?;
             self.parse_repeated(Self::parse_composition_spec, TokenKind::Comma, TokenKind::LBrace)
         } else {
             Ok(Vec::default())
@@ -52,7 +53,7 @@ impl<'input> Parser<'input> {
 
         let (alias, loc) = if self.tokens.check_next(|tok| tok.kind == TokenKind::As) {
             self.tokens.accept(TokenKind::As)
-                .map_err(|err| ParseErr::Fatal(err))?;
+                .map_err(ParseErr::Fatal)?;
 
             let alias = self.parse_ident()?;
             let loc = SourceRange::spanned(type_name.as_ref(), alias.as_ref());
@@ -74,7 +75,7 @@ impl<'input> Parser<'input> {
             .map_err(|err| err.into_fatal())?;
 
         self.tokens.accept(TokenKind::Colon)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         let type_spec = self.parse_type_spec()?;
 
@@ -86,4 +87,114 @@ impl<'input> Parser<'input> {
             location: loc,
         }).into())
     }
+
+    /// <enum_decl> ::= "enum" <ident> "{" <variant> ( "," <variant> )* "}"
+    pub(crate) fn parse_enum_decl(&mut self) -> ParseResult {
+        let start_loc = self.tokens.accept(TokenKind::EnumDecl)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::NonFatal)?;
+
+        let enum_name = self.parse_ident()
+            .map_err(|err| err.into_fatal())?;
+
+        self.tokens.accept(TokenKind::LBrace)
+            .map_err(ParseErr::Fatal)?;
+
+        let variants = self.parse_repeated(Self::parse_variant, TokenKind::Comma, TokenKind::RBrace)?;
+        let end_loc = self.tokens.accept(TokenKind::RBrace)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::Fatal)?;
+
+        let loc = SourceRange::spanned(&start_loc, &end_loc);
+
+        Ok(Ast::EnumDeclaration(EnumDeclarationNode {
+            name: enum_name,
+            variants,
+            location: loc,
+        }).into())
+    }
+
+    /// <variant> ::= <ident> ( "(" <type_spec> ( "," <type_spec> )* ")" | "{" <prop> ( ";" <prop> )* "}" )?
+    fn parse_variant(&mut self) -> ParseResult {
+        let variant_name = self.parse_ident()
+            .map_err(|err| err.into_fatal())?;
+
+        let next_kind = self.tokens.peek().map(|tok| tok.kind.clone());
+        let (payload, loc) = match next_kind {
+            Some(TokenKind::LParen) => {
+                self.tokens.next();
+                let values = self.parse_repeated(Self::parse_type_spec, TokenKind::Comma, TokenKind::RParen)?;
+                let end_loc = self.tokens.accept(TokenKind::RParen)
+                    .map(|tok| tok.location)
+                    .map_err(ParseErr::Fatal)?;
+                (VariantPayload::Tuple(values), SourceRange::spanned(variant_name.as_ref(), &end_loc))
+            }
+            Some(TokenKind::LBrace) => {
+                self.tokens.next();
+                let fields = self.parse_repeated(Self::parse_prop, TokenKind::Semicolon, TokenKind::RBrace)?;
+                let end_loc = self.tokens.accept(TokenKind::RBrace)
+                    .map(|tok| tok.location)
+                    .map_err(ParseErr::Fatal)?;
+                (VariantPayload::Struct(fields), SourceRange::spanned(variant_name.as_ref(), &end_loc))
+            }
+            _ => (VariantPayload::Unit, variant_name.source_range()),
+        };
+
+        Ok(Ast::EnumVariant(EnumVariantNode {
+            name: variant_name,
+            payload,
+            location: loc,
+        }).into())
+    }
+
+    /// <object_literal> ::= <ident> "{" ( <field_init> ( "," <field_init> )* )? "}"
+    pub(crate) fn parse_object_literal(&mut self) -> ParseResult {
+        let obj_name = self.parse_ident()?;
+        if !self.object_literals_allowed {
+            let err = SourceError::new("An object literal can't be used here without parentheses", obj_name.source_range());
+            return Err(ParseErr::NonFatal(err));
+        }
+
+        self.tokens.accept(TokenKind::LBrace)
+            .map_err(ParseErr::NonFatal)?;
+
+        // the braces delimit the literal, so nested literals are fine again
+        let fields = self.with_object_literals(true, |parser| {
+            parser.parse_repeated(Self::parse_field_init, TokenKind::Comma, TokenKind::RBrace)
+        })?;
+        let end_loc = self.tokens.accept(TokenKind::RBrace)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::Fatal)?;
+
+        let loc = SourceRange::spanned(obj_name.as_ref(), &end_loc);
+
+        Ok(Ast::ObjectLiteral(ObjectLiteralNode {
+            name: obj_name,
+            fields,
+            location: loc,
+        }).into())
+    }
+
+    /// <field_init> ::= <ident> ( "=" <expr> )?
+    fn parse_field_init(&mut self) -> ParseResult {
+        let field_name = self.parse_ident()
+            .map_err(|err| err.into_fatal())?;
+
+        // `name` on its own is shorthand for `name = name`
+        let value = if self.tokens.check_next(|tok| tok.kind == TokenKind::Assign) {
+            self.tokens.next();
+            self.parse_expr()
+                .map_err(|err| err.into_fatal())?
+        } else {
+            field_name.clone()
+        };
+
+        let loc = SourceRange::spanned(field_name.as_ref(), value.as_ref());
+
+        Ok(Ast::NamedArg(NamedArgNode {
+            param_name: field_name,
+            value,
+            location: loc,
+        }).into())
+    }
 }
@@ -0,0 +1,140 @@
+use crate::frontend::ast::{Ast, MatchArm, Pattern, PatternField};
+use crate::error::parse::ParseErr;
+use crate::error::source::SourceError;
+use crate::frontend::location::SourceRange;
+use crate::frontend::parser::Parser;
+use crate::frontend::token::TokenKind;
+
+/// The parser for match arms and the patterns they test
+impl<'input> Parser<'input> {
+    /// <match_arm> ::= <pattern> ( "if" <expr> )? "=>" ( <block> | <expr> )
+    pub(crate) fn parse_match_arm(&mut self) -> Result<MatchArm, ParseErr> {
+        let pattern = self.parse_pattern()?;
+
+        let guard = if self.tokens.check_next(|tok| tok.kind == TokenKind::If) {
+            self.tokens.next();
+            let guard = self.parse_expr()
+                .map_err(|err| err.into_fatal())?;
+            Some(guard)
+        } else {
+            None
+        };
+
+        self.tokens.accept(TokenKind::FatArrow)
+            .map_err(ParseErr::Fatal)?;
+
+        let body = self.one_of([
+            Self::parse_block,
+            Self::parse_expr,
+        ])?;
+
+        Ok(MatchArm {
+            pattern,
+            guard,
+            body,
+        })
+    }
+
+    /// <pattern> ::= "_" | <literal> | "some" <pattern> | <ident>
+    ///             | <ident> "{" <pattern_fields> "}"
+    ///             | <ident> "." <ident> ( "(" <pattern_fields> ")" )?
+    pub(crate) fn parse_pattern(&mut self) -> Result<Pattern, ParseErr> {
+        let start_loc = self.tokens.peek().unwrap().location;
+        if self.tokens.check_next(|tok| tok.kind == TokenKind::Some) {
+            self.tokens.next();
+            let inner = self.parse_pattern()?;
+            let loc = SourceRange::spanned(&start_loc, &inner);
+            Ok(Pattern::Some {
+                inner: inner.into(),
+                location: loc,
+            })
+        } else if self.tokens.check_next(|tok| matches!(tok.kind, TokenKind::Lit(_))) {
+            let Ast::Literal(lit) = *self.parse_literal()? else {
+                unreachable!()
+            };
+
+            Ok(Pattern::Literal(lit))
+        } else if self.tokens.check_next(|tok| tok.kind == TokenKind::Ident("_")) {
+            self.tokens.next();
+            Ok(Pattern::Wildcard(start_loc))
+        } else if self.tokens.check_next(|tok| matches!(tok.kind, TokenKind::Ident(_))) {
+            let name = self.parse_ident()?.into_ident();
+            if self.tokens.check_next(|tok| tok.kind == TokenKind::LBrace) {
+                self.tokens.next();
+                let (fields, end_loc) = self.parse_pattern_fields(TokenKind::RBrace)?;
+                let loc = SourceRange::spanned(&name.location, &end_loc);
+                Ok(Pattern::Object {
+                    name,
+                    fields,
+                    location: loc,
+                })
+            } else if self.tokens.check_next(|tok| tok.kind == TokenKind::Access) {
+                self.tokens.next();
+                let variant = self.parse_ident()
+                    .map_err(|err| err.into_fatal())?
+                    .into_ident();
+
+                let (values, end_loc) = if self.tokens.check_next(|tok| tok.kind == TokenKind::LParen) {
+                    self.tokens.next();
+                    let (values, end_loc) = self.parse_pattern_fields(TokenKind::RParen)?;
+                    (Some(values), end_loc)
+                } else {
+                    (None, variant.location)
+                };
+
+                let loc = SourceRange::spanned(&name.location, &end_loc);
+                Ok(Pattern::Variant {
+                    enum_name: name,
+                    variant,
+                    values,
+                    location: loc,
+                })
+            } else {
+                Ok(Pattern::Binding(name))
+            }
+        } else {
+            let found = format!("{:?}", self.tokens.peek().unwrap().kind);
+            let err = SourceError::new(format!("Expected a pattern but got '{}' instead", found), start_loc);
+            Err(ParseErr::Fatal(err))
+        }
+    }
+
+    /// <pattern_fields> ::= ( <pattern_field> ( "," <pattern_field> )* ","? )? followed by the
+    /// closing token. Also returns where the closing token is
+    fn parse_pattern_fields(&mut self, close: TokenKind) -> Result<(Vec<PatternField>, SourceRange), ParseErr> {
+        let mut fields = Vec::<PatternField>::new();
+        while !self.tokens.check_next(|tok| tok.kind == close) {
+            fields.push(self.parse_pattern_field()?);
+            if !self.tokens.check_next(|tok| tok.kind == close) {
+                self.tokens.accept(TokenKind::Comma)
+                    .map_err(ParseErr::Fatal)?;
+            }
+        }
+
+        let end_loc = self.tokens.accept(close)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::Fatal)?;
+
+        Ok((fields, end_loc))
+    }
+
+    /// <pattern_field> ::= <ident> "=" <pattern> | <pattern>
+    fn parse_pattern_field(&mut self) -> Result<PatternField, ParseErr> {
+        let is_named = self.tokens.check_next(|tok| matches!(tok.kind, TokenKind::Ident(_)))
+            && self.tokens.check_nth(1, |tok| tok.kind == TokenKind::Assign);
+
+        let name = if is_named {
+            let name = self.parse_ident()?.into_ident();
+            self.tokens.next();
+            Some(name)
+        } else {
+            None
+        };
+
+        let pattern = self.parse_pattern()?;
+        Ok(PatternField {
+            name,
+            pattern,
+        })
+    }
+}
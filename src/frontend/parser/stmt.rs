@@ -1,6 +1,8 @@
-use crate::frontend::ast::{AssignmentNode, Ast, LitNode, ReturnNode, TypeSpecNode, VariableDeclarationNode, WhileNode};
+use crate::frontend::ast::{AssignmentNode, Ast, BreakNode, ContinueNode, ForNode, LitNode, ReturnNode, TypeSpecNode, VariableDeclarationNode, WhileNode};
 use crate::error::parse::ParseErr;
+use crate::error::source::SourceError;
 use crate::literal::Literal;
+use crate::operators::BinaryOp;
 use crate::frontend::location::SourceRange;
 use crate::frontend::parser::{Parser, ParseResult};
 use crate::frontend::token::TokenKind;
@@ -9,12 +11,12 @@ use crate::types::{Type, VariableDeclarationMode};
 impl<'input> Parser<'input> {
     pub(crate) fn parse_block(&mut self) -> ParseResult {
         self.tokens.accept(TokenKind::LBrace)
-            .map_err(|err| ParseErr::NonFatal(err))?;
+            .map_err(ParseErr::NonFatal)?;
 
         let stmts = self.parse_repeated(Self::parse_stmt, TokenKind::Semicolon, TokenKind::RBrace)?;
 
         self.tokens.accept(TokenKind::RBrace)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         Ok(Ast::Block(stmts).into())
     }
@@ -23,7 +25,10 @@ impl<'input> Parser<'input> {
         let stmt_result = self.one_of([
             Self::parse_block,
             Self::parse_while,
+            Self::parse_for,
             Self::parse_return_stmt,
+            Self::parse_break_stmt,
+            Self::parse_continue_stmt,
             Self::parse_assignment_stmt,
             Self::parse_expr_stmt,
         ]);
@@ -41,7 +46,7 @@ impl<'input> Parser<'input> {
     fn parse_return_stmt(&mut self) -> ParseResult {
         let start_loc = self.tokens.accept(TokenKind::Return)
             .map(|tok| tok.location)
-            .map_err(|err| ParseErr::NonFatal(err))?;
+            .map_err(ParseErr::NonFatal)?;
 
         let (ret_value, loc) = if self.tokens.check_next(|tok| tok.kind == TokenKind::Semicolon) {
             let ret = Box::new(Ast::Literal(LitNode { lit: Literal::Unit, location: SourceRange::default() }));
@@ -58,14 +63,72 @@ impl<'input> Parser<'input> {
         }).into())
     }
 
+    /// <break_stmt> ::= "break" <expr>?
+    fn parse_break_stmt(&mut self) -> ParseResult {
+        let start_loc = self.tokens.accept(TokenKind::Break)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::NonFatal)?;
+
+        let (value, loc) = if self.tokens.check_next(|tok| tok.kind == TokenKind::Semicolon || tok.kind == TokenKind::RBrace) {
+            (None, start_loc)
+        } else {
+            let value = self.parse_expr()
+                .map_err(|err| err.into_fatal())?;
+            let loc = SourceRange::spanned(&start_loc, value.as_ref());
+            (Some(value), loc)
+        };
+
+        Ok(Ast::Break(BreakNode {
+            value,
+            location: loc,
+        }).into())
+    }
+
+    /// <continue_stmt> ::= "continue"
+    fn parse_continue_stmt(&mut self) -> ParseResult {
+        let loc = self.tokens.accept(TokenKind::Continue)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::NonFatal)?;
+
+        Ok(Ast::Continue(ContinueNode {
+            location: loc,
+        }).into())
+    }
+
+    /// <assignment_stmt> ::= ( <var_decl> | <expr> ) ( "=" | COMPOUND_ASSIGN ) <expr>
     fn parse_assignment_stmt(&mut self) -> ParseResult {
         let lhs = self.one_of([
             Self::parse_var_decl,
             Self::parse_expr,
         ])?;
 
-        self.tokens.accept(TokenKind::Assign)
-            .map_err(|err| ParseErr::NonFatal(err))?;
+        let (op, op_loc) = self.tokens.accept_if_map(|tok| {
+            let op = match tok.kind {
+                TokenKind::Assign => None,
+                TokenKind::PlusAssign => Some(BinaryOp::Plus),
+                TokenKind::MinusAssign => Some(BinaryOp::Minus),
+                TokenKind::TimesAssign => Some(BinaryOp::Times),
+                TokenKind::DividesAssign => Some(BinaryOp::Divides),
+                TokenKind::ModuloAssign => Some(BinaryOp::Modulo),
+                TokenKind::BitAndAssign => Some(BinaryOp::BitAnd),
+                TokenKind::BitOrAssign => Some(BinaryOp::BitOr),
+                TokenKind::BitXorAssign => Some(BinaryOp::BitXor),
+                TokenKind::ShiftLeftAssign => Some(BinaryOp::ShiftLeft),
+                TokenKind::ShiftRightAssign => Some(BinaryOp::ShiftRight),
+                _ => {
+                    let err = SourceError::new(format!("Expected an assignment but got '{:?}' instead", tok.kind), tok.location);
+                    return Err(ParseErr::NonFatal(err));
+                }
+            };
+
+            Ok((op, tok.location))
+        })?;
+
+        // a compound assignment needs an existing value to combine with
+        if op.is_some() && matches!(lhs.as_ref(), Ast::VariableDeclaration(_)) {
+            let err = SourceError::new("Compound assignments cannot declare a variable", op_loc);
+            return Err(ParseErr::Fatal(err));
+        }
 
         let rhs = self.parse_expr()?;
 
@@ -74,14 +137,28 @@ impl<'input> Parser<'input> {
         Ok(Ast::Assignment(AssignmentNode {
             decl: lhs,
             rhs,
+            op,
             location: loc,
         }).into())
     }
 
+    /// <var_decl> ::= ( "let" "mut"? | "const" ) <ident> ( ":" <type_spec> )?
     fn parse_var_decl(&mut self) -> ParseResult {
-        let start_loc = self.tokens.accept(TokenKind::Let)
-            .map(|tok| tok.location)
-            .map_err(|err| ParseErr::NonFatal(err))?;
+        let (start_loc, decl_mode) = self.tokens.accept_if_map(|tok| match tok.kind {
+            TokenKind::Let => Ok((tok.location, VariableDeclarationMode::Immutable)),
+            TokenKind::Const => Ok((tok.location, VariableDeclarationMode::Const)),
+            _ => {
+                let err = SourceError::new(format!("Expected 'let' or 'const' but got '{:?}' instead", tok.kind), tok.location);
+                Err(ParseErr::NonFatal(err))
+            }
+        })?;
+
+        let decl_mode = if decl_mode == VariableDeclarationMode::Immutable && self.tokens.check_next(|tok| tok.kind == TokenKind::Mut) {
+            self.tokens.next();
+            VariableDeclarationMode::Mutable
+        } else {
+            decl_mode
+        };
 
         let var_name = self.parse_ident()
             .map_err(|err| err.into_fatal())?;
@@ -101,7 +178,7 @@ impl<'input> Parser<'input> {
         };
 
         Ok(Ast::VariableDeclaration(VariableDeclarationNode {
-            decl_mode: VariableDeclarationMode::Const,
+            decl_mode,
             name: var_name,
             tp: type_spec,
             location: loc,
@@ -109,18 +186,18 @@ impl<'input> Parser<'input> {
     }
 
     /// <while_loop> ::= "while" "(" <expr> ")" <block>
-    fn parse_while(&mut self) -> ParseResult {
+    pub(crate) fn parse_while(&mut self) -> ParseResult {
         let start_location = self.tokens.accept(TokenKind::While)
             .map(|tok| tok.location)
-            .map_err(|err| ParseErr::NonFatal(err))?;
+            .map_err(ParseErr::NonFatal)?;
 
         self.tokens.accept(TokenKind::LParen)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         let cond = self.parse_expr()?;
 
         self.tokens.accept(TokenKind::RParen)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         let body = self.parse_block()?;
 
@@ -133,6 +210,35 @@ impl<'input> Parser<'input> {
         }).into())
     }
 
+    /// <for_loop> ::= "for" <ident> "in" <expr> <block>
+    pub(crate) fn parse_for(&mut self) -> ParseResult {
+        let start_location = self.tokens.accept(TokenKind::For)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::NonFatal)?;
+
+        let binding = self.parse_ident()
+            .map_err(|err| err.into_fatal())?;
+
+        self.tokens.accept(TokenKind::In)
+            .map_err(ParseErr::Fatal)?;
+
+        // the brace after the iterable opens the body, not an object literal
+        let iterable = self.with_object_literals(false, Self::parse_expr)
+            .map_err(|err| err.into_fatal())?;
+
+        let body = self.parse_block()
+            .map_err(|err| err.into_fatal())?;
+
+        let loc = SourceRange::spanned(&start_location, body.as_ref());
+
+        Ok(Ast::For(ForNode {
+            binding,
+            iterable,
+            body,
+            location: loc,
+        }).into())
+    }
+
     fn parse_expr_stmt(&mut self) -> ParseResult {
         self.parse_expr()
     }
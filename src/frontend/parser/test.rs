@@ -0,0 +1,341 @@
+use crate::error::source::SourceError;
+use crate::frontend::ast::{Ast, Pattern, VariantPayload};
+use crate::frontend::input::SourceInput;
+use crate::frontend::lexer::Lexer;
+use crate::frontend::parser::{ParseResult, Parser};
+use crate::types::VariableDeclarationMode;
+
+/// parse a source snippet with the given rule
+fn parse_with<ParseT>(source: &str, parse: ParseT) -> Result<Box<Ast>, SourceError>
+where
+    ParseT: FnOnce(&mut Parser<'_>) -> ParseResult
+{
+    let input = SourceInput::raw(source);
+    let tokens = Lexer::new(&input).into_token_stream().expect("Lexer should not error");
+    let mut parser = Parser::new(tokens);
+    parse(&mut parser).map_err(SourceError::from)
+}
+
+#[test]
+fn parse_for_loop() {
+    let ast = parse_with("for x in xs { x; }", |parser| parser.parse_stmt())
+        .expect("Parser should accept a for loop");
+    let Ast::For(node) = *ast else {
+        panic!("Expected a for loop but got {:?}", ast)
+    };
+
+    assert_eq!(node.binding.into_ident().ident, "x");
+    assert!(matches!(*node.iterable, Ast::Identifier(ref iterable) if iterable.ident == "xs"));
+}
+
+#[test]
+fn parse_for_loop_without_in() {
+    let err = parse_with("for x xs { }", |parser| parser.parse_stmt())
+        .expect_err("Parser should reject a for loop without 'in'");
+    assert_eq!(err.msg(), "Expected 'In' but got Ident(\"xs\") instead");
+}
+
+#[test]
+fn parse_break_with_and_without_value() {
+    let ast = parse_with("break;", |parser| parser.parse_stmt())
+        .expect("Parser should accept a break");
+    assert!(matches!(*ast, Ast::Break(ref node) if node.value.is_none()));
+
+    let ast = parse_with("break x + 1;", |parser| parser.parse_stmt())
+        .expect("Parser should accept a break with a value");
+    assert!(matches!(*ast, Ast::Break(ref node) if matches!(node.value.as_deref(), Some(Ast::BinaryOp(_)))));
+}
+
+#[test]
+fn parse_continue() {
+    let ast = parse_with("continue;", |parser| parser.parse_stmt())
+        .expect("Parser should accept a continue");
+    assert!(matches!(*ast, Ast::Continue(_)));
+}
+
+#[test]
+fn parse_var_decl_modes() {
+    for (source, expected) in [
+        ("let x = 1", VariableDeclarationMode::Immutable),
+        ("let mut x = 1", VariableDeclarationMode::Mutable),
+        ("const x = 1", VariableDeclarationMode::Const),
+    ] {
+        let ast = parse_with(source, |parser| parser.parse_stmt())
+            .expect("Parser should accept a declaration");
+        let Ast::Assignment(node) = *ast else {
+            panic!("Expected an assignment but got {:?}", ast)
+        };
+
+        assert!(matches!(*node.decl, Ast::VariableDeclaration(ref decl) if decl.decl_mode == expected));
+    }
+}
+
+#[test]
+fn parse_var_decl_without_name() {
+    let err = parse_with("let mut = 1", |parser| parser.parse_stmt())
+        .expect_err("Parser should reject a declaration without a name");
+    assert_eq!(err.msg(), "Expected identifier but got 'Assign' instead");
+}
+
+#[test]
+fn parse_function_types() {
+    for (source, expected) in [
+        ("fun()", "fun()"),
+        ("fun(int, b: uint): bool", "fun(int, b: uint): bool"),
+        ("fun(fun(int): int): []int", "fun(fun(int): int): []int"),
+    ] {
+        let ast = parse_with(source, |parser| parser.parse_type_spec())
+            .expect("Parser should accept a function type");
+        let Ast::TypeSpec(spec) = *ast else {
+            panic!("Expected a type spec but got {:?}", ast)
+        };
+
+        assert_eq!(spec.tp.to_string(), expected);
+    }
+}
+
+#[test]
+fn parse_function_type_without_closing_paren() {
+    let err = parse_with("fun(int, bool", |parser| parser.parse_type_spec())
+        .expect_err("Parser should reject an unclosed function type");
+    assert_eq!(err.msg(), "Expected 'Comma' or 'RParen', but got 'Eof' instead");
+}
+
+#[test]
+fn parse_array_types() {
+    for (source, expected) in [("[3]uint", "[3]uint"), ("[]int", "[]int"), ("[2][]&long", "[2][]&long")] {
+        let ast = parse_with(source, |parser| parser.parse_type_spec())
+            .expect("Parser should accept an array type");
+        let Ast::TypeSpec(spec) = *ast else {
+            panic!("Expected a type spec but got {:?}", ast)
+        };
+
+        assert_eq!(spec.tp.to_string(), expected);
+    }
+}
+
+#[test]
+fn parse_array_type_with_non_literal_length() {
+    let err = parse_with("[n]int", |parser| parser.parse_type_spec())
+        .expect_err("Parser should reject an array length that isn't a literal");
+    assert_eq!(err.msg(), "Expected an integer literal for the array length but got 'Ident(\"n\")' instead");
+}
+
+#[test]
+fn parse_array_literals() {
+    let ast = parse_with("[1, 2, 3]", |parser| parser.parse_expr())
+        .expect("Parser should accept an array literal");
+    assert!(matches!(*ast, Ast::ArrayLiteral(ref node) if node.elements.len() == 3));
+
+    let ast = parse_with("[0; 4]", |parser| parser.parse_expr())
+        .expect("Parser should accept a repeated array literal");
+    assert!(matches!(*ast, Ast::ArrayRepeat(ref node) if node.count == 4));
+}
+
+#[test]
+fn parse_repeated_array_literal_with_non_literal_count() {
+    let err = parse_with("[0; n]", |parser| parser.parse_expr())
+        .expect_err("Parser should reject a repeat count that isn't a literal");
+    assert_eq!(err.msg(), "Expected an integer literal for the array length but got 'Ident(\"n\")' instead");
+}
+
+#[test]
+fn parse_array_access() {
+    let ast = parse_with("xs[i][0].len", |parser| parser.parse_expr())
+        .expect("Parser should accept chained array accesses");
+    let Ast::FieldAccess(access) = *ast else {
+        panic!("Expected a field access but got {:?}", ast)
+    };
+    let Ast::ArrayAccess(outer) = *access.object else {
+        panic!("Expected an array access but got {:?}", access.object)
+    };
+
+    assert!(matches!(*outer.derefed, Ast::ArrayAccess(_)));
+    assert!(matches!(*outer.access, Ast::Literal(_)));
+}
+
+#[test]
+fn parse_array_access_without_closing_bracket() {
+    let err = parse_with("xs[0", |parser| parser.parse_expr())
+        .expect_err("Parser should reject an unclosed array access");
+    assert_eq!(err.msg(), "Expected 'RBracket' but got Eof instead");
+}
+
+#[test]
+fn parse_field_access_and_method_calls() {
+    let ast = parse_with("get_person().name", |parser| parser.parse_expr())
+        .expect("Parser should accept a field access on a call");
+    let Ast::FieldAccess(access) = *ast else {
+        panic!("Expected a field access but got {:?}", ast)
+    };
+    assert!(matches!(*access.object, Ast::FunCall(_)));
+
+    let ast = parse_with("(a).b.greet(1, 2)", |parser| parser.parse_expr())
+        .expect("Parser should accept a method call on a field");
+    let Ast::MethodCall(call) = *ast else {
+        panic!("Expected a method call but got {:?}", ast)
+    };
+    assert_eq!(call.args.len(), 2);
+    assert!(matches!(*call.receiver, Ast::FieldAccess(_)));
+
+    let ast = parse_with("make()(3)", |parser| parser.parse_expr())
+        .expect("Parser should accept a call on a call");
+    let Ast::FunCall(call) = *ast else {
+        panic!("Expected a call but got {:?}", ast)
+    };
+    assert!(matches!(*call.fun_name, Ast::FunCall(_)));
+}
+
+#[test]
+fn parse_field_access_without_field() {
+    let err = parse_with("person.", |parser| parser.parse_expr())
+        .expect_err("Parser should reject a field access without a field name");
+    assert_eq!(err.msg(), "Expected identifier but got 'Eof' instead");
+}
+
+#[test]
+fn parse_casts() {
+    let ast = parse_with("-x as long as double?", |parser| parser.parse_expr())
+        .expect("Parser should accept chained casts");
+    let Ast::Cast(outer) = *ast else {
+        panic!("Expected a cast but got {:?}", ast)
+    };
+    let Ast::TypeSpec(spec) = *outer.tp else {
+        panic!("Expected a type spec but got {:?}", outer.tp)
+    };
+    assert_eq!(spec.tp.to_string(), "double?");
+
+    let Ast::Cast(inner) = *outer.expr else {
+        panic!("Expected a cast but got {:?}", outer.expr)
+    };
+    assert!(matches!(*inner.expr, Ast::UnaryOp(_)));
+}
+
+#[test]
+fn parse_cast_without_type() {
+    let err = parse_with("x as", |parser| parser.parse_expr())
+        .expect_err("Parser should reject a cast without a type");
+    assert_eq!(err.msg(), "Expected identifier but got 'Eof' instead");
+}
+
+#[test]
+fn parse_object_literals() {
+    let ast = parse_with("Student { Person = Person { name = \"a\", age }, gpa = 1.0 }", |parser| parser.parse_expr())
+        .expect("Parser should accept nested object literals");
+    let Ast::ObjectLiteral(literal) = *ast else {
+        panic!("Expected an object literal but got {:?}", ast)
+    };
+    assert_eq!(literal.fields.len(), 2);
+
+    let Ast::NamedArg(ref part) = literal.fields[0] else {
+        panic!("Expected a field initializer but got {:?}", literal.fields[0])
+    };
+    let Ast::ObjectLiteral(ref person) = *part.value else {
+        panic!("Expected an object literal but got {:?}", part.value)
+    };
+
+    // shorthand initializers use the variable of the same name
+    let Ast::NamedArg(ref age) = person.fields[1] else {
+        panic!("Expected a field initializer but got {:?}", person.fields[1])
+    };
+    assert!(matches!(*age.value, Ast::Identifier(ref ident) if ident.ident == "age"));
+}
+
+#[test]
+fn parse_object_literal_without_closing_brace() {
+    let err = parse_with("Person { age = 3", |parser| parser.parse_expr())
+        .expect_err("Parser should reject an unclosed object literal");
+    assert_eq!(err.msg(), "Expected 'Comma' or 'RBrace', but got 'Eof' instead");
+}
+
+#[test]
+fn parse_enum_declaration() {
+    let ast = parse_with("enum Shape { Circle(double), Rect { w: double; h: double; }, Empty }", |parser| parser.parse_enum_decl())
+        .expect("Parser should accept an enum declaration");
+    let Ast::EnumDeclaration(decl) = *ast else {
+        panic!("Expected an enum declaration but got {:?}", ast)
+    };
+
+    let payloads = decl.variants.iter()
+        .map(|variant| match variant {
+            Ast::EnumVariant(variant) => &variant.payload,
+            other => panic!("Expected an enum variant but got {:?}", other),
+        })
+        .collect::<Vec<_>>();
+    assert!(matches!(payloads.as_slice(), [VariantPayload::Tuple(values), VariantPayload::Struct(fields), VariantPayload::Unit]
+        if values.len() == 1 && fields.len() == 2));
+}
+
+#[test]
+fn parse_enum_declaration_without_separator() {
+    let err = parse_with("enum Shape { Circle(double) Empty }", |parser| parser.parse_enum_decl())
+        .expect_err("Parser should reject variants that aren't separated by commas");
+    assert_eq!(err.msg(), "Expected 'Comma' or 'RBrace', but got 'Ident(\"Empty\")' instead");
+}
+
+#[test]
+fn parse_match_patterns() {
+    let ast = parse_with("match s { Shape.Circle(r) if r > 1.0 => r, Shape.Rect(w = 1.0, h) => h, Point { x = 0, y } => y, some null => 0, _ => { 1 } }", |parser| parser.parse_expr())
+        .expect("Parser should accept a match with every kind of pattern");
+    let Ast::Match(node) = *ast else {
+        panic!("Expected a match but got {:?}", ast)
+    };
+
+    let patterns = node.arms.iter()
+        .map(|arm| &arm.pattern)
+        .collect::<Vec<_>>();
+    assert!(matches!(patterns.as_slice(), [
+        Pattern::Variant { values: Some(_), .. },
+        Pattern::Variant { .. },
+        Pattern::Object { .. },
+        Pattern::Some { .. },
+        Pattern::Wildcard(_),
+    ]));
+    assert!(node.arms[0].guard.is_some());
+}
+
+#[test]
+fn parse_match_arm_without_arrow() {
+    let err = parse_with("match x { 1 2 }", |parser| parser.parse_expr())
+        .expect_err("Parser should reject a match arm without '=>'");
+    assert_eq!(err.msg(), "Expected 'FatArrow' but got Lit(Int(2)) instead");
+
+    let err = parse_with("match x { => 2 }", |parser| parser.parse_expr())
+        .expect_err("Parser should reject a match arm without a pattern");
+    assert_eq!(err.msg(), "Expected a pattern but got 'FatArrow' instead");
+}
+
+#[test]
+fn parse_else_if_chain() {
+    let ast = parse_with("if (a) { 1 } else if (b) { 2 } else { 3 }", |parser| parser.parse_expr())
+        .expect("Parser should accept an else if chain");
+    let Ast::CondExpr(outer) = *ast else {
+        panic!("Expected a conditional but got {:?}", ast)
+    };
+
+    // the chained conditional is the only thing in the else branch
+    let Some(Ast::Block(false_branch)) = outer.false_branch.as_deref() else {
+        panic!("Expected a block but got {:?}", outer.false_branch)
+    };
+    let [chained] = false_branch.as_slice() else {
+        panic!("Expected a single conditional but got {:?}", false_branch)
+    };
+    assert!(matches!(chained, Ast::CondExpr(inner) if inner.false_branch.is_some()));
+}
+
+#[test]
+fn parse_else_without_block() {
+    let err = parse_with("if (a) { 1 } else 2", |parser| parser.parse_expr())
+        .expect_err("Parser should reject an else branch that isn't a block or an if");
+    assert_eq!(err.msg(), "Expected 'LBrace' but got Lit(Int(2)) instead");
+}
+
+#[test]
+fn parse_compilation_unit_reports_errors_in_functions() {
+    let input = SourceInput::raw("fun main() { let c = true; if (c) { } c = false; }");
+    let tokens = Lexer::new(&input).into_token_stream().expect("Lexer should not error");
+    let errors = Parser::new(tokens).parse_compilation_unit()
+        .expect_err("Parser should reject statements that aren't separated by ';'");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].msg(), "Expected 'Semicolon' or 'RBrace', but got 'Ident(\"c\")' instead");
+}
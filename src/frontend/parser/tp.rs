@@ -1,9 +1,9 @@
-use crate::frontend::ast::{Ast, TypeSpecNode};
+use crate::frontend::ast::{Ast, ParamNode, TypeSpecNode};
 use crate::error::parse::ParseErr;
 use crate::frontend::location::{HasLocation, SourceRange};
 use crate::frontend::parser::{Parser, ParseResult};
 use crate::frontend::token::TokenKind;
-use crate::types::Type;
+use crate::types::{FunParam, FunType, Type};
 
 impl<'input> Parser<'input> {
 
@@ -16,29 +16,107 @@ impl<'input> Parser<'input> {
         self.parse_non_scalar_type()
     }
 
-    fn parse_function_type(&mut self) -> Result<Type, ParseErr> {
-        todo!()
+    /// <function_type> ::= "fun" "(" <function_type_params> ")" ( ":" <type> )?
+    fn parse_function_type(&mut self) -> Result<TypeSpecNode, ParseErr> {
+        let start_loc = self.tokens.accept(TokenKind::FunDecl)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::NonFatal)?;
+
+        self.tokens.accept(TokenKind::LParen)
+            .map_err(ParseErr::Fatal)?;
+
+        let params = self.parse_repeated(Self::parse_function_type_param, TokenKind::Comma, TokenKind::RParen)?;
+
+        let end_loc = self.tokens.accept(TokenKind::RParen)
+            .map(|tok| tok.location)
+            .map_err(ParseErr::Fatal)?;
+
+        // like function definitions, a missing return type means unit
+        let (ret, loc) = if self.tokens.check_next(|tok| tok.kind == TokenKind::Colon) {
+            self.tokens.next();
+            let ret = self.parse_type()
+                .map_err(|err| err.into_fatal())?;
+            let loc = SourceRange::spanned(&start_loc, &ret.location);
+            (ret.tp, loc)
+        } else {
+            (Type::Unit, SourceRange::spanned(&start_loc, &end_loc))
+        };
+
+        let args = params.into_iter()
+            .map(|param| match param {
+                Ast::Param(param) => {
+                    let Ast::TypeSpec(spec) = *param.tp else {
+                        panic!("Function type parameter without a type spec")
+                    };
+                    FunParam {
+                        tp: spec.tp.into(),
+                        name: param.name.into_ident().ident,
+                    }
+                }
+                Ast::TypeSpec(spec) => FunParam {
+                    tp: spec.tp.into(),
+                    name: String::new(),
+                },
+                _ => unreachable!()
+            })
+            .collect();
+
+        Ok(TypeSpecNode {
+            tp: Type::Function(FunType {
+                ret: ret.into(),
+                args,
+            }),
+            location: loc,
+        })
+    }
+
+    /// <function_type_param> ::= ( <ident> ":" )? <type>
+    fn parse_function_type_param(&mut self) -> ParseResult {
+        let is_named = self.tokens.check_next(|tok| matches!(tok.kind, TokenKind::Ident(_)))
+            && self.tokens.check_nth(1, |tok| tok.kind == TokenKind::Colon);
+
+        if !is_named {
+            return self.parse_type_spec();
+        }
+
+        let name = self.parse_ident()?;
+        self.tokens.accept(TokenKind::Colon)
+            .map_err(ParseErr::Fatal)?;
+        let tp = self.parse_type_spec()?;
+        let loc = SourceRange::spanned(name.as_ref(), tp.as_ref());
+
+        Ok(Ast::Param(ParamNode {
+            name,
+            tp,
+            location: loc,
+        }).into())
     }
 
-    /// <non_scalar_type> ::= "\[" "\]" <non_scalar_type> | "&" <non_scalar_type> | <optional_type>
+    /// <non_scalar_type> ::= "\[" INT_LITERAL? "\]" <non_scalar_type> | "&" <non_scalar_type> | <optional_type>
     fn parse_non_scalar_type(&mut self) -> Result<TypeSpecNode, ParseErr> {
-        if self.tokens.check_next(|tok| match tok.kind {
-            TokenKind::LBracket |
-            TokenKind::Ref => true,
-            _ => false,
-        }) {
+        if self.tokens.check_next(|tok| matches!(tok.kind, TokenKind::LBracket | TokenKind::Ref)) {
 
             let next_tok = self.tokens.next().unwrap();
-            let kind = next_tok.kind;
+            let kind = next_tok.kind.clone();
             let loc = next_tok.location;
             let (higher_level_type, location) = match kind {
                 TokenKind::LBracket => {
+                    // `[]T` is a view, `[N]T` a sized array
+                    let len = if self.tokens.check_next(|tok| tok.kind == TokenKind::RBracket) {
+                        None
+                    } else {
+                        Some(self.parse_array_length()?)
+                    };
+
                     self.tokens.accept(TokenKind::RBracket)
-                        .map_err(|err| ParseErr::Fatal(err))?;
+                        .map_err(ParseErr::Fatal)?;
                     let inner_type = self.parse_non_scalar_type()?;
-                    let view_type = Type::View(inner_type.tp.into());
+                    let array_type = match len {
+                        Some(len) => Type::Array(inner_type.tp.into(), len),
+                        None => Type::View(inner_type.tp.into()),
+                    };
                     let loc = SourceRange::spanned(&loc, &inner_type.location);
-                    (view_type, loc)
+                    (array_type, loc)
                 }
                 TokenKind::Ref => {
                     let inner_type = self.parse_non_scalar_type()?;
@@ -63,7 +141,7 @@ impl<'input> Parser<'input> {
         if self.tokens.check_next(|tok| tok.kind == TokenKind::Nullable) {
             let end_loc = self.tokens.accept(TokenKind::Nullable)
                 .map(|tok| tok.location)
-                .map_err(|err| ParseErr::Fatal(err))?;
+                .map_err(ParseErr::Fatal)?;
 
             let loc = SourceRange::spanned(&inner_type.location, &end_loc);
 
@@ -77,10 +155,12 @@ impl<'input> Parser<'input> {
         }
     }
 
-    /// <scalar_type> ::= TYPE_KEYWORD | "(" <type> ")"
+    /// <scalar_type> ::= TYPE_KEYWORD | "(" <type> ")" | <function_type>
     fn parse_scalar_type(&mut self) -> Result<TypeSpecNode, ParseErr> {
         if self.tokens.check_next(|tok| tok.kind == TokenKind::LParen) {
             self.parse_parened_type()
+        } else if self.tokens.check_next(|tok| tok.kind == TokenKind::FunDecl) {
+            self.parse_function_type()
         } else {
             self.parse_type_keyword()
         }
@@ -89,12 +169,12 @@ impl<'input> Parser<'input> {
     fn parse_parened_type(&mut self) -> Result<TypeSpecNode, ParseErr> {
         let start_loc = self.tokens.accept(TokenKind::LParen)
             .map(|tok| tok.location)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         let contained_type_spec = self.parse_type()?;
         let end_loc = self.tokens.accept(TokenKind::RParen)
             .map(|tok| tok.location)
-            .map_err(|err| ParseErr::Fatal(err))?;
+            .map_err(ParseErr::Fatal)?;
 
         let loc = SourceRange::spanned(&start_loc, &end_loc);
 
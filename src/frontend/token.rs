@@ -2,9 +2,9 @@
 use crate::literal::{LiteralRef};
 use crate::frontend::location::{HasLocation, SourceRange};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum TokenKind<'input> {
-    EOF,
+    Eof,
     /// identifier
     Ident(&'input str),
     /// literal value
@@ -20,14 +20,24 @@ pub enum TokenKind<'input> {
     LBrace,
     RBrace,
     Colon,
+    FatArrow,
 
     // Operators
     Plus,
     Minus,
     Times,
     Divides,
+    Modulo,
+    Exp,
     Ref,
+    BitOr,
+    BitXor,
+    BitNeg,
+    ShiftLeft,
+    ShiftRight,
     Access,
+    ChainedAccess,
+    NullCoalesce,
     Eq,
     Neq,
     Gt,
@@ -35,6 +45,16 @@ pub enum TokenKind<'input> {
     Lt,
     Lte,
     Assign,
+    PlusAssign,
+    MinusAssign,
+    TimesAssign,
+    DividesAssign,
+    ModuloAssign,
+    BitAndAssign,
+    BitOrAssign,
+    BitXorAssign,
+    ShiftLeftAssign,
+    ShiftRightAssign,
     And,
     Or,
     Not,
@@ -42,7 +62,10 @@ pub enum TokenKind<'input> {
     // General keyword token
     FunDecl,
     ObjDecl,
+    EnumDecl,
     Composes,
+    Match,
+    Some,
     If,
     Else,
     While,
@@ -53,6 +76,7 @@ pub enum TokenKind<'input> {
     Mut,
     Return,
     Break,
+    Continue,
     As,
 
     // Other
@@ -73,12 +97,22 @@ impl<'input> TryFrom<&'input str> for TokenKind<'input> {
             "{" => Ok(TokenKind::LBrace),
             "}" => Ok(TokenKind::RBrace),
             ":" => Ok(TokenKind::Colon),
+            "=>" => Ok(TokenKind::FatArrow),
             "+" => Ok(TokenKind::Plus),
             "-" => Ok(TokenKind::Minus),
             "*" => Ok(TokenKind::Times),
             "/" => Ok(TokenKind::Divides),
+            "%" => Ok(TokenKind::Modulo),
+            "**" => Ok(TokenKind::Exp),
             "&" => Ok(TokenKind::Ref),
+            "|" => Ok(TokenKind::BitOr),
+            "^" => Ok(TokenKind::BitXor),
+            "~" => Ok(TokenKind::BitNeg),
+            "<<" => Ok(TokenKind::ShiftLeft),
+            ">>" => Ok(TokenKind::ShiftRight),
             "." => Ok(TokenKind::Access),
+            "?." => Ok(TokenKind::ChainedAccess),
+            "??" => Ok(TokenKind::NullCoalesce),
             "==" => Ok(TokenKind::Eq),
             "!=" => Ok(TokenKind::Neq),
             ">" => Ok(TokenKind::Gt),
@@ -86,12 +120,25 @@ impl<'input> TryFrom<&'input str> for TokenKind<'input> {
             "<" => Ok(TokenKind::Lt),
             "<=" => Ok(TokenKind::Lte),
             "=" => Ok(TokenKind::Assign),
+            "+=" => Ok(TokenKind::PlusAssign),
+            "-=" => Ok(TokenKind::MinusAssign),
+            "*=" => Ok(TokenKind::TimesAssign),
+            "/=" => Ok(TokenKind::DividesAssign),
+            "%=" => Ok(TokenKind::ModuloAssign),
+            "&=" => Ok(TokenKind::BitAndAssign),
+            "|=" => Ok(TokenKind::BitOrAssign),
+            "^=" => Ok(TokenKind::BitXorAssign),
+            "<<=" => Ok(TokenKind::ShiftLeftAssign),
+            ">>=" => Ok(TokenKind::ShiftRightAssign),
             "&&" => Ok(TokenKind::And),
             "||" => Ok(TokenKind::Or),
             "!" => Ok(TokenKind::Not),
             "?" => Ok(TokenKind::Nullable),
             "fun" => Ok(TokenKind::FunDecl),
             "object" => Ok(TokenKind::ObjDecl),
+            "enum" => Ok(TokenKind::EnumDecl),
+            "match" => Ok(TokenKind::Match),
+            "some" => Ok(TokenKind::Some),
             "if" => Ok(TokenKind::If),
             "else" => Ok(TokenKind::Else),
             "while" => Ok(TokenKind::While),
@@ -102,6 +149,7 @@ impl<'input> TryFrom<&'input str> for TokenKind<'input> {
             "mut" => Ok(TokenKind::Mut),
             "return" => Ok(TokenKind::Return),
             "break" => Ok(TokenKind::Break),
+            "continue" => Ok(TokenKind::Continue),
             "as" => Ok(TokenKind::As),
             "composes" => Ok(TokenKind::Composes),
             "true" => Ok(TokenKind::Lit(LiteralRef::Boolean(true))),
@@ -112,7 +160,7 @@ impl<'input> TryFrom<&'input str> for TokenKind<'input> {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Token<'input> {
     /// what kind of token this is
     pub(crate) kind: TokenKind<'input>,
@@ -1,36 +1,35 @@
+use std::borrow::Cow;
 
-#[derive(Copy, Clone)]
-pub enum LiteralKind {
-    Unit,
-    Null,
-    Boolean,
-    Char,
-    Int,
-    Double,
-    String
-}
-
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum LiteralRef<'input> {
-    Unit,
     Null,
     Boolean(bool),
     Char(char),
+    /// integer literal without a type suffix
     Int(u64),
+    /// integer literal with a 'u' suffix
+    UInt(u64),
+    /// integer literal with an 'l' suffix
+    Long(u64),
+    /// integer literal with a 'ul' suffix
+    ULong(u64),
     Double(f64),
-    String(&'input str),
+    /// string contents with escapes decoded. Borrowed from source when no escapes were present
+    String(Cow<'input, str>),
 }
 
-impl<'input> Into<Literal> for LiteralRef<'input> {
-    fn into(self) -> Literal {
-        match self {
-            LiteralRef::Unit => Literal::Unit,
+impl<'input> From<LiteralRef<'input>> for Literal {
+    fn from(val: LiteralRef<'input>) -> Self {
+        match val {
             LiteralRef::Null => Literal::Null,
             LiteralRef::Boolean(bool) => Literal::Boolean(bool),
             LiteralRef::Char(ch) => Literal::Char(ch),
             LiteralRef::Int(int) => Literal::Int(int),
+            LiteralRef::UInt(int) => Literal::UInt(int),
+            LiteralRef::Long(int) => Literal::Long(int),
+            LiteralRef::ULong(int) => Literal::ULong(int),
             LiteralRef::Double(db) => Literal::Double(db),
-            LiteralRef::String(str_ref) => Literal::String(str_ref.to_string()),
+            LiteralRef::String(str_ref) => Literal::String(str_ref.into_owned()),
         }
     }
 }
@@ -41,7 +40,14 @@ pub enum Literal {
     Null,
     Boolean(bool),
     Char(char),
+    /// integer literal without a type suffix
     Int(u64),
+    /// integer literal with a 'u' suffix
+    UInt(u64),
+    /// integer literal with an 'l' suffix
+    Long(u64),
+    /// integer literal with a 'ul' suffix
+    ULong(u64),
     Double(f64),
     String(String),
 }
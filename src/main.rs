@@ -8,12 +8,22 @@ mod analysis;
 mod symtab;
 
 use std::error::Error;
-use std::process::{ExitCode, ExitStatus};
+use std::process::ExitCode;
 use clap::Parser as ClapParser;
+use crate::analysis::{analyze_ast, check_compositions, check_mutability, resolve_names};
 use crate::args::ProgramArgs;
+use crate::error::source::SourceError;
 use crate::frontend::input::SourceInput;
 use crate::frontend::parse_input_source;
 
+/// print a report for each of the errors
+fn report_errors(source_input: &SourceInput, errors: &[SourceError]) {
+    for error in errors {
+        let report = source_input.create_error_report(error);
+        eprintln!("{}", report);
+    }
+}
+
 fn main() -> Result<ExitCode, Box<dyn Error>> {
 
     let args = ProgramArgs::parse();
@@ -24,26 +34,32 @@ fn main() -> Result<ExitCode, Box<dyn Error>> {
     }
 
     let source_input  = SourceInput::open(args.input_files.first().unwrap())?;
-    if source_input.contains_non_ascii() {
-        eprintln!("Error: input cannot contain non-ascii characters");
-        return Ok(ExitCode::FAILURE);
-    }
 
     let ast = parse_input_source(&source_input);
 
     let Ok(ast) = ast else {
-        let errors = ast.unwrap_err();
         eprintln!("Parsing error occurred");
-        for error in errors {
-            let report = source_input.create_error_report(error);
-            eprintln!("{}", report);
-        }
-
+        report_errors(&source_input, &ast.unwrap_err());
         return Ok(ExitCode::FAILURE)
     };
 
     println!("--AST--");
     println!("{:#?}", ast);
 
+    // each analysis runs only if the ones before it found no errors
+    let analyzed = resolve_names(ast.clone(), args.shadowing)
+        .and_then(|(resolution, warnings)| {
+            report_errors(&source_input, &warnings);
+            check_compositions(ast.clone())?;
+            check_mutability(ast.clone())?;
+            analyze_ast(ast, &resolution)
+        });
+
+    if let Err(errors) = analyzed {
+        eprintln!("Analysis error occurred");
+        report_errors(&source_input, &errors);
+        return Ok(ExitCode::FAILURE)
+    }
+
     Ok(ExitCode::SUCCESS)
 }
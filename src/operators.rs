@@ -22,7 +22,14 @@ pub enum BinaryOp {
     Minus,
     Times,
     Divides,
+    Modulo,
     Exp,
+    // bitwise operators
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
     // Comparisons
     Gt,
     Lt,
@@ -33,6 +40,8 @@ pub enum BinaryOp {
     // boolean operators
     And,
     Or,
+    // optionals
+    NullCoalesce,
     // access
     Access,
     ChainedAccess,
@@ -47,7 +56,17 @@ impl<'input> TryFrom<Token<'input>> for BinaryOp {
             TokenKind::Minus => Ok(BinaryOp::Minus),
             TokenKind::Times => Ok(BinaryOp::Times),
             TokenKind::Divides => Ok(BinaryOp::Divides),
+            TokenKind::Modulo => Ok(BinaryOp::Modulo),
+            TokenKind::Exp => Ok(BinaryOp::Exp),
+            // '&' between two operands is a bitwise and rather than a reference
+            TokenKind::Ref => Ok(BinaryOp::BitAnd),
+            TokenKind::BitOr => Ok(BinaryOp::BitOr),
+            TokenKind::BitXor => Ok(BinaryOp::BitXor),
+            TokenKind::ShiftLeft => Ok(BinaryOp::ShiftLeft),
+            TokenKind::ShiftRight => Ok(BinaryOp::ShiftRight),
             TokenKind::Access => Ok(BinaryOp::Access),
+            TokenKind::ChainedAccess => Ok(BinaryOp::ChainedAccess),
+            TokenKind::NullCoalesce => Ok(BinaryOp::NullCoalesce),
             TokenKind::Eq => Ok(BinaryOp::Eq),
             TokenKind::Neq => Ok(BinaryOp::Neq),
             TokenKind::Gt => Ok(BinaryOp::Gt),
@@ -62,23 +81,77 @@ impl<'input> TryFrom<Token<'input>> for BinaryOp {
 }
 
 impl BinaryOp {
+    /// follows C, with exponentiation binding tighter than multiplication
     pub fn precedence(&self) -> u8 {
         match self {
-            BinaryOp::Plus |
-            BinaryOp::Minus => 10,
+            BinaryOp::Exp => 14,
             BinaryOp::Times |
-            BinaryOp::Divides => 11,
-            BinaryOp::Exp => 12,
+            BinaryOp::Divides |
+            BinaryOp::Modulo => 13,
+            BinaryOp::Plus |
+            BinaryOp::Minus => 12,
+            BinaryOp::ShiftLeft |
+            BinaryOp::ShiftRight => 11,
             BinaryOp::Gt |
             BinaryOp::Lt |
             BinaryOp::Gte |
-            BinaryOp::Lte => 9,
+            BinaryOp::Lte => 10,
             BinaryOp::Eq |
-            BinaryOp::Neq => 8,
-            BinaryOp::And => 7,
-            BinaryOp::Or => 6,
+            BinaryOp::Neq => 9,
+            BinaryOp::BitAnd => 8,
+            BinaryOp::BitXor => 7,
+            BinaryOp::BitOr => 6,
+            BinaryOp::And => 5,
+            BinaryOp::Or => 4,
+            BinaryOp::NullCoalesce => 3,
             BinaryOp::Access => 20,
             BinaryOp::ChainedAccess => 20
         }
     }
+
+    /// if a chain of this operator groups from the right, so `a ** b ** c` is `a ** (b ** c)`
+    pub fn is_right_associative(&self) -> bool {
+        matches!(self, BinaryOp::Exp)
+    }
+
+    /// the operator as it is written in source
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::Plus => "+",
+            BinaryOp::Minus => "-",
+            BinaryOp::Times => "*",
+            BinaryOp::Divides => "/",
+            BinaryOp::Modulo => "%",
+            BinaryOp::Exp => "**",
+            BinaryOp::BitAnd => "&",
+            BinaryOp::BitOr => "|",
+            BinaryOp::BitXor => "^",
+            BinaryOp::ShiftLeft => "<<",
+            BinaryOp::ShiftRight => ">>",
+            BinaryOp::Gt => ">",
+            BinaryOp::Lt => "<",
+            BinaryOp::Gte => ">=",
+            BinaryOp::Lte => "<=",
+            BinaryOp::Eq => "==",
+            BinaryOp::Neq => "!=",
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+            BinaryOp::NullCoalesce => "??",
+            BinaryOp::Access => ".",
+            BinaryOp::ChainedAccess => "?.",
+        }
+    }
+}
+
+impl UnaryOp {
+    /// the operator as it is written in source
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            UnaryOp::BitNeg => "~",
+            UnaryOp::Not => "!",
+            UnaryOp::Neg => "-",
+            UnaryOp::Deref => "*",
+            UnaryOp::Ref => "&",
+        }
+    }
 }
@@ -1,15 +1,36 @@
 use std::collections::HashMap;
 use crate::frontend::location::SourceRange;
-use crate::types::Type;
+use crate::types::{EnumType, ObjectType, Type, VariableDeclarationMode};
 
-#[derive(Clone)]
+/// identifies a symbol. Every symbol added to a symbol table gets a different id, even when it
+/// has the same name as another symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SymbolId(usize);
+
+#[derive(Debug, Clone)]
 pub struct Symbol {
+    /// the id of this symbol, given to it when it is added to a symbol table
+    pub(crate) id: SymbolId,
     /// name associated with this symbol
     pub(crate) name: String,
     /// this symbol's type
     pub(crate) tp: Type,
     /// where this symbol is located in source
     pub(crate) loc: SourceRange,
+    /// how this symbol was declared, which determines if it can be reassigned
+    pub(crate) mode: VariableDeclarationMode,
+}
+
+impl Symbol {
+    pub fn new<StrT: Into<String>>(name: StrT, tp: Type, loc: SourceRange, mode: VariableDeclarationMode) -> Self {
+        Self {
+            id: SymbolId(0),
+            name: name.into(),
+            tp,
+            loc,
+            mode,
+        }
+    }
 }
 
 type ScopeFrame = HashMap<String, Symbol>;
@@ -18,13 +39,16 @@ type ScopeFrame = HashMap<String, Symbol>;
 pub struct SymbolTable {
     /// stack of symbol frames. Bottom most is global scope, and then they lower etc.
     frames: Vec<ScopeFrame>,
+    /// the id given to the next symbol that is added
+    next_id: usize,
 }
 
 impl SymbolTable {
     pub fn new() -> Self {
         let global_frame = ScopeFrame::new();
         Self {
-            frames: Vec::from([global_frame])
+            frames: Vec::from([global_frame]),
+            next_id: 0,
         }
     }
 
@@ -51,6 +75,23 @@ impl SymbolTable {
         None
     }
 
+    /// every symbol that can be named from the current scope, innermost scope first. Symbols
+    /// hidden by one with the same name in an inner scope are left out
+    pub fn visible_symbols(&self) -> impl Iterator<Item = &Symbol> {
+        self.frames.iter()
+            .rev()
+            .enumerate()
+            .flat_map(|(depth, frame)| frame.values().map(move |symbol| (depth, symbol)))
+            .filter(|(depth, symbol)| self.frames.iter().rev().take(*depth).all(|inner| !inner.contains_key(&symbol.name)))
+            .map(|(_, symbol)| symbol)
+    }
+
+    /// checks if the given symbol is defined in the global scope
+    pub fn symbol_defined_globally<StrT: AsRef<str>>(&self, name: StrT) -> Option<&Symbol> {
+        self.frames.first()
+            .and_then(|frame| frame.get(name.as_ref()))
+    }
+
     /// checks if the given symbol is defined in the current scope
     pub fn symbol_defined_in_current_scope<StrT: AsRef<str>>(&self, name: StrT) -> Option<&Symbol> {
         self.frames.last()
@@ -60,13 +101,34 @@ impl SymbolTable {
     /// retrieves the type of the symbol if it exists, or unknown if it is not defined
     pub fn symbol_type_or_unknown<StrT: AsRef<str>>(&self, name: StrT) -> Type {
         self.symbol_defined(name.as_ref())
-            .map(|symbol| symbol.tp)
+            .map(|symbol| symbol.tp.clone())
             .unwrap_or(Type::Unknown)
     }
 
-    /// add a new symbol to the current scope
-    pub fn add_symbol(&mut self, symbol: Symbol) {
+    /// find a declared object type by name
+    pub fn lookup_object<StrT: AsRef<str>>(&self, name: StrT) -> Option<&ObjectType> {
+        match &self.symbol_defined(name)?.tp {
+            Type::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    /// find a declared enum type by name
+    pub fn lookup_enum<StrT: AsRef<str>>(&self, name: StrT) -> Option<&EnumType> {
+        match &self.symbol_defined(name)?.tp {
+            Type::Enum(enum_type) => Some(enum_type),
+            _ => None,
+        }
+    }
+
+    /// add a new symbol to the current scope, and give it a new id. Returns that id
+    pub fn add_symbol(&mut self, mut symbol: Symbol) -> SymbolId {
+        let id = SymbolId(self.next_id);
+        self.next_id += 1;
+        symbol.id = id;
+
         let current_frame = self.frames.last_mut().unwrap();
         current_frame.insert(symbol.name.clone(), symbol);
+        id
     }
 }
@@ -1,13 +1,15 @@
+mod relations;
+
 use std::collections::HashMap;
 use crate::literal::Literal;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FunParam {
     pub(crate) tp: Box<Type>,
     pub(crate) name: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FunType {
     /// the return type of this function
     pub(crate) ret: Box<Type>,
@@ -15,19 +17,53 @@ pub struct FunType {
     pub(crate) args: Vec<FunParam>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComposedObject {
+    /// name of the composed object
+    pub(crate) name: String,
+    /// if the composed object was given an alias. An aliased object's fields are reached through
+    /// the alias (`student.person.name`), otherwise they are flattened (`student.name`)
+    pub(crate) aliased: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ObjectType {
     /// name of the object
-    /// TODO this can probably be omitted
     pub(crate) name: String,
     /// the properties on this object
     pub(crate) props: HashMap<String, Box<Type>>,
-    /// the composed types with this object
-    /// TODO might need some reworking
-    pub(crate) comps: HashMap<String, String>,
+    /// the objects composed into this one, keyed by alias (or by the type name if there is no
+    /// alias)
+    pub(crate) comps: HashMap<String, ComposedObject>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariantType {
+    /// the variant carries no data
+    Unit,
+    /// the variant carries positional values
+    Tuple(Vec<Type>),
+    /// the variant carries named fields
+    Struct(HashMap<String, Box<Type>>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumVariant {
+    /// name of the variant
+    pub(crate) name: String,
+    /// the data carried by the variant
+    pub(crate) payload: VariantType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumType {
+    /// name of the enum
+    pub(crate) name: String,
+    /// the variants of this enum, in declaration order
+    pub(crate) variants: Vec<EnumVariant>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Type {
     /// type is unknown at compile time
     Unknown,
@@ -57,6 +93,8 @@ pub enum Type {
     Function(FunType),
     /// Object type
     Object(ObjectType),
+    /// Enum type, a tagged union of its variants
+    Enum(EnumType),
     /// A sized array type
     Array(Box<Type>, usize),
     /// A variable sized, non-owning view of contiguous memory
@@ -65,6 +103,129 @@ pub enum Type {
     UserDefined(String),
 }
 
+impl ObjectType {
+    /// find the type of a field on this object. An aliased composed object is a field itself, and
+    /// the fields of objects composed without an alias can be accessed as if they were declared on
+    /// this object, so those are searched too. Composed objects are found by name through the
+    /// given lookup
+    pub fn field_type<'a, LookupT>(&'a self, field: &str, lookup: &LookupT) -> Option<Type>
+    where
+        LookupT: Fn(&str) -> Option<&'a ObjectType>
+    {
+        self.field_type_visiting(field, lookup, &mut Vec::new())
+    }
+
+    fn field_type_visiting<'a, LookupT>(&'a self, field: &str, lookup: &LookupT, visited: &mut Vec<&'a str>) -> Option<Type>
+    where
+        LookupT: Fn(&str) -> Option<&'a ObjectType>
+    {
+        if let Some(tp) = self.props.get(field) {
+            return Some(tp.as_ref().clone());
+        }
+
+        if let Some(composed) = self.comps.get(field).filter(|composed| composed.aliased) {
+            return Some(Type::UserDefined(composed.name.clone()));
+        }
+
+        // composition cycles are reported elsewhere, we only need to make sure not to loop forever
+        if visited.contains(&self.name.as_str()) {
+            return None;
+        }
+        visited.push(&self.name);
+
+        self.flattened_comps(lookup)
+            .into_iter()
+            .find_map(|composed| composed.field_type_visiting(field, lookup, visited))
+    }
+
+    /// the names of every field that can be accessed directly on this object, along with the name
+    /// of the object each one is declared on
+    pub fn field_names<'a, LookupT>(&'a self, lookup: &LookupT) -> Vec<(&'a str, &'a str)>
+    where
+        LookupT: Fn(&str) -> Option<&'a ObjectType>
+    {
+        let mut names = Vec::new();
+        self.field_names_visiting(lookup, &mut names, &mut Vec::new());
+        names
+    }
+
+    fn field_names_visiting<'a, LookupT>(&'a self, lookup: &LookupT, names: &mut Vec<(&'a str, &'a str)>, visited: &mut Vec<&'a str>)
+    where
+        LookupT: Fn(&str) -> Option<&'a ObjectType>
+    {
+        if visited.contains(&self.name.as_str()) {
+            return;
+        }
+        visited.push(&self.name);
+
+        names.extend(self.props.keys().map(|name| (name.as_str(), self.name.as_str())));
+        names.extend(self.comps.iter()
+            .filter(|(_, composed)| composed.aliased)
+            .map(|(alias, _)| (alias.as_str(), self.name.as_str())));
+
+        for composed in self.flattened_comps(lookup) {
+            composed.field_names_visiting(lookup, names, visited);
+        }
+    }
+
+    /// the composed objects whose fields are flattened into this one
+    fn flattened_comps<'a, LookupT>(&'a self, lookup: &LookupT) -> Vec<&'a ObjectType>
+    where
+        LookupT: Fn(&str) -> Option<&'a ObjectType>
+    {
+        self.comps.values()
+            .filter(|composed| !composed.aliased)
+            .filter_map(|composed| lookup(&composed.name))
+            .collect()
+    }
+
+    /// if this object composes the named object, either directly or through one of its composed
+    /// objects
+    pub fn composes<'a, LookupT>(&'a self, base: &str, lookup: &LookupT) -> bool
+    where
+        LookupT: Fn(&str) -> Option<&'a ObjectType>
+    {
+        self.composes_visiting(base, lookup, &mut Vec::new())
+    }
+
+    fn composes_visiting<'a, LookupT>(&'a self, base: &str, lookup: &LookupT, visited: &mut Vec<&'a str>) -> bool
+    where
+        LookupT: Fn(&str) -> Option<&'a ObjectType>
+    {
+        if visited.contains(&self.name.as_str()) {
+            return false;
+        }
+        visited.push(&self.name);
+
+        self.comps.values().any(|composed| composed.name == base)
+            || self.comps.values()
+                .filter_map(|composed| lookup(&composed.name))
+                .any(|composed| composed.composes_visiting(base, lookup, visited))
+    }
+}
+
+impl EnumType {
+    /// find a variant of this enum by name
+    pub fn variant(&self, name: &str) -> Option<&EnumVariant> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+}
+
+impl Type {
+    /// if this is one of the integer types
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Type::Int | Type::UInt | Type::Long | Type::ULong)
+    }
+
+    /// an optional of the given type, unless it already is one
+    pub fn optional_of(tp: Type) -> Type {
+        match tp {
+            Type::Optional(_) => tp,
+            tp => Type::Optional(tp.into()),
+        }
+    }
+}
+
 impl From<&str> for Type {
     fn from(value: &str) -> Self {
         match value {
@@ -82,9 +243,13 @@ impl From<&str> for Type {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VariableDeclarationMode {
+    /// declared with `const`. Can never be reassigned
     Const,
+    /// declared with a plain `let`. Cannot be reassigned after initialization
+    Immutable,
+    /// declared with `let mut`. Can be freely reassigned
     Mutable,
 }
 
@@ -96,7 +261,10 @@ impl From<Literal> for Type {
             Literal::Null => Type::Optional(Type::Unknown.into()),
             Literal::Boolean(_) => Type::Boolean,
             Literal::Char(_) => Type::Char,
-            Literal::Int(_) => Type::UInt,
+            Literal::Int(_) => Type::Int,
+            Literal::UInt(_) => Type::UInt,
+            Literal::Long(_) => Type::Long,
+            Literal::ULong(_) => Type::ULong,
             Literal::Double(_) => Type::Double,
             Literal::String(_) => Type::String,
         }
@@ -0,0 +1,181 @@
+use std::fmt::{Display, Formatter};
+use crate::types::{ObjectType, Type};
+
+#[cfg(test)]
+mod test;
+
+/// How types relate to each other: when two types are the same, when a value of one type can be
+/// stored where another is expected, which type two types join to when either can turn up, like
+/// in the branches of an `if`, and which types a value can be cast to. Objects and enums are
+/// nominal, so naming one is the same as the type it names. An unknown type only comes from a
+/// mistake that is reported elsewhere, so it is taken to be the same as any type rather than
+/// causing more errors
+impl Type {
+    /// if this is the same type as the other type
+    pub fn is_same_as(&self, other: &Type) -> bool {
+        match (self, other) {
+            (Type::Unknown, _) | (_, Type::Unknown) => true,
+            (Type::Reference(lhs), Type::Reference(rhs))
+            | (Type::Optional(lhs), Type::Optional(rhs))
+            | (Type::View(lhs), Type::View(rhs)) => lhs.is_same_as(rhs),
+            (Type::Array(lhs, lhs_len), Type::Array(rhs, rhs_len)) => lhs_len == rhs_len && lhs.is_same_as(rhs),
+            (Type::Function(lhs), Type::Function(rhs)) => lhs.ret.is_same_as(&rhs.ret)
+                && lhs.args.len() == rhs.args.len()
+                && lhs.args.iter().zip(&rhs.args).all(|(lhs, rhs)| lhs.tp.is_same_as(&rhs.tp)),
+            (Type::UserDefined(name), tp) | (tp, Type::UserDefined(name)) => tp.type_name() == Some(name),
+            (lhs, rhs) => lhs == rhs,
+        }
+    }
+
+    /// if a value of this type can be stored where a value of the target type is expected. On top
+    /// of values of the same type, a sized array can be stored as a view of its elements, and any
+    /// value, `null` included, can be stored as an optional of its type
+    pub fn is_assignable_to(&self, target: &Type) -> bool {
+        if self.is_same_as(target) {
+            return true;
+        }
+
+        match (self, target) {
+            (Type::Array(element, _), Type::View(target_element)) => element.is_same_as(target_element),
+            (value, Type::Optional(inner)) => value.is_assignable_to(inner),
+            _ => false,
+        }
+    }
+
+    /// if a value of this type can be converted into the target type with an explicit `as` cast.
+    /// On top of the values that can be stored as the target type, the numeric types convert
+    /// between each other whether that widens or narrows, and a reference to an object converts
+    /// to a reference to any object it composes. Objects are found by name through the given lookup
+    pub fn casts_to<'a, LookupT>(&self, target: &Type, lookup: &LookupT) -> bool
+    where
+        LookupT: Fn(&str) -> Option<&'a ObjectType>
+    {
+        if self.is_assignable_to(target) {
+            return true;
+        }
+
+        match (self, target) {
+            (from, to) if NUMERIC_CASTS.contains(from) && NUMERIC_CASTS.contains(to) => true,
+            (Type::Reference(derived), Type::Reference(base)) => {
+                let (Some(derived), Some(base)) = (derived.object_name(), base.object_name()) else {
+                    return false;
+                };
+
+                lookup(derived).is_some_and(|derived| derived.composes(base, lookup))
+            }
+            _ => false,
+        }
+    }
+
+    /// the least upper bound of this type and the other type: the most specific type that values
+    /// of either type can be stored as, if there is one. `null` joins with any type as an optional
+    /// of that type, and arrays of the same elements but different sizes join as a view
+    pub fn lub(&self, other: &Type) -> Option<Type> {
+        if self.is_same_as(other) {
+            return Some(fill_unknown(self, other));
+        }
+
+        match (self, other) {
+            (Type::Optional(null), tp) | (tp, Type::Optional(null)) if matches!(null.as_ref(), Type::Unknown) => Some(Type::optional_of(tp.clone())),
+            (lhs, rhs) if lhs.is_assignable_to(rhs) => Some(rhs.clone()),
+            (lhs, rhs) if rhs.is_assignable_to(lhs) => Some(lhs.clone()),
+            (Type::Array(lhs, _), Type::Array(rhs, _)) if lhs.is_same_as(rhs) => Some(Type::View(fill_unknown(lhs, rhs).into())),
+            (Type::Optional(lhs), Type::Optional(rhs)) => Some(Type::optional_of(lhs.lub(rhs)?)),
+            (Type::Optional(inner), tp) | (tp, Type::Optional(inner)) => Some(Type::optional_of(inner.lub(tp)?)),
+            _ => None,
+        }
+    }
+
+    /// the object type this type names, if it names one. Declared objects are found by name
+    /// through the given lookup
+    pub fn resolve<'a, LookupT>(&'a self, lookup: &LookupT) -> Option<&'a ObjectType>
+    where
+        LookupT: Fn(&str) -> Option<&'a ObjectType>
+    {
+        match self {
+            Type::Object(object) => Some(object),
+            Type::UserDefined(name) => lookup(name),
+            _ => None,
+        }
+    }
+
+    /// the name of the object type this type refers to, if it refers to one
+    fn object_name(&self) -> Option<&str> {
+        match self {
+            Type::Object(object) => Some(&object.name),
+            Type::UserDefined(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// the name of the object or enum this type is, or names
+    fn type_name(&self) -> Option<&String> {
+        match self {
+            Type::Object(object) => Some(&object.name),
+            Type::Enum(enum_type) => Some(&enum_type.name),
+            Type::UserDefined(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+/// the scalar types that can be freely converted between each other with `as`
+const NUMERIC_CASTS: [Type; 6] = [Type::Char, Type::Int, Type::UInt, Type::Long, Type::ULong, Type::Double];
+
+/// combine two types that are the same, taking whichever side is known for parts that are
+/// unknown on the other side
+fn fill_unknown(lhs: &Type, rhs: &Type) -> Type {
+    match (lhs, rhs) {
+        (Type::Unknown, tp) | (tp, Type::Unknown) => tp.clone(),
+        (Type::Reference(lhs), Type::Reference(rhs)) => Type::Reference(fill_unknown(lhs, rhs).into()),
+        (Type::Optional(lhs), Type::Optional(rhs)) => Type::Optional(fill_unknown(lhs, rhs).into()),
+        (Type::View(lhs), Type::View(rhs)) => Type::View(fill_unknown(lhs, rhs).into()),
+        (Type::Array(lhs, len), Type::Array(rhs, _)) => Type::Array(fill_unknown(lhs, rhs).into(), *len),
+        (lhs, _) => lhs.clone(),
+    }
+}
+
+/// Types are written the way they are in source, e.g. `&([]uint)?`
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Unknown => write!(f, "{{unknown}}"),
+            Type::Unit => write!(f, "()"),
+            Type::Boolean => write!(f, "bool"),
+            Type::Char => write!(f, "char"),
+            Type::Int => write!(f, "int"),
+            Type::UInt => write!(f, "uint"),
+            Type::Long => write!(f, "long"),
+            Type::ULong => write!(f, "ulong"),
+            Type::Double => write!(f, "double"),
+            Type::String => write!(f, "str"),
+            Type::Reference(inner) => write!(f, "&{}", inner),
+            // `null` is an optional before anything tells what it holds
+            Type::Optional(inner) if matches!(inner.as_ref(), Type::Unknown) => write!(f, "null"),
+            // `?` binds tighter than the prefix type operators, and can't be written twice in a row
+            Type::Optional(inner) => match inner.as_ref() {
+                Type::Reference(_) | Type::Optional(_) | Type::Function(_) | Type::Array(..) | Type::View(_) => write!(f, "({})?", inner),
+                _ => write!(f, "{}?", inner),
+            },
+            Type::Function(fun) => {
+                let params = fun.args.iter()
+                    .map(|param| match param.name.as_str() {
+                        "" => param.tp.to_string(),
+                        name => format!("{}: {}", name, param.tp),
+                    })
+                    .collect::<Vec<_>>();
+
+                write!(f, "fun({})", params.join(", "))?;
+                match fun.ret.as_ref() {
+                    Type::Unit => Ok(()),
+                    ret => write!(f, ": {}", ret),
+                }
+            }
+            Type::Object(object) => write!(f, "{}", object.name),
+            Type::Enum(enum_type) => write!(f, "{}", enum_type.name),
+            Type::Array(element, len) => write!(f, "[{}]{}", len, element),
+            Type::View(element) => write!(f, "[]{}", element),
+            Type::UserDefined(name) => write!(f, "{}", name),
+        }
+    }
+}
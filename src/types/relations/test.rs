@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use crate::types::{ComposedObject, FunParam, FunType, ObjectType, Type};
+
+fn optional(tp: Type) -> Type {
+    Type::Optional(tp.into())
+}
+
+fn reference(tp: Type) -> Type {
+    Type::Reference(tp.into())
+}
+
+fn object(name: &str, composes: &[&str]) -> ObjectType {
+    ObjectType {
+        name: name.to_string(),
+        props: HashMap::new(),
+        comps: composes.iter()
+            .map(|composed| (composed.to_string(), ComposedObject { name: composed.to_string(), aliased: false }))
+            .collect(),
+    }
+}
+
+fn function(args: &[(&str, Type)], ret: Type) -> Type {
+    Type::Function(FunType {
+        ret: ret.into(),
+        args: args.iter()
+            .map(|(name, tp)| FunParam { tp: tp.clone().into(), name: name.to_string() })
+            .collect(),
+    })
+}
+
+#[test]
+fn same_types() {
+    assert!(Type::Int.is_same_as(&Type::Int));
+    assert!(!Type::Int.is_same_as(&Type::Long));
+    assert!(optional(Type::Unknown).is_same_as(&optional(Type::Char)));
+    assert!(Type::Array(Type::Int.into(), 3).is_same_as(&Type::Array(Type::Int.into(), 3)));
+    assert!(!Type::Array(Type::Int.into(), 3).is_same_as(&Type::Array(Type::Int.into(), 4)));
+    assert!(Type::Object(object("Person", &[])).is_same_as(&Type::UserDefined("Person".to_string())));
+    assert!(!Type::Object(object("Person", &[])).is_same_as(&Type::UserDefined("Pet".to_string())));
+
+    // parameter names don't matter, only their types
+    assert!(function(&[("a", Type::Int)], Type::Boolean).is_same_as(&function(&[("", Type::Int)], Type::Boolean)));
+    assert!(!function(&[("a", Type::Int)], Type::Boolean).is_same_as(&function(&[("a", Type::Int), ("b", Type::Int)], Type::Boolean)));
+}
+
+#[test]
+fn assignable_types() {
+    assert!(Type::Int.is_assignable_to(&optional(Type::Int)));
+    assert!(optional(Type::Unknown).is_assignable_to(&optional(Type::Long)));
+    assert!(Type::Array(Type::UInt.into(), 3).is_assignable_to(&Type::View(Type::UInt.into())));
+    assert!(!Type::View(Type::UInt.into()).is_assignable_to(&Type::Array(Type::UInt.into(), 3)));
+    assert!(!optional(Type::Int).is_assignable_to(&Type::Int));
+    assert!(!Type::Int.is_assignable_to(&Type::Long));
+}
+
+#[test]
+fn optional_of() {
+    assert_eq!(Type::optional_of(Type::Int), optional(Type::Int));
+    assert_eq!(Type::optional_of(optional(Type::Int)), optional(Type::Int));
+    assert_eq!(Type::optional_of(Type::Unknown), optional(Type::Unknown));
+}
+
+#[test]
+fn least_upper_bound() {
+    assert_eq!(Type::Int.lub(&Type::Int), Some(Type::Int));
+    assert_eq!(Type::Unknown.lub(&Type::Char), Some(Type::Char));
+    assert_eq!(optional(Type::Unknown).lub(&Type::Int), Some(optional(Type::Int)));
+    assert_eq!(Type::Int.lub(&optional(Type::Unknown)), Some(optional(Type::Int)));
+    assert_eq!(optional(Type::Int).lub(&Type::Int), Some(optional(Type::Int)));
+    assert_eq!(Type::Array(Type::Int.into(), 2).lub(&Type::Array(Type::Int.into(), 3)), Some(Type::View(Type::Int.into())));
+    assert_eq!(Type::Int.lub(&Type::Boolean), None);
+    assert_eq!(optional(Type::Int).lub(&optional(Type::Long)), None);
+}
+
+#[test]
+fn casts() {
+    let objects = [object("Person", &[]), object("Student", &["Person"]), object("Pet", &[])];
+    let lookup = |name: &str| objects.iter().find(|object| object.name == name);
+
+    assert!(Type::Char.casts_to(&Type::Long, &lookup));
+    assert!(Type::Double.casts_to(&Type::UInt, &lookup));
+    assert!(Type::Int.casts_to(&optional(Type::Int), &lookup));
+    assert!(reference(Type::UserDefined("Student".to_string())).casts_to(&reference(Type::UserDefined("Person".to_string())), &lookup));
+
+    assert!(!optional(Type::Int).casts_to(&Type::Int, &lookup));
+    assert!(!Type::Boolean.casts_to(&Type::Int, &lookup));
+    assert!(!reference(Type::UserDefined("Person".to_string())).casts_to(&reference(Type::UserDefined("Student".to_string())), &lookup));
+    assert!(!reference(Type::UserDefined("Student".to_string())).casts_to(&reference(Type::UserDefined("Pet".to_string())), &lookup));
+}
+
+#[test]
+fn resolve_user_defined() {
+    let objects = [object("Person", &[])];
+    let lookup = |name: &str| objects.iter().find(|object| object.name == name);
+
+    let person = Type::UserDefined("Person".to_string());
+    let pet = Type::UserDefined("Pet".to_string());
+
+    assert_eq!(person.resolve(&lookup).map(|object| object.name.as_str()), Some("Person"));
+    assert!(pet.resolve(&lookup).is_none());
+    assert!(Type::Int.resolve(&lookup).is_none());
+}
+
+#[test]
+fn display_types() {
+    assert_eq!(reference(optional(Type::View(Type::UInt.into()))).to_string(), "&([]uint)?");
+    assert_eq!(optional(reference(Type::Int)).to_string(), "(&int)?");
+    assert_eq!(optional(Type::String).to_string(), "str?");
+    assert_eq!(optional(Type::Unknown).to_string(), "null");
+    assert_eq!(Type::Array(Type::Array(Type::Long.into(), 2).into(), 3).to_string(), "[3][2]long");
+    assert_eq!(function(&[("", Type::Int), ("b", Type::UInt)], Type::Boolean).to_string(), "fun(int, b: uint): bool");
+    assert_eq!(function(&[], Type::Unit).to_string(), "fun()");
+    assert_eq!(Type::Unit.to_string(), "()");
+}